@@ -89,7 +89,7 @@ impl CookieExtractor {
     }
 
     /// Extract cookies from a specific profile
-    fn extract_profile_cookies(
+    pub fn extract_profile_cookies(
         browser: &DetectedBrowser,
         profile: &BrowserProfile,
         domain: &str,
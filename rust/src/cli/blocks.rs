@@ -0,0 +1,169 @@
+//! Blocks command implementation
+//!
+//! Rebuilds Claude's 5-hour billing blocks from local session logs and shows
+//! tokens and cost per block, plus burn rate and projected cost for the
+//! active block. Works offline without OAuth or browser cookies.
+
+use chrono::{Duration, Local, Utc};
+use clap::Args;
+
+use super::usage::OutputFormat;
+use crate::core::{SessionBlock, SessionBlocks};
+
+/// Arguments for the blocks command
+#[derive(Args, Debug)]
+pub struct BlocksArgs {
+    /// Number of days of history to show
+    #[arg(short, long, default_value = "3")]
+    pub days: u32,
+
+    /// Only show the active block
+    #[arg(long)]
+    pub active: bool,
+
+    /// Output format: text or json
+    #[arg(short, long, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Shorthand for --format json
+    #[arg(long)]
+    pub json: bool,
+
+    /// Pretty-print JSON output
+    #[arg(long)]
+    pub pretty: bool,
+
+    /// Disable ANSI colors in text output
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+}
+
+/// Run the blocks command
+pub async fn run(args: BlocksArgs) -> anyhow::Result<()> {
+    let format = if args.json { OutputFormat::Json } else { args.format };
+    let use_color = !args.no_color && is_terminal();
+    let now = Utc::now();
+
+    tracing::debug!("Running blocks command: days={}, active={}", args.days, args.active);
+
+    let days = args.days;
+    let blocks = tokio::task::spawn_blocking(move || SessionBlocks::claude_blocks(days, now)).await?;
+    let limit = SessionBlocks::max_completed_tokens(&blocks);
+
+    let cutoff = now - Duration::days(args.days as i64);
+    let shown: Vec<&SessionBlock> = blocks
+        .iter()
+        .filter(|b| if args.active { b.is_active } else { b.end > cutoff })
+        .collect();
+
+    match format {
+        OutputFormat::Text => print_text_output(&shown, limit, use_color),
+        OutputFormat::Json => print_json_output(&shown, limit, args.pretty)?,
+    }
+
+    Ok(())
+}
+
+/// Print text output
+fn print_text_output(blocks: &[&SessionBlock], limit: Option<i64>, use_color: bool) {
+    if blocks.is_empty() {
+        println!("No Claude usage found in local logs");
+        return;
+    }
+
+    let now = Utc::now();
+    for block in blocks {
+        let start = block.start.with_timezone(&Local);
+        let end = block.end.with_timezone(&Local);
+        let status = if block.is_active { "ACTIVE" } else { "" };
+
+        if use_color && block.is_active {
+            println!(
+                "\x1b[1m{} - {}\x1b[0m  \x1b[32m{}\x1b[0m",
+                start.format("%Y-%m-%d %H:%M"),
+                end.format("%H:%M"),
+                status
+            );
+        } else {
+            println!("{} - {}  {}", start.format("%Y-%m-%d %H:%M"), end.format("%H:%M"), status);
+        }
+
+        println!(
+            "  Tokens:   {} ({} messages)",
+            format_number(block.total_tokens()),
+            block.message_count
+        );
+        println!("  Cost:     ${:.2}", block.cost_usd);
+        println!("  Models:   {}", block.models.join(", "));
+
+        if block.is_active {
+            let remaining = block.remaining(now);
+            println!(
+                "  Remaining: {}h {:02}m",
+                remaining.num_hours(),
+                remaining.num_minutes() % 60
+            );
+            if let Some(rate) = block.burn_rate_per_hour(now) {
+                println!("  Burn rate: ${:.2}/h", rate);
+            }
+            if let Some(projected) = block.projected_cost_usd(now) {
+                println!("  Projected: ${:.2} by block end", projected);
+            }
+            if let Some(limit) = limit {
+                println!(
+                    "  Tokens vs busiest block: {:.0}%",
+                    block.total_tokens() as f64 / limit as f64 * 100.0
+                );
+            }
+        }
+        println!();
+    }
+}
+
+/// Print JSON output
+fn print_json_output(blocks: &[&SessionBlock], limit: Option<i64>, pretty: bool) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let payload: Vec<serde_json::Value> = blocks
+        .iter()
+        .map(|b| {
+            let mut value = serde_json::to_value(b).unwrap_or_default();
+            value["total_tokens"] = serde_json::json!(b.total_tokens());
+            if b.is_active {
+                value["remaining_minutes"] = serde_json::json!(b.remaining(now).num_minutes());
+                value["burn_rate_per_hour"] = serde_json::json!(b.burn_rate_per_hour(now));
+                value["projected_cost_usd"] = serde_json::json!(b.projected_cost_usd(now));
+                value["token_limit_estimate"] = serde_json::json!(limit);
+            }
+            value
+        })
+        .collect();
+
+    let output = if pretty {
+        serde_json::to_string_pretty(&payload)?
+    } else {
+        serde_json::to_string(&payload)?
+    };
+    println!("{}", output);
+
+    Ok(())
+}
+
+/// Format a number with commas
+fn format_number(n: i64) -> String {
+    let s = n.to_string();
+    let mut result = String::new();
+    let chars: Vec<char> = s.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if i > 0 && (chars.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(*c);
+    }
+    result
+}
+
+/// Check if stdout is a terminal
+fn is_terminal() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdout().is_terminal()
+}
@@ -0,0 +1,569 @@
+//! Check command implementation
+//!
+//! Evaluates usage and status conditions such as `claude.session<80` or
+//! `any.status!=major` and exits non-zero when one fails, so hooks and CI
+//! jobs can refuse to start an agent that would run out of quota mid-run.
+
+use chrono::{DateTime, Utc};
+use clap::Args;
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+
+use super::usage::create_provider;
+use super::wait::parse_wait_duration;
+use crate::core::{FetchContext, ProviderId, SourceMode, UsageWindow, WidgetSnapshotStore};
+use crate::settings::Settings;
+use crate::status::{fetch_provider_status, get_status_page_url, StatusLevel};
+
+/// Arguments for the check command
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// Conditions like `claude.session<80`, `codex.weekly<=95` or
+    /// `any.status!=major` (`any` means every enabled provider)
+    #[arg(required = true, value_name = "CONDITION")]
+    pub conditions: Vec<String>,
+
+    /// Use the data saved by the menu bar app instead of fetching
+    #[arg(long)]
+    pub cached: bool,
+
+    /// With --cached, treat data older than this as unknown (e.g. 15m, 1h)
+    #[arg(long = "max-age", default_value = "30m", value_parser = parse_wait_duration)]
+    pub max_age: Duration,
+
+    /// Let conditions pass when their data can't be fetched
+    #[arg(long = "allow-unknown")]
+    pub allow_unknown: bool,
+
+    /// Data source: auto, web, cli, oauth, local
+    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth", "local"])]
+    pub source: String,
+
+    /// Web fetch timeout in seconds
+    #[arg(long = "web-timeout", default_value = "60")]
+    pub web_timeout: u64,
+
+    /// Print the results as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Only set the exit code
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+/// At least one condition didn't hold
+#[derive(Debug, Error)]
+#[error("{failed} of {total} conditions not met")]
+pub struct CheckFailed {
+    pub failed: usize,
+    pub total: usize,
+}
+
+/// Which providers a condition applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Any,
+    Provider(ProviderId),
+}
+
+impl Target {
+    fn covers(self, id: ProviderId) -> bool {
+        match self {
+            Target::Any => true,
+            Target::Provider(target) => target == id,
+        }
+    }
+
+    fn providers(self, enabled: &[ProviderId]) -> Vec<ProviderId> {
+        match self {
+            Target::Any => enabled.to_vec(),
+            Target::Provider(id) => vec![id],
+        }
+    }
+}
+
+/// The value a condition looks at
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Window(UsageWindow),
+    Status,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    /// Two-character operators first so `<=` isn't read as `<`
+    const ALL: [(&'static str, CompareOp); 6] = [
+        ("<=", CompareOp::Le),
+        (">=", CompareOp::Ge),
+        ("==", CompareOp::Eq),
+        ("!=", CompareOp::Ne),
+        ("<", CompareOp::Lt),
+        (">", CompareOp::Gt),
+    ];
+
+    fn holds(self, actual: f64, expected: f64) -> bool {
+        match self {
+            CompareOp::Lt => actual < expected,
+            CompareOp::Le => actual <= expected,
+            CompareOp::Gt => actual > expected,
+            CompareOp::Ge => actual >= expected,
+            CompareOp::Eq => actual == expected,
+            CompareOp::Ne => actual != expected,
+        }
+    }
+}
+
+/// A parsed `<provider|any>.<field><op><value>` condition
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    raw: String,
+    target: Target,
+    field: Field,
+    op: CompareOp,
+    /// Percentage, or the status severity
+    value: f64,
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |why: &str| format!("Invalid condition '{}': {}", s, why);
+        let raw: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+
+        let (target, rest) = raw
+            .split_once('.')
+            .ok_or_else(|| invalid("expected <provider>.<field><op><value>"))?;
+        let target = match target.to_lowercase().as_str() {
+            "any" | "all" => Target::Any,
+            name => Target::Provider(
+                ProviderId::from_cli_name(name).ok_or_else(|| invalid(&format!("unknown provider '{}'", name)))?,
+            ),
+        };
+
+        let op_start = rest
+            .find(['<', '>', '=', '!'])
+            .ok_or_else(|| invalid("missing operator (<, <=, >, >=, ==, !=)"))?;
+        let (field, rest) = rest.split_at(op_start);
+        let (symbol, op) = CompareOp::ALL
+            .into_iter()
+            .find(|(symbol, _)| rest.starts_with(symbol))
+            .ok_or_else(|| invalid("unknown operator"))?;
+        let value = &rest[symbol.len()..];
+
+        let field = match field.to_lowercase().as_str() {
+            "status" => Field::Status,
+            name => Field::Window(name.parse().map_err(|_| invalid(&format!("unknown field '{}'", name)))?),
+        };
+        let value = match field {
+            Field::Window(_) => value
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| invalid("expected a percentage"))?,
+            Field::Status => severity(StatusLevel::from_indicator(value))
+                .ok_or_else(|| invalid("expected operational, degraded, partial or major"))?,
+        };
+
+        Ok(Self {
+            raw,
+            target,
+            field,
+            op,
+            value,
+        })
+    }
+}
+
+/// Status levels ordered from best to worst; unknown has no place
+fn severity(level: StatusLevel) -> Option<f64> {
+    match level {
+        StatusLevel::Operational => Some(0.0),
+        StatusLevel::Degraded => Some(1.0),
+        StatusLevel::Partial => Some(2.0),
+        StatusLevel::Major => Some(3.0),
+        StatusLevel::Unknown => None,
+    }
+}
+
+/// Data for one provider, live or cached
+#[derive(Debug, Default)]
+struct Observation {
+    /// Used percent per window
+    windows: BTreeMap<&'static str, f64>,
+    status: Option<StatusLevel>,
+    /// Why usage is missing
+    usage_error: Option<String>,
+    /// Why the status is missing
+    status_error: Option<String>,
+}
+
+/// Outcome of a condition for one provider
+#[derive(Debug, Serialize)]
+struct ProviderOutcome {
+    provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual: Option<String>,
+    /// None when the data is unknown
+    ok: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConditionResult {
+    condition: String,
+    ok: bool,
+    providers: Vec<ProviderOutcome>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckReport {
+    ok: bool,
+    source: &'static str,
+    checked_at: DateTime<Utc>,
+    results: Vec<ConditionResult>,
+}
+
+/// Run the check command
+pub async fn run(args: CheckArgs) -> anyhow::Result<()> {
+    let conditions = args
+        .conditions
+        .iter()
+        .map(|c| c.parse::<Condition>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(anyhow::Error::msg)?;
+
+    let enabled = Settings::load().get_enabled_provider_ids();
+    let mut providers: Vec<ProviderId> = Vec::new();
+    for condition in &conditions {
+        for id in condition.target.providers(&enabled) {
+            if !providers.contains(&id) {
+                providers.push(id);
+            }
+        }
+    }
+    let wants_usage = |id: ProviderId| {
+        conditions
+            .iter()
+            .any(|c| matches!(c.field, Field::Window(_)) && c.target.covers(id))
+    };
+    let wants_status = |id: ProviderId| {
+        conditions
+            .iter()
+            .any(|c| c.field == Field::Status && c.target.covers(id))
+    };
+
+    tracing::debug!(
+        "Running check command: conditions={}, providers={}, cached={}",
+        conditions.len(),
+        providers.len(),
+        args.cached
+    );
+
+    let observations: HashMap<ProviderId, Observation> = if args.cached {
+        load_cached(&providers, args.max_age)
+    } else {
+        let fetches = providers.iter().map(|&id| fetch_live(&args, id, wants_usage(id), wants_status(id)));
+        providers.iter().copied().zip(join_all(fetches).await).collect()
+    };
+
+    let results: Vec<ConditionResult> = conditions
+        .iter()
+        .map(|condition| evaluate(condition, &condition.target.providers(&enabled), &observations, args.allow_unknown))
+        .collect();
+
+    let failed = results.iter().filter(|r| !r.ok).count();
+    let report = CheckReport {
+        ok: failed == 0,
+        source: if args.cached { "cached" } else { "live" },
+        checked_at: Utc::now(),
+        results,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !args.quiet {
+        print_report(&report);
+    }
+
+    if failed > 0 {
+        return Err(CheckFailed {
+            failed,
+            total: conditions.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Evaluate a condition against each of its providers. With `any`, providers
+/// that don't report the field at all are skipped rather than unknown.
+fn evaluate(
+    condition: &Condition,
+    targets: &[ProviderId],
+    observations: &HashMap<ProviderId, Observation>,
+    allow_unknown: bool,
+) -> ConditionResult {
+    let mut providers = Vec::new();
+
+    for &id in targets {
+        let observation = observations.get(&id);
+        let actual = observation.and_then(|o| match condition.field {
+            Field::Window(window) => o.windows.get(window.name()).map(|&used| (used, format!("{:.0}%", used))),
+            Field::Status => o
+                .status
+                .and_then(|level| severity(level).map(|s| (s, level.indicator().to_string()))),
+        });
+
+        let outcome = match actual {
+            Some((value, shown)) => {
+                let ok = condition.op.holds(value, condition.value);
+                ProviderOutcome {
+                    provider: id.cli_name().to_string(),
+                    actual: Some(shown),
+                    ok: Some(ok),
+                    reason: None,
+                }
+            }
+            None => {
+                let error = observation.and_then(|o| match condition.field {
+                    Field::Window(_) => o.usage_error.clone(),
+                    Field::Status => o.status_error.clone(),
+                });
+                let not_reported = match condition.field {
+                    Field::Window(window) => error.is_none().then(|| format!("no {} window", window.name())),
+                    Field::Status => get_status_page_url(id.cli_name())
+                        .is_none()
+                        .then(|| "no status page".to_string()),
+                };
+                if condition.target == Target::Any && not_reported.is_some() {
+                    continue;
+                }
+                ProviderOutcome {
+                    provider: id.cli_name().to_string(),
+                    actual: None,
+                    ok: None,
+                    reason: error.or(not_reported).or_else(|| Some("status unavailable".to_string())),
+                }
+            }
+        };
+        providers.push(outcome);
+    }
+
+    let ok = providers.iter().all(|p| p.ok.unwrap_or(allow_unknown));
+    ConditionResult {
+        condition: condition.raw.clone(),
+        ok,
+        providers,
+    }
+}
+
+async fn fetch_live(args: &CheckArgs, id: ProviderId, usage: bool, status: bool) -> Observation {
+    let mut observation = Observation::default();
+
+    let usage_fetch = async {
+        if !usage {
+            return None;
+        }
+        let provider = create_provider(id);
+        let ctx = FetchContext {
+            source_mode: SourceMode::from_str(&args.source).unwrap_or(SourceMode::Auto),
+            include_credits: false,
+            web_timeout: args.web_timeout,
+            ..FetchContext::default()
+        };
+        let timeout = Duration::from_secs(args.web_timeout + 30);
+        Some(match tokio::time::timeout(timeout, provider.fetch_usage(&ctx)).await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(_) => Err(format!("fetch timed out after {}s", timeout.as_secs())),
+        })
+    };
+    let status_fetch = async {
+        if !status {
+            return None;
+        }
+        tokio::time::timeout(Duration::from_secs(15), fetch_provider_status(id.cli_name()))
+            .await
+            .ok()
+            .flatten()
+    };
+    let (usage_result, status_result) = tokio::join!(usage_fetch, status_fetch);
+
+    match usage_result {
+        Some(Ok(result)) => {
+            for window in [UsageWindow::Session, UsageWindow::Weekly, UsageWindow::Model] {
+                if let Some(rate) = result.usage.window(window) {
+                    observation.windows.insert(window.name(), rate.used_percent);
+                }
+            }
+        }
+        Some(Err(e)) => observation.usage_error = Some(e),
+        None => {}
+    }
+    observation.status = status_result.map(|s| s.level);
+    if status && observation.status.is_none() && get_status_page_url(id.cli_name()).is_some() {
+        observation.status_error = Some("status page unreachable".to_string());
+    }
+    observation
+}
+
+/// Observations from the menu bar app's last refresh
+fn load_cached(providers: &[ProviderId], max_age: Duration) -> HashMap<ProviderId, Observation> {
+    let snapshot = WidgetSnapshotStore::load();
+    let max_age = chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+
+    providers
+        .iter()
+        .map(|&id| {
+            let mut observation = Observation::default();
+            match snapshot.as_ref().and_then(|s| s.entry_for(id)) {
+                None => {
+                    let reason = "no cached data (is the menu bar app running?)".to_string();
+                    observation.usage_error = Some(reason.clone());
+                    observation.status_error = Some(reason);
+                }
+                Some(entry) if Utc::now() - entry.updated_at > max_age => {
+                    let reason = format!("cached data from {} is too old", entry.updated_at.to_rfc3339());
+                    observation.usage_error = Some(reason.clone());
+                    observation.status_error = Some(reason);
+                }
+                Some(entry) => {
+                    for window in [UsageWindow::Session, UsageWindow::Weekly, UsageWindow::Model] {
+                        if let Some(rate) = entry.window(window) {
+                            observation.windows.insert(window.name(), rate.used_percent);
+                        }
+                    }
+                    observation.status = entry.status.as_deref().map(StatusLevel::from_indicator);
+                }
+            }
+            (id, observation)
+        })
+        .collect()
+}
+
+fn print_report(report: &CheckReport) {
+    for result in &report.results {
+        let details: Vec<String> = result
+            .providers
+            .iter()
+            .map(|p| match (&p.actual, &p.reason) {
+                (Some(actual), _) => format!("{} {}", p.provider, actual),
+                (None, Some(reason)) => format!("{} unknown: {}", p.provider, reason),
+                (None, None) => format!("{} unknown", p.provider),
+            })
+            .collect();
+        let details = if details.is_empty() {
+            "no providers".to_string()
+        } else {
+            details.join(", ")
+        };
+        println!("{} {} ({})", if result.ok { "PASS" } else { "FAIL" }, result.condition, details);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_condition() {
+        let c: Condition = "claude.session<80".parse().unwrap();
+        assert_eq!(c.target, Target::Provider(ProviderId::Claude));
+        assert_eq!(c.field, Field::Window(UsageWindow::Session));
+        assert_eq!(c.op, CompareOp::Lt);
+        assert_eq!(c.value, 80.0);
+
+        let c: Condition = "codex.weekly <= 95%".parse().unwrap();
+        assert_eq!(c.raw, "codex.weekly<=95%");
+        assert_eq!(c.op, CompareOp::Le);
+        assert_eq!(c.value, 95.0);
+
+        let c: Condition = "any.status!=major".parse().unwrap();
+        assert_eq!(c.target, Target::Any);
+        assert_eq!(c.field, Field::Status);
+        assert_eq!(c.op, CompareOp::Ne);
+        assert_eq!(c.value, 3.0);
+
+        assert!("claude".parse::<Condition>().is_err());
+        assert!("nobody.session<80".parse::<Condition>().is_err());
+        assert!("claude.daily<80".parse::<Condition>().is_err());
+        assert!("claude.session~80".parse::<Condition>().is_err());
+        assert!("claude.session<lots".parse::<Condition>().is_err());
+        assert!("claude.status==purple".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mut observations = HashMap::new();
+        let mut claude = Observation::default();
+        claude.windows.insert("session", 42.0);
+        claude.status = Some(StatusLevel::Degraded);
+        observations.insert(ProviderId::Claude, claude);
+        observations.insert(
+            ProviderId::Codex,
+            Observation {
+                usage_error: Some("not logged in".to_string()),
+                ..Observation::default()
+            },
+        );
+        let both = [ProviderId::Claude, ProviderId::Codex];
+
+        let c: Condition = "claude.session<80".parse().unwrap();
+        assert!(evaluate(&c, &[ProviderId::Claude], &observations, false).ok);
+        let c: Condition = "claude.session<40".parse().unwrap();
+        assert!(!evaluate(&c, &[ProviderId::Claude], &observations, false).ok);
+
+        // Fetch errors are unknown and fail unless allowed
+        let c: Condition = "codex.session<80".parse().unwrap();
+        let result = evaluate(&c, &[ProviderId::Codex], &observations, false);
+        assert!(!result.ok);
+        assert_eq!(result.providers[0].reason.as_deref(), Some("not logged in"));
+        assert!(evaluate(&c, &[ProviderId::Codex], &observations, true).ok);
+
+        // `any` skips providers without the window but not failed fetches
+        let c: Condition = "any.weekly<90".parse().unwrap();
+        let result = evaluate(&c, &[ProviderId::Claude], &observations, false);
+        assert!(result.ok);
+        assert!(result.providers.is_empty());
+        assert!(!evaluate(&c, &both, &observations, false).ok);
+
+        let c: Condition = "claude.status<partial".parse().unwrap();
+        assert!(evaluate(&c, &[ProviderId::Claude], &observations, false).ok);
+        let c: Condition = "claude.status==operational".parse().unwrap();
+        assert!(!evaluate(&c, &[ProviderId::Claude], &observations, false).ok);
+    }
+
+    #[test]
+    fn test_minor_status_is_degraded() {
+        // Statuspage reports a minor incident as "minor", as cached by the app
+        let mut observations = HashMap::new();
+        observations.insert(
+            ProviderId::Claude,
+            Observation {
+                status: Some(StatusLevel::from_indicator("minor")),
+                ..Observation::default()
+            },
+        );
+
+        let c: Condition = "claude.status<=degraded".parse().unwrap();
+        assert!(evaluate(&c, &[ProviderId::Claude], &observations, false).ok);
+        let c: Condition = "claude.status==operational".parse().unwrap();
+        assert!(!evaluate(&c, &[ProviderId::Claude], &observations, false).ok);
+    }
+}
@@ -4,7 +4,7 @@
 
 use clap::{Parser, Subcommand};
 
-use crate::core::TokenAccountStore;
+use crate::core::{CostUsagePricing, PricingFile, TokenAccountStore};
 use crate::settings::{ManualCookies, Settings};
 
 /// Arguments for the config command
@@ -112,6 +112,31 @@ async fn validate_config() -> anyhow::Result<()> {
         println!("NOT FOUND (none configured)");
     }
 
+    // Check pricing overrides
+    if let Some(path) = CostUsagePricing::user_pricing_path() {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        print!("Checking {}... ", name);
+        if path.exists() {
+            match PricingFile::load(&path) {
+                Ok(file) => {
+                    let problems = file.validate();
+                    if problems.is_empty() {
+                        println!("OK");
+                    } else {
+                        println!("INVALID");
+                        errors.extend(problems.into_iter().map(|p| format!("{}: {}", name, p)));
+                    }
+                }
+                Err(e) => {
+                    println!("INVALID");
+                    errors.push(e.to_string());
+                }
+            }
+        } else {
+            println!("NOT FOUND (using built-in prices)");
+        }
+    }
+
     // Print summary
     println!();
     if errors.is_empty() && warnings.is_empty() {
@@ -178,6 +203,11 @@ async fn show_paths() -> anyhow::Result<()> {
     let exists = if token_path.exists() { "" } else { " (not found)" };
     println!("  Token accounts: {}{}", token_path.display(), exists);
 
+    if let Some(path) = CostUsagePricing::user_pricing_path() {
+        let exists = if path.exists() { "" } else { " (not found)" };
+        println!("  Pricing:        {}{}", path.display(), exists);
+    }
+
     // Show config directory
     if let Some(config_dir) = dirs::config_dir() {
         let codexbar_dir = config_dir.join("CodexBar");
@@ -1,28 +1,122 @@
 //! Cost command implementation
 //!
-//! Scans local JSONL logs to calculate token costs for Codex and Claude.
+//! Scans local session logs to calculate token costs for Codex, Claude, Gemini
+//! and OpenCode.
+//! Results can be limited to a date range, grouped by day, week, month,
+//! model, project or session, and exported as text, JSON or CSV. Projects
+//! are resolved to their git repository root, with aliases from settings.
+//! `codexbar cost sessions` lists individual sessions to find runaway ones.
+//! Cache hit ratio, cache read/write cost and savings versus uncached input
+//! are reported alongside token totals. `--forecast` adds a month-end
+//! projection and flags days with unusually high spend. `--check-budget`
+//! compares spend with the budgets in settings and fails when one is over.
 
-use clap::Args;
+use chrono::{Datelike, Local, NaiveDate, Utc};
+use clap::{Args, Subcommand};
 
-use super::usage::{OutputFormat, ProviderSelection};
-use crate::core::ProviderId;
-use crate::cost_scanner::{CostScanner, CostSummary};
+use super::usage::ProviderSelection;
+use crate::core::{
+    detect_cost_anomalies, forecast_month_end, AnomalyOptions, BudgetSpend, BudgetStatus, CostAnomaly,
+    FetchContext, LogRoots, MonthForecast, ProviderId, FORECAST_BASELINE_DAYS,
+};
+use crate::cost_scanner::{
+    daily_cost_history, scan_provider_costs, top_project_costs, CacheUsage, CostGroup, CostGrouping, CostScanner, CostSummary, HourBucket, SessionCost,
+    UsageHeatmap, WEEKDAYS,
+};
+use crate::settings::Settings;
+
+/// Output format for the cost command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CostFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for CostFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(CostFormat::Text),
+            "json" => Ok(CostFormat::Json),
+            "csv" => Ok(CostFormat::Csv),
+            _ => Err(format!("Invalid format: {}. Use 'text', 'json' or 'csv'", s)),
+        }
+    }
+}
+
+/// Sort order for grouped output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostSort {
+    Cost,
+    Tokens,
+    Name,
+}
+
+impl std::str::FromStr for CostSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cost" => Ok(CostSort::Cost),
+            "tokens" => Ok(CostSort::Tokens),
+            "name" | "key" | "date" => Ok(CostSort::Name),
+            _ => Err(format!("Invalid sort: {}. Use 'cost', 'tokens' or 'name'", s)),
+        }
+    }
+}
+
+/// Sort order for the session list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionSort {
+    #[default]
+    Cost,
+    Tokens,
+    Start,
+    Duration,
+}
+
+impl std::str::FromStr for SessionSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cost" => Ok(SessionSort::Cost),
+            "tokens" => Ok(SessionSort::Tokens),
+            "start" | "date" | "recent" => Ok(SessionSort::Start),
+            "duration" => Ok(SessionSort::Duration),
+            _ => Err(format!(
+                "Invalid sort: {}. Use 'cost', 'tokens', 'start' or 'duration'",
+                s
+            )),
+        }
+    }
+}
 
 /// Arguments for the cost command
 #[derive(Args, Debug, Default)]
 pub struct CostArgs {
+    #[command(subcommand)]
+    pub command: Option<CostCommand>,
+
     /// Provider to query (codex, claude, cursor, gemini, copilot, all, both)
     #[arg(short, long)]
     pub provider: Option<String>,
 
-    /// Output format: text or json
+    /// Output format: text, json or csv
     #[arg(short, long, default_value = "text")]
-    pub format: OutputFormat,
+    pub format: CostFormat,
 
     /// Shorthand for --format json
     #[arg(long)]
     pub json: bool,
 
+    /// Shorthand for --format csv
+    #[arg(long, conflicts_with = "json")]
+    pub csv: bool,
+
     /// Disable ANSI colors in text output
     #[arg(long = "no-color")]
     pub no_color: bool,
@@ -34,68 +128,207 @@ pub struct CostArgs {
     /// Number of days to scan (default: 30)
     #[arg(short, long, default_value = "30")]
     pub days: u32,
+
+    /// First day to include (YYYY-MM-DD); overrides --days
+    #[arg(long, value_parser = parse_date)]
+    pub since: Option<NaiveDate>,
+
+    /// Last day to include (YYYY-MM-DD, default: today)
+    #[arg(long, value_parser = parse_date)]
+    pub until: Option<NaiveDate>,
+
+    /// Group results by day, week, month, model, provider, project, session
+    /// or environment (log root)
+    #[arg(short, long = "group-by")]
+    pub group_by: Option<CostGrouping>,
+
+    /// Sort groups by cost, tokens or name (default: date order for
+    /// day/week/month, cost otherwise)
+    #[arg(long)]
+    pub sort: Option<CostSort>,
+
+    /// Only show the top N groups
+    #[arg(long)]
+    pub top: Option<usize>,
+
+    /// Only include projects whose name or path contains this text
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Show cache hit ratio, cache cost and savings for each group
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Project spend to the end of the month and flag unusual days
+    #[arg(long)]
+    pub forecast: bool,
+
+    /// Check spend against the budgets in settings; exits with code 5 when
+    /// any budget is exceeded
+    #[arg(long = "check-budget")]
+    pub check_budget: bool,
+}
+
+/// Returned by `--check-budget` when spend is over a budget
+#[derive(Debug, thiserror::Error)]
+#[error("{count} budget(s) exceeded")]
+pub struct BudgetExceeded {
+    pub count: usize,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CostCommand {
+    /// List individual sessions by cost, tokens, start time or duration
+    Sessions(CostSessionsArgs),
+}
+
+/// Arguments for `codexbar cost sessions`
+#[derive(Args, Debug)]
+pub struct CostSessionsArgs {
+    /// Provider to query (codex, claude, gemini, opencode, both)
+    #[arg(short, long)]
+    pub provider: Option<String>,
+
+    /// Number of days to scan (default: 30)
+    #[arg(short, long, default_value = "30")]
+    pub days: u32,
+
+    /// First day to include (YYYY-MM-DD); overrides --days
+    #[arg(long, value_parser = parse_date)]
+    pub since: Option<NaiveDate>,
+
+    /// Last day to include (YYYY-MM-DD, default: today)
+    #[arg(long, value_parser = parse_date)]
+    pub until: Option<NaiveDate>,
+
+    /// Sort by cost, tokens, start or duration (highest/newest first)
+    #[arg(long, default_value = "cost")]
+    pub sort: SessionSort,
+
+    /// Number of sessions to show (0 = all)
+    #[arg(long, default_value = "20")]
+    pub top: usize,
+
+    /// Only include projects whose name or path contains this text
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Only include sessions that used a model containing this text
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Only include sessions costing at least this many USD
+    #[arg(long = "min-cost")]
+    pub min_cost: Option<f64>,
+
+    /// Output format: text, json or csv
+    #[arg(short, long, default_value = "text")]
+    pub format: CostFormat,
+
+    /// Shorthand for --format json
+    #[arg(long)]
+    pub json: bool,
+
+    /// Shorthand for --format csv
+    #[arg(long, conflicts_with = "json")]
+    pub csv: bool,
+
+    /// Pretty-print JSON output
+    #[arg(long)]
+    pub pretty: bool,
+
+    /// Disable ANSI colors in text output
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+}
+
+/// Parse a YYYY-MM-DD date argument
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}. Use YYYY-MM-DD", s))
 }
 
 /// Run the cost command
 pub async fn run(args: CostArgs) -> anyhow::Result<()> {
+    if let Some(CostCommand::Sessions(sessions_args)) = args.command {
+        return run_sessions(sessions_args).await;
+    }
+
     let format = if args.json {
-        OutputFormat::Json
+        CostFormat::Json
+    } else if args.csv {
+        CostFormat::Csv
     } else {
         args.format
     };
 
+    if args.check_budget {
+        return check_budgets(format, args.pretty, !args.no_color && is_terminal()).await;
+    }
+
     let providers = ProviderSelection::from_arg(args.provider.as_deref())?;
     let use_color = !args.no_color && is_terminal();
-    let scanner = CostScanner::new(args.days);
+    let period = Period::new(args.days, args.since, args.until)?;
+    let settings = Settings::load();
+    let aliases = settings.project_aliases.clone();
+    let roots = settings.cost_log_roots();
+    let scanner = CostScanner::with_range(period.since, period.until)
+        .with_project_aliases(aliases.clone())
+        .with_log_roots(roots.clone());
 
     tracing::debug!(
-        "Running cost command: providers={:?}, format={:?}, days={}",
+        "Running cost command: providers={:?}, format={:?}, since={}, until={}, group_by={:?}, project={:?}",
         providers.as_list(),
         format,
-        args.days
+        period.since,
+        period.until,
+        args.group_by,
+        args.project
     );
 
     // Collect cost data for requested providers
     let mut results: Vec<CostResult> = Vec::new();
 
     for provider in providers.as_list() {
-        match provider {
-            ProviderId::Codex => {
-                let summary = scanner.scan_codex();
+        match scanner.scan(provider) {
+            Some(summary) => {
+                let summary = filter_project(summary, &args);
+                let groups = group_summary(&summary, &args);
+                let insights = args
+                    .forecast
+                    .then(|| cost_insights(provider, &period, &args, aliases.clone(), roots.clone()));
                 results.push(CostResult {
                     provider: provider.cli_name().to_string(),
                     display_name: provider.display_name().to_string(),
                     summary,
+                    groups,
                     supported: true,
+                    insights,
                 });
             }
-            ProviderId::Claude => {
-                let summary = scanner.scan_claude();
-                results.push(CostResult {
-                    provider: provider.cli_name().to_string(),
-                    display_name: provider.display_name().to_string(),
-                    summary,
-                    supported: true,
-                });
-            }
-            _ => {
+            None => {
                 // Other providers don't have local logs to scan
                 results.push(CostResult {
                     provider: provider.cli_name().to_string(),
                     display_name: provider.display_name().to_string(),
                     summary: CostSummary::default(),
+                    groups: Vec::new(),
                     supported: false,
+                    insights: None,
                 });
             }
         }
     }
 
     match format {
-        OutputFormat::Text => {
-            print_text_output(&results, use_color, args.days);
+        CostFormat::Text => {
+            print_text_output(&results, use_color, &period, args.group_by, args.cache);
         }
-        OutputFormat::Json => {
-            print_json_output(&results, args.pretty, args.days)?;
+        CostFormat::Json => {
+            print_json_output(&results, args.pretty, &period, args.group_by)?;
+        }
+        CostFormat::Csv => {
+            print_csv_output(&results, args.group_by);
         }
     }
 
@@ -107,21 +340,140 @@ struct CostResult {
     provider: String,
     display_name: String,
     summary: CostSummary,
+    groups: Vec<CostGroup>,
     supported: bool,
+    insights: Option<CostInsights>,
+}
+
+/// Month-end forecast and unusual days (--forecast)
+struct CostInsights {
+    forecast: Option<MonthForecast>,
+    /// Unusual days within the reported period
+    anomalies: Vec<CostAnomaly>,
+}
+
+/// Scan enough history for the forecast baseline and for judging every
+/// day of the period against the days before it
+fn cost_insights(
+    provider: ProviderId,
+    period: &Period,
+    args: &CostArgs,
+    aliases: std::collections::HashMap<String, String>,
+    roots: LogRoots,
+) -> CostInsights {
+    let today = Utc::now().date_naive();
+    let options = AnomalyOptions::default();
+    let history_days = ((today - period.since).num_days().max(0) as u32 + 1 + options.window as u32)
+        .max(FORECAST_BASELINE_DAYS as u32 + 1)
+        .max(today.day());
+
+    let scanner = CostScanner::new(history_days)
+        .with_project_aliases(aliases)
+        .with_log_roots(roots);
+    let summary = scanner.scan(provider).unwrap_or_default();
+    let series = daily_cost_history(&filter_project(summary, args), history_days);
+
+    let (since, until) = (period.since.to_string(), period.until.to_string());
+    CostInsights {
+        forecast: forecast_month_end(&series, today),
+        anomalies: detect_cost_anomalies(&series, &options)
+            .into_iter()
+            .filter(|a| a.date >= since && a.date <= until)
+            .collect(),
+    }
+}
+
+/// Inclusive date range being reported
+struct Period {
+    since: NaiveDate,
+    until: NaiveDate,
+    /// Whether the range came from --days rather than explicit dates
+    from_days: bool,
+    days: u32,
+}
+
+impl Period {
+    fn new(days: u32, since: Option<NaiveDate>, until: Option<NaiveDate>) -> anyhow::Result<Self> {
+        let today = Utc::now().date_naive();
+        let from_days = since.is_none() && until.is_none();
+        let until = until.unwrap_or(today);
+        let since = match since {
+            Some(since) => since,
+            None => until - chrono::Duration::days(days.saturating_sub(1) as i64),
+        };
+
+        if since > until {
+            anyhow::bail!("Invalid date range: --since {} is after --until {}", since, until);
+        }
+
+        let days = (until - since).num_days() as u32 + 1;
+        Ok(Self {
+            since,
+            until,
+            from_days,
+            days,
+        })
+    }
+
+    fn label(&self) -> String {
+        if self.from_days {
+            format!("last {} days", self.days)
+        } else {
+            format!("{} to {}", self.since, self.until)
+        }
+    }
+}
+
+/// Apply the --project filter
+fn filter_project(summary: CostSummary, args: &CostArgs) -> CostSummary {
+    match args.project {
+        Some(ref needle) => summary.filter_project(needle),
+        None => summary,
+    }
+}
+
+/// Group, sort and truncate a summary according to the CLI arguments
+fn group_summary(summary: &CostSummary, args: &CostArgs) -> Vec<CostGroup> {
+    let Some(grouping) = args.group_by else {
+        return Vec::new();
+    };
+
+    let mut groups = summary.group_by(grouping);
+    match args.sort {
+        Some(CostSort::Cost) => groups.sort_by(|a, b| {
+            b.cost_usd
+                .partial_cmp(&a.cost_usd)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        Some(CostSort::Tokens) => groups.sort_by_key(|g| std::cmp::Reverse(g.total_tokens())),
+        Some(CostSort::Name) => groups.sort_by(|a, b| a.key.cmp(&b.key)),
+        None => {}
+    }
+
+    if let Some(top) = args.top {
+        groups.truncate(top);
+    }
+    groups
 }
 
 /// Print text output
-fn print_text_output(results: &[CostResult], use_color: bool, days: u32) {
+fn print_text_output(
+    results: &[CostResult],
+    use_color: bool,
+    period: &Period,
+    grouping: Option<CostGrouping>,
+    show_cache: bool,
+) {
     for (i, result) in results.iter().enumerate() {
         if use_color {
-            println!("\x1b[1m{} Cost (last {} days)\x1b[0m", result.display_name, days);
+            println!("\x1b[1m{} Cost ({})\x1b[0m", result.display_name, period.label());
         } else {
-            println!("{} Cost (last {} days)", result.display_name, days);
+            println!("{} Cost ({})", result.display_name, period.label());
         }
 
         if !result.supported {
             println!("  Local cost scanning not available for this provider");
-            println!("  (Only Codex and Claude have local logs)");
+            println!("  (Only Codex, Claude, Gemini and OpenCode have local logs)");
         } else if result.summary.sessions_count == 0 {
             println!("  No usage data found");
             println!("  Check that you have used {} locally", result.display_name);
@@ -141,17 +493,56 @@ fn print_text_output(results: &[CostResult], use_color: bool, days: u32) {
                 format_number(result.summary.cached_tokens)
             );
 
+            // Prompt caching
+            if let Some(ratio) = result.summary.cache_hit_ratio().filter(|_| result.summary.cached_tokens > 0) {
+                let cache = &result.summary.cache;
+                println!(
+                    "  Cache:    {:.0}% hit ratio, reads ${:.2}, writes ${:.2}, {} ${:.2} vs uncached",
+                    ratio * 100.0,
+                    cache.read_cost_usd,
+                    cache.write_cost_usd,
+                    if cache.savings_usd >= 0.0 { "saved" } else { "lost" },
+                    cache.savings_usd.abs()
+                );
+            }
+
+            if result.summary.unpriced_tokens > 0 {
+                println!(
+                    "  Unpriced: {} tokens from {} (estimated at fallback rates)",
+                    format_number(result.summary.unpriced_tokens),
+                    result.summary.unpriced_models.join(", ")
+                );
+            }
+
             // Sessions
             println!("  Sessions: {}", result.summary.sessions_count);
 
-            // Cost by model
-            if !result.summary.by_model.is_empty() {
+            if let Some((day, hour)) = result.summary.heatmap.peak() {
+                println!("  Busiest:  {} {:02}:00-{:02}:00 (local time)", WEEKDAYS[day], hour, (hour + 1) % 24);
+            }
+
+            if let Some(ref insights) = result.insights {
+                print_insights(insights, use_color);
+            }
+
+            if let Some(grouping) = grouping {
+                print_group_table(&result.groups, grouping, show_cache);
+            } else if !result.summary.by_model.is_empty() {
+                // Cost by model
                 println!("  By model:");
                 let mut models: Vec<_> = result.summary.by_model.iter().collect();
                 models.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal));
                 for (model, cost) in models {
                     println!("    {}: ${:.2}", model, cost);
                 }
+
+                let projects = top_project_costs(&result.summary, 5);
+                if !projects.is_empty() {
+                    println!("  Top projects:");
+                    for (project, cost) in projects {
+                        println!("    {}: ${:.2}", project, cost);
+                    }
+                }
             }
         }
 
@@ -161,8 +552,188 @@ fn print_text_output(results: &[CostResult], use_color: bool, days: u32) {
     }
 }
 
+/// Evaluate the budgets from settings. Codex, Claude, Gemini and OpenCode
+/// spend comes from local logs; other providers contribute the cost they report.
+async fn check_budgets(format: CostFormat, pretty: bool, use_color: bool) -> anyhow::Result<()> {
+    let settings = Settings::load();
+    if settings.budgets.is_empty() {
+        anyhow::bail!("No budgets configured. Add them in Preferences or under \"budgets\" in settings.json");
+    }
+
+    let today = Utc::now().date_naive();
+    let has_overall = settings.budgets.iter().any(|b| b.provider.is_none());
+    let mut providers: Vec<ProviderId> = settings
+        .budgets
+        .iter()
+        .filter_map(|b| b.provider.as_deref().and_then(ProviderId::from_cli_name))
+        .collect();
+    if has_overall {
+        providers.extend(settings.get_enabled_provider_ids());
+        providers.extend([ProviderId::Codex, ProviderId::Claude, ProviderId::Gemini, ProviderId::OpenCode]);
+    }
+    providers.sort_by_key(|p| p.cli_name());
+    providers.dedup();
+
+    let mut spend = BudgetSpend::default();
+    // Enough days for the current month and a week that started last month
+    let history_days = today.day().max(7);
+    let roots = settings.cost_log_roots();
+    for provider in providers {
+        match provider {
+            ProviderId::Codex | ProviderId::Claude | ProviderId::Gemini | ProviderId::OpenCode => {
+                let name = provider.cli_name();
+                let summary =
+                    scan_provider_costs(name, history_days, settings.project_aliases.clone(), roots.clone());
+                spend.add_daily(name, daily_cost_history(&summary, history_days));
+            }
+            _ => {
+                let client = super::usage::create_provider(provider);
+                let ctx = FetchContext::default();
+                match tokio::time::timeout(std::time::Duration::from_secs(30), client.fetch_usage(&ctx)).await {
+                    Ok(Ok(result)) => {
+                        if let Some(cost) = result.cost {
+                            spend.add_snapshot(provider.cli_name(), cost);
+                        }
+                    }
+                    Ok(Err(e)) => tracing::warn!("Budget check: {} unavailable: {}", provider.display_name(), e),
+                    Err(_) => tracing::warn!("Budget check: {} timed out", provider.display_name()),
+                }
+            }
+        }
+    }
+
+    let statuses = spend.evaluate(&settings.budgets, today);
+    match format {
+        CostFormat::Json => {
+            let payload = serde_json::json!({
+                "ok": statuses.iter().all(|s| !s.is_over()),
+                "budgets": statuses.iter().map(|s| {
+                    let mut value = serde_json::to_value(s).unwrap_or_default();
+                    value["over"] = serde_json::json!(s.is_over());
+                    value
+                }).collect::<Vec<_>>(),
+            });
+            let output = if pretty {
+                serde_json::to_string_pretty(&payload)?
+            } else {
+                serde_json::to_string(&payload)?
+            };
+            println!("{}", output);
+        }
+        _ => print_budget_text(&statuses, use_color),
+    }
+
+    let over = statuses.iter().filter(|s| s.is_over()).count();
+    if over > 0 {
+        return Err(BudgetExceeded { count: over }.into());
+    }
+    Ok(())
+}
+
+/// Print one line per budget
+fn print_budget_text(statuses: &[BudgetStatus], use_color: bool) {
+    let width = statuses.iter().map(|s| s.budget.label().len()).max().unwrap_or(0);
+    for status in statuses {
+        let state = if status.is_over() { "OVER" } else { "ok" };
+        let line = format!(
+            "{:<width$}  ${:>9.2} of ${:<9.2} {:>5.0}%  {}",
+            status.budget.label(),
+            status.spent_usd,
+            status.budget.limit_usd,
+            status.percent,
+            state,
+            width = width
+        );
+        if use_color && status.is_over() {
+            println!("\x1b[31m{}\x1b[0m", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Print the month-end forecast and unusual days
+fn print_insights(insights: &CostInsights, use_color: bool) {
+    if let Some(ref forecast) = insights.forecast {
+        println!(
+            "  Forecast: ${:.2} by {} (${:.2} so far, weekday-adjusted)",
+            forecast.projected_usd,
+            forecast.month_end.format("%b %-d"),
+            forecast.month_to_date_usd
+        );
+    }
+    for anomaly in &insights.anomalies {
+        let line = format!(
+            "  Unusual:  {} ${:.2} (usually ${:.2}, {:.1}σ above)",
+            anomaly.date, anomaly.cost_usd, anomaly.baseline_usd, anomaly.z_score
+        );
+        if use_color {
+            println!("\x1b[33m{}\x1b[0m", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Print grouped rows as an aligned table
+fn print_group_table(groups: &[CostGroup], grouping: CostGrouping, show_cache: bool) {
+    if groups.is_empty() {
+        return;
+    }
+
+    let width = groups
+        .iter()
+        .map(|g| g.key.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(grouping.name().len());
+
+    println!("  By {}:", grouping.name());
+    if show_cache {
+        println!(
+            "    {:<width$}  {:>10}  {:>15}         {:>6}  {:>10}  {:>10}  {:>10}",
+            "",
+            "cost",
+            "tokens",
+            "hit",
+            "read",
+            "write",
+            "saved",
+            width = width
+        );
+    }
+    for group in groups {
+        let mut line = format!(
+            "    {:<width$}  {:>10}  {:>15} tokens",
+            group.key,
+            format!("${:.2}", group.cost_usd),
+            format_number(group.total_tokens()),
+            width = width
+        );
+        if show_cache {
+            let hit = group
+                .cache_hit_ratio()
+                .map(|r| format!("{:.0}%", r * 100.0))
+                .unwrap_or_else(|| "-".to_string());
+            line.push_str(&format!(
+                "  {:>6}  {:>10}  {:>10}  {:>10}",
+                hit,
+                format!("${:.2}", group.cache.read_cost_usd),
+                format!("${:.2}", group.cache.write_cost_usd),
+                format!("${:.2}", group.cache.savings_usd)
+            ));
+        }
+        println!("{}", line);
+    }
+}
+
 /// Print JSON output
-fn print_json_output(results: &[CostResult], pretty: bool, days: u32) -> anyhow::Result<()> {
+fn print_json_output(
+    results: &[CostResult],
+    pretty: bool,
+    period: &Period,
+    grouping: Option<CostGrouping>,
+) -> anyhow::Result<()> {
     let payloads: Vec<serde_json::Value> = results
         .iter()
         .map(|r| {
@@ -173,10 +744,10 @@ fn print_json_output(results: &[CostResult], pretty: bool, days: u32) -> anyhow:
                     "error": "Local cost scanning not available for this provider"
                 })
             } else {
-                serde_json::json!({
+                let mut payload = serde_json::json!({
                     "provider": r.provider,
                     "supported": true,
-                    "days_scanned": days,
+                    "days_scanned": period.days,
                     "cost": {
                         "total_usd": r.summary.total_cost_usd,
                         "currency": "USD"
@@ -184,15 +755,44 @@ fn print_json_output(results: &[CostResult], pretty: bool, days: u32) -> anyhow:
                     "tokens": {
                         "input": r.summary.input_tokens,
                         "output": r.summary.output_tokens,
-                        "cached": r.summary.cached_tokens
+                        "cached": r.summary.cached_tokens,
+                        "unpriced": r.summary.unpriced_tokens
                     },
+                    "unpriced_models": r.summary.unpriced_models,
+                    "cache": cache_json(&r.summary.cache, r.summary.cache_hit_ratio()),
                     "sessions_count": r.summary.sessions_count,
                     "by_model": r.summary.by_model,
+                    "heatmap": heatmap_json(&r.summary.heatmap),
                     "period": {
                         "start": r.summary.period_start.map(|d| d.to_string()),
                         "end": r.summary.period_end.map(|d| d.to_string())
                     }
-                })
+                });
+                if let Some(ref insights) = r.insights {
+                    payload["forecast"] = serde_json::json!(insights.forecast);
+                    payload["anomalies"] = serde_json::json!(insights.anomalies);
+                }
+                if let Some(grouping) = grouping {
+                    payload["group_by"] = serde_json::json!(grouping.name());
+                    payload["groups"] = r
+                        .groups
+                        .iter()
+                        .map(|g| {
+                            serde_json::json!({
+                                "key": g.key,
+                                "cost_usd": g.cost_usd,
+                                "tokens": {
+                                    "input": g.input_tokens,
+                                    "output": g.output_tokens,
+                                    "cached": g.cached_tokens,
+                                    "total": g.total_tokens()
+                                },
+                                "cache": cache_json(&g.cache, g.cache_hit_ratio())
+                            })
+                        })
+                        .collect();
+                }
+                payload
             }
         })
         .collect();
@@ -207,6 +807,305 @@ fn print_json_output(results: &[CostResult], pretty: bool, days: u32) -> anyhow:
     Ok(())
 }
 
+/// Cache metrics as JSON
+fn cache_json(cache: &CacheUsage, hit_ratio: Option<f64>) -> serde_json::Value {
+    serde_json::json!({
+        "read_tokens": cache.read_tokens,
+        "write_tokens": cache.write_tokens,
+        "hit_ratio": hit_ratio,
+        "read_cost_usd": cache.read_cost_usd,
+        "write_cost_usd": cache.write_cost_usd,
+        "savings_usd": cache.savings_usd
+    })
+}
+
+/// Heatmap as 7 rows (Monday first) of 24 local hours
+fn heatmap_json(heatmap: &UsageHeatmap) -> serde_json::Value {
+    let rows = |value: fn(&HourBucket) -> serde_json::Value| -> Vec<Vec<serde_json::Value>> {
+        (0..7)
+            .map(|day| (0..24).map(|hour| value(&heatmap.cell(day, hour))).collect())
+            .collect()
+    };
+
+    serde_json::json!({
+        "days": WEEKDAYS,
+        "tokens": rows(|c| serde_json::json!(c.tokens)),
+        "cost_usd": rows(|c| serde_json::json!(c.cost_usd)),
+    })
+}
+
+/// Print CSV output, one row per group (or per provider when ungrouped)
+fn print_csv_output(results: &[CostResult], grouping: Option<CostGrouping>) {
+    println!(
+        "provider,group,input_tokens,cached_tokens,output_tokens,total_tokens,cost_usd,\
+         cache_read_tokens,cache_write_tokens,cache_hit_ratio,cache_read_cost_usd,cache_write_cost_usd,\
+         cache_savings_usd"
+    );
+
+    for result in results.iter().filter(|r| r.supported) {
+        if grouping.is_some() {
+            for group in &result.groups {
+                println!(
+                    "{},{},{},{},{},{},{:.4},{}",
+                    result.provider,
+                    csv_field(&group.key),
+                    group.input_tokens,
+                    group.cached_tokens,
+                    group.output_tokens,
+                    group.total_tokens(),
+                    group.cost_usd,
+                    cache_csv(&group.cache, group.cache_hit_ratio())
+                );
+            }
+        } else {
+            let s = &result.summary;
+            println!(
+                "{},total,{},{},{},{},{:.4},{}",
+                result.provider,
+                s.input_tokens,
+                s.cached_tokens,
+                s.output_tokens,
+                s.input_tokens + s.cached_tokens + s.output_tokens,
+                s.total_cost_usd,
+                cache_csv(&s.cache, s.cache_hit_ratio())
+            );
+        }
+    }
+}
+
+/// A session together with the provider it came from
+struct ProviderSession {
+    provider: ProviderId,
+    session: SessionCost,
+}
+
+/// Run `codexbar cost sessions`
+async fn run_sessions(args: CostSessionsArgs) -> anyhow::Result<()> {
+    let format = if args.json {
+        CostFormat::Json
+    } else if args.csv {
+        CostFormat::Csv
+    } else {
+        args.format
+    };
+
+    let providers = ProviderSelection::from_arg(args.provider.as_deref())?;
+    let use_color = !args.no_color && is_terminal();
+    let period = Period::new(args.days, args.since, args.until)?;
+    let settings = Settings::load();
+    let scanner = CostScanner::with_range(period.since, period.until)
+        .with_project_aliases(settings.project_aliases.clone())
+        .with_log_roots(settings.cost_log_roots());
+
+    tracing::debug!(
+        "Running cost sessions command: providers={:?}, since={}, until={}, sort={:?}",
+        providers.as_list(),
+        period.since,
+        period.until,
+        args.sort
+    );
+
+    let mut sessions: Vec<ProviderSession> = Vec::new();
+    for provider in providers.as_list() {
+        let Some(summary) = scanner.scan(provider) else {
+            continue;
+        };
+        sessions.extend(
+            summary
+                .sessions
+                .into_iter()
+                .map(|session| ProviderSession { provider, session }),
+        );
+    }
+
+    let project = args.project.as_deref().map(str::to_lowercase);
+    let model = args.model.as_deref().map(str::to_lowercase);
+    sessions.retain(|s| {
+        let session = &s.session;
+        project.as_ref().is_none_or(|needle| {
+            session
+                .project
+                .as_deref()
+                .is_some_and(|p| p.to_lowercase().contains(needle))
+        }) && model.as_ref().is_none_or(|needle| {
+            session.models.iter().any(|(m, _)| m.to_lowercase().contains(needle))
+        }) && args.min_cost.is_none_or(|min| session.cost_usd >= min)
+    });
+
+    match args.sort {
+        SessionSort::Cost => sessions.sort_by(|a, b| {
+            b.session
+                .cost_usd
+                .partial_cmp(&a.session.cost_usd)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        SessionSort::Tokens => sessions.sort_by_key(|s| std::cmp::Reverse(s.session.total_tokens())),
+        SessionSort::Start => sessions.sort_by_key(|s| std::cmp::Reverse(s.session.started_at)),
+        SessionSort::Duration => sessions.sort_by_key(|s| std::cmp::Reverse(s.session.duration())),
+    }
+    if args.top > 0 {
+        sessions.truncate(args.top);
+    }
+
+    match format {
+        CostFormat::Text => print_sessions_text(&sessions, &period, use_color),
+        CostFormat::Json => print_sessions_json(&sessions, args.pretty)?,
+        CostFormat::Csv => print_sessions_csv(&sessions),
+    }
+
+    Ok(())
+}
+
+/// Print sessions as a table
+fn print_sessions_text(sessions: &[ProviderSession], period: &Period, use_color: bool) {
+    if use_color {
+        println!("\x1b[1mSessions ({})\x1b[0m", period.label());
+    } else {
+        println!("Sessions ({})", period.label());
+    }
+
+    if sessions.is_empty() {
+        println!("  No sessions found");
+        return;
+    }
+
+    for s in sessions {
+        let session = &s.session;
+        let started = session
+            .started_at
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let cost = format!("${:.2}", session.cost_usd);
+
+        if use_color {
+            println!(
+                "  \x1b[32m{:>9}\x1b[0m  {:>13} tokens  {}  {:>7}  {}",
+                cost,
+                format_number(session.total_tokens()),
+                started,
+                format_duration(session.duration()),
+                s.provider.display_name()
+            );
+        } else {
+            println!(
+                "  {:>9}  {:>13} tokens  {}  {:>7}  {}",
+                cost,
+                format_number(session.total_tokens()),
+                started,
+                format_duration(session.duration()),
+                s.provider.display_name()
+            );
+        }
+
+        let models: Vec<&str> = session.models.iter().map(|(m, _)| m.as_str()).collect();
+        println!(
+            "             {} | {}",
+            session.project.as_deref().unwrap_or("(unknown project)"),
+            models.join(", ")
+        );
+        println!("             {}", session.path.display());
+    }
+}
+
+/// Print sessions as JSON
+fn print_sessions_json(sessions: &[ProviderSession], pretty: bool) -> anyhow::Result<()> {
+    let payload: Vec<serde_json::Value> = sessions
+        .iter()
+        .map(|s| {
+            let session = &s.session;
+            serde_json::json!({
+                "provider": s.provider.cli_name(),
+                "session_id": session.session_id,
+                "path": session.path.display().to_string(),
+                "project": session.project,
+                "started_at": session.started_at.map(|t| t.to_rfc3339()),
+                "ended_at": session.ended_at.map(|t| t.to_rfc3339()),
+                "duration_minutes": session.duration().map(|d| d.num_minutes()),
+                "models": session
+                    .models
+                    .iter()
+                    .map(|(model, cost)| serde_json::json!({ "model": model, "cost_usd": cost }))
+                    .collect::<Vec<_>>(),
+                "tokens": {
+                    "input": session.input_tokens,
+                    "cached": session.cached_tokens,
+                    "output": session.output_tokens,
+                    "total": session.total_tokens()
+                },
+                "cost_usd": session.cost_usd
+            })
+        })
+        .collect();
+
+    let output = if pretty {
+        serde_json::to_string_pretty(&payload)?
+    } else {
+        serde_json::to_string(&payload)?
+    };
+    println!("{}", output);
+
+    Ok(())
+}
+
+/// Print sessions as CSV
+fn print_sessions_csv(sessions: &[ProviderSession]) {
+    println!(
+        "provider,session_id,project,started_at,ended_at,duration_minutes,main_model,\
+         input_tokens,cached_tokens,output_tokens,total_tokens,cost_usd,path"
+    );
+
+    for s in sessions {
+        let session = &s.session;
+        println!(
+            "{},{},{},{},{},{},{},{},{},{},{},{:.4},{}",
+            s.provider.cli_name(),
+            csv_field(&session.session_id),
+            csv_field(session.project.as_deref().unwrap_or("")),
+            session.started_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            session.ended_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            session.duration().map(|d| d.num_minutes().to_string()).unwrap_or_default(),
+            csv_field(session.main_model().unwrap_or("")),
+            session.input_tokens,
+            session.cached_tokens,
+            session.output_tokens,
+            session.total_tokens(),
+            session.cost_usd,
+            csv_field(&session.path.display().to_string())
+        );
+    }
+}
+
+/// Format a duration as "3h 05m" or "12m"
+fn format_duration(duration: Option<chrono::Duration>) -> String {
+    match duration {
+        Some(d) if d.num_hours() > 0 => format!("{}h {:02}m", d.num_hours(), d.num_minutes() % 60),
+        Some(d) => format!("{}m", d.num_minutes()),
+        None => "-".to_string(),
+    }
+}
+
+/// Cache columns for a CSV row
+fn cache_csv(cache: &CacheUsage, hit_ratio: Option<f64>) -> String {
+    format!(
+        "{},{},{},{:.4},{:.4},{:.4}",
+        cache.read_tokens,
+        cache.write_tokens,
+        hit_ratio.map(|r| format!("{:.4}", r)).unwrap_or_default(),
+        cache.read_cost_usd,
+        cache.write_cost_usd,
+        cache.savings_usd
+    )
+}
+
+/// Quote a CSV field if it contains separators or quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Format a number with commas
 fn format_number(n: u64) -> String {
     let s = n.to_string();
@@ -0,0 +1,731 @@
+//! Doctor command implementation
+//!
+//! Diagnoses every authentication source a provider can use without
+//! fetching usage: CLI binaries, credential files, token expiry and scopes,
+//! browser cookies, saved manual cookies, API keys and token accounts.
+
+use chrono::{DateTime, Utc};
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use super::usage::{create_provider, OutputFormat, ProviderSelection};
+use crate::browser::cookie_cache::CookieHeaderCache;
+use crate::browser::cookies::CookieExtractor;
+use crate::browser::detection::{BrowserDetector, DetectedBrowser};
+use crate::core::{ProviderId, TokenAccountStore, TokenAccountSupport};
+use crate::providers::claude::ClaudeOAuthFetcher;
+use crate::settings::{get_api_key_providers, ApiKeys, ManualCookies, Settings};
+
+/// Arguments for the doctor command
+#[derive(Args, Debug, Default)]
+pub struct DoctorArgs {
+    /// Provider to diagnose (codex, claude, cursor, gemini, copilot, all, both).
+    /// Defaults to the providers enabled in settings.
+    #[arg(short, long)]
+    pub provider: Option<String>,
+
+    /// Output format: text or json
+    #[arg(short, long, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Shorthand for --format json
+    #[arg(long)]
+    pub json: bool,
+
+    /// Disable ANSI colors in text output
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+
+    /// Pretty-print JSON output
+    #[arg(long)]
+    pub pretty: bool,
+
+    /// Skip reading browser cookie databases (faster, no DPAPI prompts)
+    #[arg(long = "no-browser")]
+    pub no_browser: bool,
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Source is present and usable
+    Ok,
+    /// Source is present but has a problem that may break fetching
+    Warn,
+    /// Source is present but unusable
+    Error,
+    /// Source is not configured
+    Missing,
+}
+
+impl CheckStatus {
+    fn symbol(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Error => "✗",
+            CheckStatus::Missing => "-",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "\x1b[32m",
+            CheckStatus::Warn => "\x1b[33m",
+            CheckStatus::Error => "\x1b[31m",
+            CheckStatus::Missing => "\x1b[90m",
+        }
+    }
+}
+
+/// A single diagnostic check for one authentication source
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    /// Source name (e.g., "cli", "credentials", "browser:Google Chrome/Default")
+    pub source: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// Actionable fix when the check did not pass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(source: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            status,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Diagnostic report for a provider
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderReport {
+    pub provider: String,
+    pub display_name: String,
+    /// Whether at least one authentication source looks usable
+    pub ready: bool,
+    pub checks: Vec<DoctorCheck>,
+}
+
+/// Returned when at least one provider has no usable authentication source
+#[derive(Debug, Error)]
+#[error("{count} provider(s) have no usable authentication source")]
+pub struct ProvidersNotReady {
+    pub count: usize,
+}
+
+/// Run the doctor command
+pub async fn run(args: DoctorArgs) -> anyhow::Result<()> {
+    let format = if args.json {
+        OutputFormat::Json
+    } else {
+        args.format
+    };
+
+    let providers = match args.provider.as_deref() {
+        Some(arg) => ProviderSelection::from_arg(Some(arg))?.as_list(),
+        None => Settings::load().get_enabled_provider_ids(),
+    };
+    let use_color = !args.no_color && is_terminal();
+
+    tracing::debug!(
+        "Running doctor command: providers={:?}, format={:?}, browser={}",
+        providers,
+        format,
+        !args.no_browser
+    );
+
+    let browsers = if args.no_browser {
+        None
+    } else {
+        Some(BrowserDetector::detect_all())
+    };
+    let manual_cookies = ManualCookies::load();
+    let api_keys = ApiKeys::load();
+
+    let reports: Vec<ProviderReport> = providers
+        .iter()
+        .map(|&id| diagnose_provider(id, browsers.as_deref(), &manual_cookies, &api_keys))
+        .collect();
+
+    match format {
+        OutputFormat::Text => print_text_output(&reports, use_color),
+        OutputFormat::Json => {
+            let output = if args.pretty {
+                serde_json::to_string_pretty(&reports)?
+            } else {
+                serde_json::to_string(&reports)?
+            };
+            println!("{}", output);
+        }
+    }
+
+    let not_ready = reports.iter().filter(|r| !r.ready).count();
+    if not_ready > 0 {
+        return Err(ProvidersNotReady { count: not_ready }.into());
+    }
+
+    Ok(())
+}
+
+/// Run every applicable check for a provider
+pub fn diagnose_provider(
+    id: ProviderId,
+    browsers: Option<&[DetectedBrowser]>,
+    manual_cookies: &ManualCookies,
+    api_keys: &ApiKeys,
+) -> ProviderReport {
+    let mut checks = Vec::new();
+
+    if let Some(check) = check_cli(id) {
+        checks.push(check);
+    }
+    checks.extend(check_credential_files(id));
+    checks.extend(check_api_key(id, api_keys));
+    checks.extend(check_token_accounts(id));
+
+    if let Some(domain) = id.cookie_domain() {
+        checks.push(check_manual_cookie(id, manual_cookies));
+        if let Some(check) = check_cookie_cache(id) {
+            checks.push(check);
+        }
+        match browsers {
+            Some(browsers) => checks.extend(check_browser_cookies(domain, browsers)),
+            None => checks.push(DoctorCheck::new(
+                "browser",
+                CheckStatus::Missing,
+                "Skipped (--no-browser)",
+            )),
+        }
+    }
+
+    // The CLI binary alone does not authenticate anything
+    let ready = checks
+        .iter()
+        .any(|c| c.status == CheckStatus::Ok && c.source != "cli");
+
+    ProviderReport {
+        provider: id.cli_name().to_string(),
+        display_name: id.display_name().to_string(),
+        ready,
+        checks,
+    }
+}
+
+/// Check the provider's CLI binary and version
+fn check_cli(id: ProviderId) -> Option<DoctorCheck> {
+    let (binary, path, install_hint) = match id {
+        ProviderId::Codex => (
+            "codex",
+            crate::providers::codex::which_codex(),
+            "npm install -g @openai/codex",
+        ),
+        ProviderId::Claude => (
+            "claude",
+            crate::providers::claude::which_claude(),
+            "npm install -g @anthropic-ai/claude-code",
+        ),
+        ProviderId::Kiro => (
+            "kiro-cli",
+            crate::providers::kiro::find_kiro_cli(),
+            "Install Kiro from https://kiro.dev",
+        ),
+        ProviderId::Gemini => (
+            "gemini",
+            which::which("gemini").ok(),
+            "npm install -g @google/gemini-cli",
+        ),
+        ProviderId::Copilot => (
+            "gh",
+            which::which("gh").ok(),
+            "Install the GitHub CLI from https://cli.github.com",
+        ),
+        ProviderId::VertexAI => (
+            "gcloud",
+            which::which("gcloud").ok(),
+            "Install the Google Cloud SDK from https://cloud.google.com/sdk",
+        ),
+        ProviderId::Augment => (
+            "augment",
+            which::which("augment").ok(),
+            "Install the Augment CLI",
+        ),
+        _ => return None,
+    };
+
+    let Some(path) = path else {
+        return Some(
+            DoctorCheck::new("cli", CheckStatus::Missing, format!("{} not found on PATH", binary))
+                .with_fix(install_hint),
+        );
+    };
+
+    let version = match id {
+        ProviderId::Kiro => crate::providers::kiro::detect_version(),
+        _ => create_provider(id).detect_version(),
+    };
+
+    let detail = match version {
+        Some(v) => format!("{} {} ({})", binary, v, path.display()),
+        None => format!("{} ({}, version unknown)", binary, path.display()),
+    };
+
+    Some(DoctorCheck::new("cli", CheckStatus::Ok, detail))
+}
+
+/// Check credential files written by provider CLIs
+pub fn check_credential_files(id: ProviderId) -> Vec<DoctorCheck> {
+    match id {
+        ProviderId::Codex => vec![check_codex_auth()],
+        ProviderId::Claude => vec![check_claude_oauth()],
+        ProviderId::Gemini => vec![check_gemini_oauth()],
+        _ => Vec::new(),
+    }
+}
+
+/// Check ~/.codex/auth.json (or $CODEX_HOME/auth.json)
+fn check_codex_auth() -> DoctorCheck {
+    let path = codex_home().join("auth.json");
+    let source = "credentials";
+    let login_fix = "Run `codex login` to sign in";
+
+    let json = match read_json(&path) {
+        Ok(Some(json)) => json,
+        Ok(None) => {
+            return DoctorCheck::new(source, CheckStatus::Missing, format!("{} not found", path.display()))
+                .with_fix(login_fix)
+        }
+        Err(e) => {
+            return DoctorCheck::new(source, CheckStatus::Error, format!("{}: {}", path.display(), e))
+                .with_fix(login_fix)
+        }
+    };
+
+    if json
+        .get("OPENAI_API_KEY")
+        .and_then(|v| v.as_str())
+        .is_some_and(|k| !k.trim().is_empty())
+    {
+        return DoctorCheck::new(
+            source,
+            CheckStatus::Warn,
+            format!("{} contains an API key, which cannot read ChatGPT rate limits", path.display()),
+        )
+        .with_fix("Run `codex login` and sign in with ChatGPT");
+    }
+
+    let Some(access_token) = json
+        .get("tokens")
+        .and_then(|t| t.get("access_token"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+    else {
+        return DoctorCheck::new(source, CheckStatus::Error, format!("{} has no access token", path.display()))
+            .with_fix(login_fix);
+    };
+
+    let has_refresh = json
+        .get("tokens")
+        .and_then(|t| t.get("refresh_token"))
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.is_empty());
+
+    match jwt_expiry(access_token) {
+        Some(expires_at) if expires_at <= Utc::now() => {
+            let detail = format!("Access token expired {}", format_relative(expires_at));
+            let check = DoctorCheck::new(source, CheckStatus::Error, detail);
+            if has_refresh {
+                check.with_fix("Run any `codex` command to refresh the token, or `codex login`")
+            } else {
+                check.with_fix(login_fix)
+            }
+        }
+        Some(expires_at) => DoctorCheck::new(
+            source,
+            CheckStatus::Ok,
+            format!("{} (token expires {})", path.display(), format_relative(expires_at)),
+        ),
+        None => DoctorCheck::new(source, CheckStatus::Ok, path.display().to_string()),
+    }
+}
+
+/// Check Claude OAuth credentials (environment or ~/.claude/.credentials.json)
+fn check_claude_oauth() -> DoctorCheck {
+    let source = "credentials";
+    let login_fix = "Run `claude` and complete `/login`";
+
+    let credentials = match ClaudeOAuthFetcher::new().load_credentials() {
+        Ok(c) => c,
+        Err(e) => {
+            let path = dirs::home_dir()
+                .map(|h| h.join(".claude").join(".credentials.json"))
+                .unwrap_or_default();
+            let status = if path.exists() {
+                CheckStatus::Error
+            } else {
+                CheckStatus::Missing
+            };
+            return DoctorCheck::new(source, status, e.to_string()).with_fix(login_fix);
+        }
+    };
+
+    if !credentials.scopes.is_empty() && !credentials.has_scope("user:profile") {
+        return DoctorCheck::new(
+            source,
+            CheckStatus::Error,
+            format!("Token missing 'user:profile' scope (has: {})", credentials.scopes.join(", ")),
+        )
+        .with_fix("Run `claude setup-token` to regenerate the token");
+    }
+
+    match credentials.expires_at {
+        Some(expires_at) if credentials.is_expired() => {
+            DoctorCheck::new(
+                source,
+                CheckStatus::Error,
+                format!("OAuth token expired {}", format_relative(expires_at)),
+            )
+            .with_fix("Run `claude` to refresh the token")
+        }
+        Some(expires_at) => DoctorCheck::new(
+            source,
+            CheckStatus::Ok,
+            format!("OAuth token expires {}", format_relative(expires_at)),
+        ),
+        None => DoctorCheck::new(source, CheckStatus::Ok, "OAuth token (no expiry)"),
+    }
+}
+
+/// Check ~/.gemini/oauth_creds.json
+fn check_gemini_oauth() -> DoctorCheck {
+    let source = "credentials";
+    let login_fix = "Run `gemini` and sign in with Google";
+    let path = dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".gemini")
+        .join("oauth_creds.json");
+
+    let json = match read_json(&path) {
+        Ok(Some(json)) => json,
+        Ok(None) => {
+            return DoctorCheck::new(source, CheckStatus::Missing, format!("{} not found", path.display()))
+                .with_fix(login_fix)
+        }
+        Err(e) => {
+            return DoctorCheck::new(source, CheckStatus::Error, format!("{}: {}", path.display(), e))
+                .with_fix(login_fix)
+        }
+    };
+
+    let has_access = json
+        .get("access_token")
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.is_empty());
+    let has_refresh = json
+        .get("refresh_token")
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.is_empty());
+    let expires_at = json
+        .get("expiry_date")
+        .and_then(|v| v.as_f64())
+        .and_then(|ms| DateTime::from_timestamp((ms / 1000.0) as i64, 0));
+
+    if !has_access && !has_refresh {
+        return DoctorCheck::new(source, CheckStatus::Error, format!("{} has no tokens", path.display()))
+            .with_fix(login_fix);
+    }
+
+    match expires_at {
+        Some(at) if at <= Utc::now() && has_refresh => DoctorCheck::new(
+            source,
+            CheckStatus::Ok,
+            format!("Access token expired {} (will be refreshed)", format_relative(at)),
+        ),
+        Some(at) if at <= Utc::now() => DoctorCheck::new(
+            source,
+            CheckStatus::Error,
+            format!("Access token expired {} and no refresh token", format_relative(at)),
+        )
+        .with_fix(login_fix),
+        Some(at) => DoctorCheck::new(
+            source,
+            CheckStatus::Ok,
+            format!("Access token expires {}", format_relative(at)),
+        ),
+        None => DoctorCheck::new(source, CheckStatus::Ok, path.display().to_string()),
+    }
+}
+
+/// Check saved API keys and their environment variable fallbacks
+fn check_api_key(id: ProviderId, api_keys: &ApiKeys) -> Vec<DoctorCheck> {
+    let Some(info) = get_api_key_providers().into_iter().find(|p| p.id == id) else {
+        return Vec::new();
+    };
+
+    let mut checks = Vec::new();
+
+    if api_keys.has_key(id.cli_name()) {
+        checks.push(DoctorCheck::new("api_key", CheckStatus::Ok, "API key saved in settings"));
+    } else {
+        let mut check = DoctorCheck::new("api_key", CheckStatus::Missing, "No API key saved");
+        if let Some(help) = info.api_key_help {
+            check = check.with_fix(format!(
+                "{}, then add it in Preferences → API Keys",
+                help
+            ));
+        }
+        checks.push(check);
+    }
+
+    if let Some(env_var) = info.api_key_env_var {
+        let set = std::env::var(env_var).is_ok_and(|v| !v.trim().is_empty());
+        if set {
+            checks.push(DoctorCheck::new(
+                format!("env:{}", env_var),
+                CheckStatus::Ok,
+                format!("{} is set", env_var),
+            ));
+        } else {
+            checks.push(DoctorCheck::new(
+                format!("env:{}", env_var),
+                CheckStatus::Missing,
+                format!("{} is not set", env_var),
+            ));
+        }
+    }
+
+    checks
+}
+
+/// Check configured token accounts
+fn check_token_accounts(id: ProviderId) -> Vec<DoctorCheck> {
+    if !TokenAccountSupport::is_supported(id) {
+        return Vec::new();
+    }
+
+    let store = TokenAccountStore::new();
+    let check = match store.load_provider(id) {
+        Ok(data) => match data.active_account() {
+            Some(account) => DoctorCheck::new(
+                "token_account",
+                CheckStatus::Ok,
+                format!("{} account(s), active: {}", data.count(), account.display_name()),
+            ),
+            None => DoctorCheck::new("token_account", CheckStatus::Missing, "No token accounts"),
+        },
+        Err(e) => DoctorCheck::new("token_account", CheckStatus::Error, e.to_string()).with_fix(format!(
+            "Fix or remove {}",
+            TokenAccountStore::default_path().display()
+        )),
+    };
+
+    vec![check]
+}
+
+/// Check for a saved manual cookie header
+fn check_manual_cookie(id: ProviderId, manual_cookies: &ManualCookies) -> DoctorCheck {
+    match manual_cookies.cookies.get(id.cli_name()) {
+        Some(entry) if !entry.cookie_header.trim().is_empty() => DoctorCheck::new(
+            "manual_cookie",
+            CheckStatus::Ok,
+            format!("Saved {}", entry.saved_at),
+        ),
+        _ => DoctorCheck::new("manual_cookie", CheckStatus::Missing, "No manual cookie saved"),
+    }
+}
+
+/// Check the cached cookie header from a previous extraction
+fn check_cookie_cache(id: ProviderId) -> Option<DoctorCheck> {
+    /// Cached headers older than this are likely to have rotated
+    const STALE_AFTER_SECS: i64 = 7 * 24 * 60 * 60;
+
+    let entry = CookieHeaderCache::load(id)?;
+    let age = format_relative(entry.stored_at);
+    let check = if entry.is_stale(STALE_AFTER_SECS) {
+        DoctorCheck::new(
+            "cookie_cache",
+            CheckStatus::Warn,
+            format!("Cached from {} {}", entry.source_label, age),
+        )
+        .with_fix(format!("Sign in to https://{} again to refresh cookies", id.cookie_domain().unwrap_or_default()))
+    } else {
+        DoctorCheck::new(
+            "cookie_cache",
+            CheckStatus::Ok,
+            format!("Cached from {} {}", entry.source_label, age),
+        )
+    };
+
+    Some(check)
+}
+
+/// Check cookie availability in every detected browser profile
+fn check_browser_cookies(domain: &str, browsers: &[DetectedBrowser]) -> Vec<DoctorCheck> {
+    if browsers.is_empty() {
+        return vec![DoctorCheck::new("browser", CheckStatus::Missing, "No supported browser detected")
+            .with_fix("Install Chrome, Edge, Brave or Firefox, or save a manual cookie")];
+    }
+
+    let mut checks = Vec::new();
+
+    for browser in browsers {
+        for profile in &browser.profiles {
+            let source = format!("browser:{}/{}", browser.browser_type.display_name(), profile.name);
+            let check = match CookieExtractor::extract_profile_cookies(browser, profile, domain) {
+                Ok(cookies) if !cookies.is_empty() => DoctorCheck::new(
+                    source,
+                    CheckStatus::Ok,
+                    format!("{} cookie(s) for {}", cookies.len(), domain),
+                ),
+                Ok(_) => DoctorCheck::new(source, CheckStatus::Missing, format!("No cookies for {}", domain))
+                    .with_fix(format!("Sign in to https://{} in this profile", domain)),
+                Err(e) => DoctorCheck::new(source, CheckStatus::Error, e.to_string()).with_fix(
+                    "Close the browser and retry, or paste a cookie header in Preferences",
+                ),
+            };
+            checks.push(check);
+        }
+    }
+
+    checks
+}
+
+/// Print reports as text
+fn print_text_output(reports: &[ProviderReport], use_color: bool) {
+    for (i, report) in reports.iter().enumerate() {
+        let verdict = if report.ready { "ready" } else { "no usable auth source" };
+        if use_color {
+            let color = if report.ready { "\x1b[32m" } else { "\x1b[31m" };
+            println!("\x1b[1m{}\x1b[0m ({}{}\x1b[0m)", report.display_name, color, verdict);
+        } else {
+            println!("{} ({})", report.display_name, verdict);
+        }
+
+        for check in &report.checks {
+            let symbol = if use_color {
+                format!("{}{}\x1b[0m", check.status.color(), check.status.symbol())
+            } else {
+                check.status.symbol().to_string()
+            };
+            println!("  {} {:<14} {}", symbol, check.source, check.detail);
+            if let Some(ref fix) = check.fix {
+                if check.status != CheckStatus::Ok {
+                    println!("    Fix: {}", fix);
+                }
+            }
+        }
+
+        if i < reports.len() - 1 {
+            println!();
+        }
+    }
+}
+
+/// Resolve the Codex home directory ($CODEX_HOME or ~/.codex)
+fn codex_home() -> PathBuf {
+    if let Ok(codex_home) = std::env::var("CODEX_HOME") {
+        let trimmed = codex_home.trim();
+        if !trimmed.is_empty() {
+            return PathBuf::from(trimmed);
+        }
+    }
+
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".codex")
+}
+
+/// Read a JSON file, returning `Ok(None)` if it does not exist
+fn read_json(path: &PathBuf) -> anyhow::Result<Option<serde_json::Value>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+/// Decode the `exp` claim of a JWT without verifying it
+fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    use base64::Engine;
+
+    let payload = token.split('.').nth(1)?;
+    let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let json: serde_json::Value = serde_json::from_slice(&decoded).ok()?;
+    let exp = json.get("exp")?.as_i64()?;
+    DateTime::from_timestamp(exp, 0)
+}
+
+/// Format a timestamp relative to now (e.g., "in 3h 12m", "2d ago")
+fn format_relative(at: DateTime<Utc>) -> String {
+    let delta = at.signed_duration_since(Utc::now());
+    let secs = delta.num_seconds().abs();
+    let span = if secs >= 86_400 {
+        format!("{}d", secs / 86_400)
+    } else if secs >= 3_600 {
+        format!("{}h {}m", secs / 3_600, (secs % 3_600) / 60)
+    } else {
+        format!("{}m", secs / 60)
+    };
+
+    if delta.num_seconds() >= 0 {
+        format!("in {}", span)
+    } else {
+        format!("{} ago", span)
+    }
+}
+
+/// Check if stdout is a terminal
+fn is_terminal() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdout().is_terminal()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jwt_expiry() {
+        use base64::Engine;
+        let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(r#"{"exp":1767225600}"#);
+        let token = format!("eyJhbGciOiJub25lIn0.{}.sig", payload);
+        let expiry = jwt_expiry(&token).unwrap();
+        assert_eq!(expiry.timestamp(), 1_767_225_600);
+
+        assert!(jwt_expiry("not-a-jwt").is_none());
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let future = Utc::now() + chrono::Duration::hours(3) + chrono::Duration::minutes(5);
+        assert!(format_relative(future).starts_with("in 3h"));
+
+        let past = Utc::now() - chrono::Duration::days(2);
+        assert_eq!(format_relative(past), "2d ago");
+    }
+
+    #[test]
+    fn test_manual_cookie_check() {
+        let mut cookies = ManualCookies::default();
+        let check = check_manual_cookie(ProviderId::Claude, &cookies);
+        assert_eq!(check.status, CheckStatus::Missing);
+
+        cookies.set("claude", "sessionKey=abc");
+        let check = check_manual_cookie(ProviderId::Claude, &cookies);
+        assert_eq!(check.status, CheckStatus::Ok);
+    }
+}
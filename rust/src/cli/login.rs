@@ -0,0 +1,237 @@
+//! Login command implementation
+//!
+//! Drives provider login flows from the terminal so headless machines can be
+//! set up without the GUI. CLI-backed providers (Claude, Codex, Gemini) run
+//! their own login flow attached to the terminal, and the login counts once
+//! they save fresh credentials; Copilot uses GitHub's device flow and stores the
+//! resulting token in CodexBar's API key store.
+
+use clap::Args;
+use std::time::Duration;
+
+use super::doctor::{check_credential_files, CheckStatus, DoctorCheck};
+use crate::core::ProviderId;
+use crate::login::{
+    run_claude_login, run_codex_login, run_copilot_login, run_gemini_login, LoginOutcome,
+    LoginPhase, LoginResult,
+};
+use crate::providers::copilot::device_flow::{CopilotDeviceFlow, DeviceFlowError};
+use crate::settings::ApiKeys;
+
+/// Arguments for the login command
+#[derive(Args, Debug)]
+pub struct LoginArgs {
+    /// Provider to log in to (claude, codex, gemini, copilot)
+    pub provider: String,
+
+    /// Maximum time to wait for the login to complete, in seconds
+    #[arg(short, long, default_value = "300")]
+    pub timeout: u64,
+
+    /// Do not open the verification URL in a browser (device flow only)
+    #[arg(long = "no-browser")]
+    pub no_browser: bool,
+
+    /// Use `gh auth login` instead of the built-in device flow (Copilot only)
+    #[arg(long)]
+    pub gh: bool,
+}
+
+/// Run the login command
+pub async fn run(args: LoginArgs) -> anyhow::Result<()> {
+    let provider = ProviderId::from_cli_name(&args.provider).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown provider: '{}'. Supported: claude, codex, gemini, copilot",
+            args.provider
+        )
+    })?;
+
+    tracing::debug!(
+        "Running login command: provider={}, timeout={}s",
+        provider.cli_name(),
+        args.timeout
+    );
+
+    match provider {
+        ProviderId::Claude => {
+            eprintln!("Complete /login in Claude Code, then exit it (/exit) to continue.");
+            let result = run_claude_login(args.timeout, print_phase).await;
+            finish_cli_login(provider, "claude", result)
+        }
+        ProviderId::Codex => {
+            let result = run_codex_login(args.timeout, print_phase).await;
+            finish_cli_login(provider, "codex", result)
+        }
+        ProviderId::Gemini => {
+            eprintln!("Sign in with Google when the Gemini CLI asks, then exit it (/quit) to continue.");
+            let result = run_gemini_login(args.timeout, print_phase).await;
+            finish_cli_login(provider, "gemini", result)
+        }
+        ProviderId::Copilot if args.gh => {
+            let result = run_copilot_login(args.timeout, print_phase).await;
+            finish_cli_login(provider, "gh", result)
+        }
+        ProviderId::Copilot => run_copilot_device_flow(args.timeout, !args.no_browser).await,
+        other => anyhow::bail!(
+            "{} has no login flow. Use 'codexbar account add {}' or Preferences to add a token.",
+            other.display_name(),
+            other.cli_name()
+        ),
+    }
+}
+
+/// Print login phase progress to stderr
+fn print_phase(phase: LoginPhase) {
+    match phase {
+        LoginPhase::Idle => {}
+        LoginPhase::Requesting => eprintln!("Starting login..."),
+        LoginPhase::WaitingBrowser => eprintln!("Waiting for you to finish signing in..."),
+        LoginPhase::Complete => eprintln!("Login complete."),
+    }
+}
+
+/// Report the outcome of a CLI-driven login and verify the stored credentials
+fn finish_cli_login(provider: ProviderId, binary: &str, result: LoginResult) -> anyhow::Result<()> {
+    if let Some(ref link) = result.auth_link {
+        println!("Sign-in URL: {}", link);
+    }
+    if let LoginOutcome::Failed { .. } = result.outcome {
+        let output = result.output.trim();
+        if !output.is_empty() {
+            eprintln!("{}", output);
+        }
+    }
+
+    // The CLI stores its own credentials; confirm CodexBar can read them
+    let checks = match result.outcome {
+        LoginOutcome::Success => check_credential_files(provider),
+        _ => Vec::new(),
+    };
+    for line in login_report(provider, binary, &result.outcome, &checks)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Lines describing a finished login and its credential checks, or the
+/// error for a login that did not finish
+fn login_report(
+    provider: ProviderId,
+    binary: &str,
+    outcome: &LoginOutcome,
+    checks: &[DoctorCheck],
+) -> anyhow::Result<Vec<String>> {
+    match outcome {
+        LoginOutcome::Success => {}
+        LoginOutcome::TimedOut => anyhow::bail!("Login timed out waiting for {}", binary),
+        LoginOutcome::Failed { status } => anyhow::bail!("{} login exited with status {}", binary, status),
+        LoginOutcome::NoCredentials => anyhow::bail!("{} exited without saving new credentials", binary),
+        LoginOutcome::MissingBinary => anyhow::bail!(
+            "{} binary not found. Run 'codexbar doctor -p {}' for install hints.",
+            binary,
+            provider.cli_name()
+        ),
+        LoginOutcome::LaunchFailed(e) => anyhow::bail!("Failed to launch {}: {}", binary, e),
+    }
+
+    let mut lines = Vec::new();
+    for check in checks {
+        match check.status {
+            CheckStatus::Ok => lines.push(format!("{} credentials OK: {}", provider.display_name(), check.detail)),
+            _ => {
+                lines.push(format!(
+                    "{} login finished, but credentials look unusable: {}",
+                    provider.display_name(),
+                    check.detail
+                ));
+                if let Some(ref fix) = check.fix {
+                    lines.push(format!("  Fix: {}", fix));
+                }
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// Run GitHub's device flow and store the token for Copilot
+async fn run_copilot_device_flow(timeout_secs: u64, open_browser: bool) -> anyhow::Result<()> {
+    let flow = CopilotDeviceFlow::new();
+
+    print_phase(LoginPhase::Requesting);
+    let device = flow
+        .request_device_code()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to start GitHub device flow: {}", e))?;
+
+    println!("Open {} and enter code: {}", device.verification_uri, device.user_code);
+    if open_browser && open::that(&device.verification_uri).is_err() {
+        tracing::debug!("Could not open browser for device flow");
+    }
+
+    print_phase(LoginPhase::WaitingBrowser);
+    let expires_in = device.expires_in.min(u32::try_from(timeout_secs).unwrap_or(u32::MAX));
+    let wait = flow.wait_for_token(&device.device_code, device.interval, expires_in);
+
+    // Guard against a stalled poll request outliving the timeout
+    let token = match tokio::time::timeout(Duration::from_secs(timeout_secs + 30), wait).await {
+        Ok(Ok(token)) => token,
+        Ok(Err(DeviceFlowError::ExpiredToken)) | Err(_) => {
+            anyhow::bail!("Login timed out before the device code was authorized")
+        }
+        Ok(Err(DeviceFlowError::AccessDenied)) => anyhow::bail!("Access was denied on GitHub"),
+        Ok(Err(e)) => anyhow::bail!("GitHub device flow failed: {}", e),
+    };
+
+    let mut api_keys = ApiKeys::load();
+    api_keys.set(ProviderId::Copilot.cli_name(), &token, Some("GitHub device flow"));
+    api_keys.save()?;
+    print_phase(LoginPhase::Complete);
+
+    if let Some(path) = ApiKeys::keys_path() {
+        println!("Saved GitHub token for Copilot to {}", path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(status: CheckStatus, detail: &str, fix: Option<&str>) -> DoctorCheck {
+        DoctorCheck {
+            source: "credentials".to_string(),
+            status,
+            detail: detail.to_string(),
+            fix: fix.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_login_report() {
+        let ok = [check(CheckStatus::Ok, "signed in", None)];
+        let lines = login_report(ProviderId::Gemini, "gemini", &LoginOutcome::Success, &ok).unwrap();
+        assert_eq!(lines, vec!["Gemini credentials OK: signed in".to_string()]);
+
+        let missing = [check(
+            CheckStatus::Missing,
+            "oauth_creds.json not found",
+            Some("Run `gemini` and sign in with Google"),
+        )];
+        let lines = login_report(ProviderId::Gemini, "gemini", &LoginOutcome::Success, &missing).unwrap();
+        assert!(lines[0].contains("credentials look unusable"));
+        assert_eq!(lines[1], "  Fix: Run `gemini` and sign in with Google");
+    }
+
+    #[test]
+    fn test_login_report_failures() {
+        let err = login_report(ProviderId::Gemini, "gemini", &LoginOutcome::Failed { status: 1 }, &[]).unwrap_err();
+        assert_eq!(err.to_string(), "gemini login exited with status 1");
+
+        let err = login_report(ProviderId::Gemini, "gemini", &LoginOutcome::NoCredentials, &[]).unwrap_err();
+        assert_eq!(err.to_string(), "gemini exited without saving new credentials");
+
+        let err = login_report(ProviderId::Gemini, "gemini", &LoginOutcome::MissingBinary, &[]).unwrap_err();
+        assert!(err.to_string().contains("codexbar doctor -p gemini"));
+    }
+}
@@ -3,17 +3,31 @@
 //! Matches the original CodexBar CLI structure:
 //! - `codexbar` - defaults to usage command
 //! - `codexbar cost` - print local token cost usage
+//! - `codexbar blocks` - show Claude 5-hour blocks rebuilt from local logs
 //! - `codexbar menubar` - launch the menu bar GUI app
 //! - `codexbar autostart` - manage Windows auto-start
+//! - `codexbar doctor` - diagnose provider authentication sources
+//! - `codexbar login` - run a provider login flow in the terminal
+//! - `codexbar pricing` - list, validate and resolve model prices
+//! - `codexbar wait` - block until a usage window is available again
+//! - `codexbar check` - gate scripts on usage and status conditions
+//! - `codexbar run` - supervise an agent CLI and report what it consumed
 
 #![allow(dead_code)]
 
 pub mod account;
 pub mod autostart;
+pub mod blocks;
+pub mod check;
 pub mod config;
 pub mod cost;
+pub mod doctor;
+pub mod login;
+pub mod pricing;
+pub mod run;
 pub mod tty_runner;
 pub mod usage;
+pub mod wait;
 
 use clap::{Parser, Subcommand};
 
@@ -24,6 +38,9 @@ pub mod exit_codes {
     pub const PROVIDER_MISSING: i32 = 2;
     pub const PARSE_ERROR: i32 = 3;
     pub const CLI_TIMEOUT: i32 = 4;
+    pub const BUDGET_EXCEEDED: i32 = 5;
+    pub const WAIT_TIMEOUT: i32 = 6;
+    pub const AUTH_MISSING: i32 = 7;
 }
 
 /// CodexBar - Monitor AI provider usage limits
@@ -81,8 +98,8 @@ pub struct Cli {
     #[arg(long = "no-credits")]
     pub no_credits: bool,
 
-    /// Data source: auto, web, cli, oauth
-    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth"])]
+    /// Data source: auto, web, cli, oauth, local
+    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth", "local"])]
     pub source: String,
 
     /// Web fetch timeout in seconds
@@ -106,6 +123,9 @@ pub enum Commands {
     /// Print local token cost usage (Claude + Codex) without web/CLI access
     Cost(cost::CostArgs),
 
+    /// Show Claude 5-hour billing blocks rebuilt from local logs
+    Blocks(blocks::BlocksArgs),
+
     /// Launch the menu bar GUI application
     Menubar,
 
@@ -117,6 +137,24 @@ pub enum Commands {
 
     /// Configuration utilities
     Config(config::ConfigArgs),
+
+    /// Diagnose each provider's authentication sources without fetching usage
+    Doctor(doctor::DoctorArgs),
+
+    /// Log in to a provider from the terminal (claude, codex, gemini, copilot)
+    Login(login::LoginArgs),
+
+    /// List, validate and resolve model prices used for cost estimates
+    Pricing(pricing::PricingArgs),
+
+    /// Wait until a provider's usage window is below a threshold again
+    Wait(wait::WaitArgs),
+
+    /// Check usage and status conditions (e.g. claude.session<80); exits 1 if any fails
+    Check(check::CheckArgs),
+
+    /// Run an agent CLI under supervision: `codexbar run -- claude ...`
+    Run(run::RunArgs),
 }
 
 impl Cli {
@@ -0,0 +1,259 @@
+//! Pricing command implementation
+//!
+//! Lists the model prices used for local cost estimates, validates user
+//! pricing files and shows which entry a model name resolves to.
+
+use std::path::PathBuf;
+
+use chrono::{NaiveDate, Utc};
+use clap::{Parser, Subcommand};
+
+use crate::core::{CostUsagePricing, PricingEntry, PricingFamily, PricingFile};
+
+/// Arguments for the pricing command
+#[derive(Parser, Debug)]
+pub struct PricingArgs {
+    #[command(subcommand)]
+    pub command: PricingCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PricingCommand {
+    /// List model prices from the built-in table and your pricing file
+    List {
+        /// Only list prices for one provider (codex, claude or gemini)
+        #[arg(short, long, value_parser = parse_family)]
+        provider: Option<PricingFamily>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Validate a pricing file (defaults to your pricing file)
+    Validate {
+        /// Path to a TOML or JSON pricing file
+        file: Option<PathBuf>,
+    },
+    /// Show which price a model name resolves to
+    Show {
+        /// Model name as it appears in logs (e.g. claude-sonnet-4-5-20250929)
+        model: String,
+
+        /// Resolve the price in effect on this day (YYYY-MM-DD, default: today)
+        #[arg(long, value_parser = parse_date)]
+        date: Option<NaiveDate>,
+
+        /// Provider the model belongs to (default: guessed from the name)
+        #[arg(short, long, value_parser = parse_family)]
+        provider: Option<PricingFamily>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the pricing file path
+    Path,
+}
+
+/// Run the pricing command
+pub async fn run(args: PricingArgs) -> anyhow::Result<()> {
+    match args.command {
+        PricingCommand::List { provider, json } => list_prices(provider, json),
+        PricingCommand::Validate { file } => validate_file(file),
+        PricingCommand::Show {
+            model,
+            date,
+            provider,
+            json,
+        } => show_price(&model, date, provider, json),
+        PricingCommand::Path => show_path(),
+    }
+}
+
+fn parse_family(s: &str) -> Result<PricingFamily, String> {
+    match s.to_lowercase().as_str() {
+        "codex" | "openai" => Ok(PricingFamily::Codex),
+        "claude" | "anthropic" => Ok(PricingFamily::Claude),
+        "gemini" | "google" => Ok(PricingFamily::Gemini),
+        _ => Err(format!("Invalid provider: {}. Use 'codex', 'claude' or 'gemini'", s)),
+    }
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}. Use YYYY-MM-DD", s))
+}
+
+/// List all price entries
+fn list_prices(provider: Option<PricingFamily>, json: bool) -> anyhow::Result<()> {
+    let table = CostUsagePricing::table();
+    if let Some(ref e) = table.user_error {
+        eprintln!("Warning: {} (using built-in prices)", e);
+    }
+
+    let entries: Vec<&PricingEntry> = table
+        .entries
+        .iter()
+        .filter(|e| provider.is_none_or(|p| e.family == p))
+        .collect();
+
+    if json {
+        let payload: Vec<serde_json::Value> = entries.iter().map(|e| entry_json(e)).collect();
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!("Prices in USD per million tokens");
+    for family in [PricingFamily::Codex, PricingFamily::Claude, PricingFamily::Gemini] {
+        let rows: Vec<&&PricingEntry> = entries.iter().filter(|e| e.family == family).collect();
+        if rows.is_empty() {
+            continue;
+        }
+
+        println!();
+        println!("{}:", family.name());
+        for entry in rows {
+            let p = &entry.pricing;
+            println!(
+                "  {:<28} in {:>7.3}  out {:>7.3}  cache r/w {:>6.3}/{:<6.3}  {} [{}]",
+                p.model,
+                p.input,
+                p.output,
+                p.cache_read_price(),
+                p.cache_write_price(),
+                p.effective_label(),
+                entry.source
+            );
+            if !p.aliases.is_empty() {
+                println!("    aliases: {}", p.aliases.join(", "));
+            }
+            if let (Some(threshold), Some(tier)) = (p.threshold_tokens, p.above_threshold) {
+                println!(
+                    "    above {} tokens: in {:.3}  out {:.3}",
+                    threshold,
+                    tier.input.unwrap_or(p.input),
+                    tier.output.unwrap_or(p.output)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a pricing file
+fn validate_file(file: Option<PathBuf>) -> anyhow::Result<()> {
+    let path = match file.or_else(CostUsagePricing::user_pricing_path) {
+        Some(path) => path,
+        None => anyhow::bail!("Could not determine config path"),
+    };
+
+    if !path.exists() {
+        anyhow::bail!("Pricing file not found: {}", path.display());
+    }
+
+    let parsed = PricingFile::load(&path)?;
+    let problems = parsed.validate();
+
+    if problems.is_empty() {
+        println!(
+            "{}: OK ({} codex, {} claude, {} gemini entries)",
+            path.display(),
+            parsed.codex.len(),
+            parsed.claude.len(),
+            parsed.gemini.len()
+        );
+        return Ok(());
+    }
+
+    println!("{}:", path.display());
+    for problem in &problems {
+        println!("  - {}", problem);
+    }
+    anyhow::bail!("Pricing file is invalid with {} error(s).", problems.len())
+}
+
+/// Show the entry a model is priced with, which may be the family fallback
+fn show_price(
+    model: &str,
+    date: Option<NaiveDate>,
+    provider: Option<PricingFamily>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let table = CostUsagePricing::table();
+    let family = provider.unwrap_or_else(|| PricingFamily::for_model(model));
+    let date = date.unwrap_or_else(|| Utc::now().date_naive());
+    let normalized = table.normalize(family, model);
+    let pricing_path = || {
+        CostUsagePricing::user_pricing_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "your pricing file".to_string())
+    };
+
+    let fallback = table.resolve(family, model, date).is_none();
+    let Some(entry) = table.price(family, model, date) else {
+        anyhow::bail!(
+            "No {} price found for '{}' (normalized: '{}') on {}. Add it to {}",
+            family.name(),
+            model,
+            normalized,
+            date,
+            pricing_path()
+        );
+    };
+
+    if json {
+        let mut payload = entry_json(entry);
+        payload["query"] = serde_json::json!({
+            "model": model,
+            "normalized": normalized,
+            "date": date.to_string(),
+            "fallback": fallback,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let p = &entry.pricing;
+    println!("{} -> {} ({})", model, p.model, family.name());
+    println!("  Source:      {}", entry.source);
+    if fallback {
+        println!(
+            "  Fallback:    no {} price for '{}', estimated at {} rates. Add it to {}",
+            family.name(),
+            normalized,
+            p.model,
+            pricing_path()
+        );
+    }
+    println!("  Effective:   {}", p.effective_label());
+    println!("  Input:       ${:.3} / 1M tokens", p.input);
+    println!("  Output:      ${:.3} / 1M tokens", p.output);
+    println!("  Cache read:  ${:.3} / 1M tokens", p.cache_read_price());
+    println!("  Cache write: ${:.3} / 1M tokens", p.cache_write_price());
+    if let (Some(threshold), Some(tier)) = (p.threshold_tokens, p.above_threshold) {
+        println!("  Above {} tokens per request:", threshold);
+        println!("    Input:  ${:.3}", tier.input.unwrap_or(p.input));
+        println!("    Output: ${:.3}", tier.output.unwrap_or(p.output));
+    }
+
+    Ok(())
+}
+
+/// Show the pricing file path
+fn show_path() -> anyhow::Result<()> {
+    match CostUsagePricing::user_pricing_path() {
+        Some(path) => {
+            let state = if path.exists() { "" } else { " (not found)" };
+            println!("{}{}", path.display(), state);
+            Ok(())
+        }
+        None => anyhow::bail!("Could not determine config path"),
+    }
+}
+
+fn entry_json(entry: &PricingEntry) -> serde_json::Value {
+    let mut value = serde_json::to_value(&entry.pricing).unwrap_or_default();
+    value["provider"] = serde_json::json!(entry.family.name());
+    value["source"] = serde_json::json!(entry.source.to_string());
+    value
+}
@@ -0,0 +1,460 @@
+//! Run command implementation
+//!
+//! Launches an agent CLI (`codexbar run -- claude ...`) attached to the
+//! terminal, polls the provider's usage and local cost while it runs, and
+//! prints what the run consumed when it exits. With `--max-usage` or
+//! `--max-cost`, the agent is warned, interrupted or killed once a ceiling
+//! is crossed.
+
+use chrono::{NaiveDate, Utc};
+use clap::Args;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use super::usage::create_provider;
+use super::wait::{format_duration, parse_wait_duration};
+use crate::core::{FetchContext, LogRoots, Provider, ProviderId, SourceMode, UsageSnapshot, UsageWindow};
+use crate::cost_scanner::CostScanner;
+use crate::host::{interrupt, CommandOptions, CommandRunner};
+use crate::settings::Settings;
+
+/// How often the child is checked for exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Arguments for the run command
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Provider the command uses (default: guessed from the command name)
+    #[arg(short, long)]
+    pub provider: Option<String>,
+
+    /// Window --max-usage applies to: session, weekly or model
+    #[arg(short, long, default_value = "session")]
+    pub window: UsageWindow,
+
+    /// Stop the agent once the window's usage reaches this percentage
+    #[arg(long = "max-usage")]
+    pub max_usage: Option<f64>,
+
+    /// Stop the agent once this run has cost this many dollars
+    #[arg(long = "max-cost")]
+    pub max_cost: Option<f64>,
+
+    /// What to do when a ceiling is crossed
+    #[arg(long = "on-limit", default_value = "interrupt", value_parser = ["warn", "interrupt", "kill"])]
+    pub on_limit: String,
+
+    /// After interrupting, kill the agent if it is still running this much later
+    #[arg(long, default_value = "15s", value_parser = parse_wait_duration)]
+    pub grace: Duration,
+
+    /// How often to check usage and cost while the agent runs
+    #[arg(long, default_value = "1m", value_parser = parse_wait_duration)]
+    pub interval: Duration,
+
+    /// Data source: auto, web, cli, oauth, local
+    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth", "local"])]
+    pub source: String,
+
+    /// Web fetch timeout in seconds
+    #[arg(long = "web-timeout", default_value = "60")]
+    pub web_timeout: u64,
+
+    /// Print the summary as JSON (to stderr, after the agent exits)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Command to run, after `--`
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+/// The agent was stopped because it crossed a ceiling
+#[derive(Debug, Error)]
+#[error("Stopped {command}: {reason}")]
+pub struct RunLimitReached {
+    pub command: String,
+    pub reason: String,
+}
+
+/// Usage of one window before and after the run
+#[derive(Debug, Serialize, PartialEq)]
+struct WindowDelta {
+    window: &'static str,
+    before: f64,
+    after: f64,
+}
+
+/// What the run consumed
+#[derive(Debug, Serialize)]
+struct RunSummary {
+    provider: String,
+    command: String,
+    duration_secs: i64,
+    exit_code: Option<i32>,
+    windows: Vec<WindowDelta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<String>,
+}
+
+/// Local cost for the provider from the start day through today. Log days
+/// are UTC, so the range is too.
+#[derive(Clone)]
+struct CostProbe {
+    provider: ProviderId,
+    since: NaiveDate,
+    project_aliases: HashMap<String, String>,
+    /// Resolved once; discovery can shell out to WSL
+    roots: LogRoots,
+}
+
+impl CostProbe {
+    fn new(provider: ProviderId, settings: &Settings) -> Self {
+        Self {
+            provider,
+            since: Utc::now().date_naive(),
+            project_aliases: settings.project_aliases.clone(),
+            roots: settings.cost_log_roots(),
+        }
+    }
+
+    /// (dollars, tokens) so far; None for providers without local logs.
+    /// Scans on a blocking thread.
+    async fn totals(&self) -> Option<(f64, u64)> {
+        let probe = self.clone();
+        tokio::task::spawn_blocking(move || probe.scan()).await.ok()?
+    }
+
+    fn scan(self) -> Option<(f64, u64)> {
+        let summary = CostScanner::with_range(self.since, Utc::now().date_naive())
+            .with_project_aliases(self.project_aliases)
+            .with_log_roots(self.roots)
+            .scan(self.provider)?;
+        Some((summary.total_cost_usd, summary.input_tokens + summary.output_tokens))
+    }
+}
+
+/// Run the run command, returning the agent's exit code
+pub async fn run(args: RunArgs) -> anyhow::Result<i32> {
+    let (binary, command_args) = args
+        .command
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("No command given. Use e.g. `codexbar run -- claude`"))?;
+    let provider_id = match &args.provider {
+        Some(name) => ProviderId::from_cli_name(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown provider: '{}'. Use --help to see available providers.", name))?,
+        None => infer_provider(binary)
+            .ok_or_else(|| anyhow::anyhow!("Can't tell which provider '{}' uses. Pass it with -p", binary))?,
+    };
+    if let Some(max) = args.max_usage.filter(|m| *m <= 0.0 || *m > 100.0) {
+        anyhow::bail!("Invalid --max-usage value: {}. Use a percentage between 0 and 100", max);
+    }
+    let command_line = args.command.join(" ");
+
+    let provider: Arc<dyn Provider> = create_provider(provider_id).into();
+    let ctx = FetchContext {
+        source_mode: SourceMode::from_str(&args.source).unwrap_or(SourceMode::Auto),
+        include_credits: false,
+        web_timeout: args.web_timeout,
+        ..FetchContext::default()
+    };
+    let fetch_timeout = Duration::from_secs(args.web_timeout + 30);
+    let costs = CostProbe::new(provider_id, &Settings::load());
+
+    tracing::debug!(
+        "Running run command: provider={}, command={}, max_usage={:?}, max_cost={:?}",
+        provider_id.cli_name(),
+        command_line,
+        args.max_usage,
+        args.max_cost
+    );
+
+    let usage_before = match fetch_usage(provider.as_ref(), &ctx, fetch_timeout).await {
+        Ok(usage) => Some(usage),
+        Err(e) => {
+            eprintln!("codexbar: couldn't read {} usage before the run: {}", provider_id.display_name(), e);
+            None
+        }
+    };
+    let cost_before = costs.totals().await;
+    if args.max_cost.is_some() && cost_before.is_none() {
+        anyhow::bail!(
+            "--max-cost needs local cost logs, which {} doesn't keep",
+            provider_id.display_name()
+        );
+    }
+
+    ignore_ctrl_c()?;
+    let started = Instant::now();
+    let mut child = CommandRunner::new().spawn_attached(binary, command_args, &CommandOptions::default())?;
+    #[cfg(windows)]
+    forward_ctrl_c(child.id())?;
+
+    let mut next_poll = started + args.interval;
+    let mut poll: Option<tokio::task::JoinHandle<(Option<f64>, Option<f64>)>> = None;
+    let mut limit: Option<String> = None;
+    let mut interrupted_at: Option<Instant> = None;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if interrupted_at.is_some_and(|at| at.elapsed() >= args.grace) {
+            eprintln!("codexbar: {} still running after {}s, killing it", binary, args.grace.as_secs());
+            let _ = child.kill();
+            interrupted_at = None;
+        }
+
+        if limit.is_none() && poll.is_none() && Instant::now() >= next_poll {
+            let (provider, ctx, costs) = (Arc::clone(&provider), ctx.clone(), costs.clone());
+            poll = Some(tokio::spawn(poll_limits(provider, ctx, fetch_timeout, costs, args.window, cost_before)));
+        }
+
+        // A slow poll must not hold up noticing the exit or the grace kill
+        let polled = match poll.as_mut() {
+            Some(task) => tokio::select! {
+                result = task => Some(result),
+                _ = tokio::time::sleep(EXIT_POLL_INTERVAL) => None,
+            },
+            None => {
+                tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+                None
+            }
+        };
+        let Some(result) = polled else {
+            continue;
+        };
+        poll = None;
+        next_poll = Instant::now() + args.interval;
+
+        if let Ok((used, run_cost)) = result {
+            limit = limit_reason(&args, used, run_cost);
+            if let Some(ref reason) = limit {
+                eprintln!("codexbar: {}", reason);
+                match args.on_limit.as_str() {
+                    "kill" => {
+                        let _ = child.kill();
+                    }
+                    "interrupt" => match interrupt(child.id()) {
+                        Ok(()) => interrupted_at = Some(Instant::now()),
+                        Err(e) => {
+                            eprintln!("codexbar: couldn't interrupt {} ({}), killing it", binary, e);
+                            let _ = child.kill();
+                        }
+                    },
+                    _ => {}
+                }
+            }
+        }
+    };
+    if let Some(task) = poll {
+        task.abort();
+    }
+
+    let elapsed = started.elapsed();
+    let usage_after = fetch_usage(provider.as_ref(), &ctx, fetch_timeout).await.ok();
+    let cost = cost_delta(cost_before, costs.totals().await);
+
+    let summary = RunSummary {
+        provider: provider_id.cli_name().to_string(),
+        command: command_line.clone(),
+        duration_secs: elapsed.as_secs() as i64,
+        exit_code: status.code(),
+        windows: window_deltas(usage_before.as_ref(), usage_after.as_ref()),
+        cost_usd: cost.map(|(cost, _)| cost),
+        tokens: cost.map(|(_, tokens)| tokens),
+        limit: limit.clone(),
+    };
+    print_summary(&summary, provider_id, args.json)?;
+
+    match limit {
+        Some(reason) if args.on_limit != "warn" => Err(RunLimitReached {
+            command: command_line,
+            reason,
+        }
+        .into()),
+        _ => Ok(status.code().unwrap_or(1)),
+    }
+}
+
+/// Provider for a well-known agent binary (`claude`, `codex.cmd`, ...)
+fn infer_provider(binary: &str) -> Option<ProviderId> {
+    let stem = Path::new(binary).file_stem()?.to_str()?.to_lowercase();
+    ProviderId::from_cli_name(&stem)
+}
+
+/// Which ceiling, if any, the latest numbers cross
+fn limit_reason(args: &RunArgs, used_percent: Option<f64>, run_cost: Option<f64>) -> Option<String> {
+    if let (Some(max), Some(used)) = (args.max_usage, used_percent) {
+        if used >= max {
+            return Some(format!("{} usage at {:.0}% (limit {:.0}%)", args.window.name(), used, max));
+        }
+    }
+    if let (Some(max), Some(cost)) = (args.max_cost, run_cost) {
+        if cost >= max {
+            return Some(format!("run cost ${:.2} (limit ${:.2})", cost, max));
+        }
+    }
+    None
+}
+
+/// Dollars and tokens added since `before`
+fn cost_delta(before: Option<(f64, u64)>, after: Option<(f64, u64)>) -> Option<(f64, u64)> {
+    let (before_cost, before_tokens) = before.unwrap_or_default();
+    let (after_cost, after_tokens) = after?;
+    Some(((after_cost - before_cost).max(0.0), after_tokens.saturating_sub(before_tokens)))
+}
+
+/// Windows reported both before and after the run
+fn window_deltas(before: Option<&UsageSnapshot>, after: Option<&UsageSnapshot>) -> Vec<WindowDelta> {
+    let (Some(before), Some(after)) = (before, after) else {
+        return Vec::new();
+    };
+    [UsageWindow::Session, UsageWindow::Weekly, UsageWindow::Model]
+        .into_iter()
+        .filter_map(|kind| {
+            Some(WindowDelta {
+                window: kind.name(),
+                before: before.window(kind)?.used_percent,
+                after: after.window(kind)?.used_percent,
+            })
+        })
+        .collect()
+}
+
+/// Window usage and run cost so far, for the limit check
+async fn poll_limits(
+    provider: Arc<dyn Provider>,
+    ctx: FetchContext,
+    timeout: Duration,
+    costs: CostProbe,
+    window: UsageWindow,
+    cost_before: Option<(f64, u64)>,
+) -> (Option<f64>, Option<f64>) {
+    let used = match fetch_usage(provider.as_ref(), &ctx, timeout).await {
+        Ok(usage) => usage.window(window).map(|w| w.used_percent),
+        Err(e) => {
+            tracing::debug!("Usage poll failed: {}", e);
+            None
+        }
+    };
+    let run_cost = cost_delta(cost_before, costs.totals().await).map(|(cost, _)| cost);
+    (used, run_cost)
+}
+
+async fn fetch_usage(provider: &dyn Provider, ctx: &FetchContext, timeout: Duration) -> anyhow::Result<UsageSnapshot> {
+    match tokio::time::timeout(timeout, provider.fetch_usage(ctx)).await {
+        Ok(result) => Ok(result?.usage),
+        Err(_) => anyhow::bail!("fetch timed out after {}s", timeout.as_secs()),
+    }
+}
+
+/// Ctrl+C is meant for the agent, so keep it from ending the supervisor
+/// before the summary
+fn ignore_ctrl_c() -> std::io::Result<()> {
+    #[cfg(windows)]
+    let mut signals = tokio::signal::windows::ctrl_c()?;
+    #[cfg(not(windows))]
+    let mut signals = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())?;
+
+    tokio::spawn(async move { while signals.recv().await.is_some() {} });
+    Ok(())
+}
+
+/// The agent runs in its own process group on Windows, which the console's
+/// Ctrl+C doesn't reach, so pass it on
+#[cfg(windows)]
+fn forward_ctrl_c(pid: u32) -> std::io::Result<()> {
+    let mut signals = tokio::signal::windows::ctrl_c()?;
+    tokio::spawn(async move {
+        while signals.recv().await.is_some() {
+            let _ = interrupt(pid);
+        }
+    });
+    Ok(())
+}
+
+fn print_summary(summary: &RunSummary, provider: ProviderId, json: bool) -> anyhow::Result<()> {
+    if json {
+        eprintln!("{}", serde_json::to_string(summary)?);
+        return Ok(());
+    }
+
+    let exit = summary
+        .exit_code
+        .map(|code| format!("exit {}", code))
+        .unwrap_or_else(|| "terminated".to_string());
+    eprintln!();
+    eprintln!(
+        "codexbar: {} finished in {} ({})",
+        summary.command,
+        format_duration(chrono::Duration::seconds(summary.duration_secs)),
+        exit
+    );
+    for delta in &summary.windows {
+        eprintln!(
+            "  {} {}: {:.0}% -> {:.0}% ({:+.0}%)",
+            provider.display_name(),
+            delta.window,
+            delta.before,
+            delta.after,
+            delta.after - delta.before
+        );
+    }
+    if let (Some(cost), Some(tokens)) = (summary.cost_usd, summary.tokens) {
+        eprintln!("  Cost: ${:.2} ({} tokens, local logs)", cost, tokens);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        run: RunArgs,
+    }
+
+    #[test]
+    fn test_infer_provider() {
+        assert_eq!(infer_provider("claude"), Some(ProviderId::Claude));
+        assert_eq!(infer_provider("C:\\tools\\codex.cmd"), Some(ProviderId::Codex));
+        assert_eq!(infer_provider("/usr/local/bin/gemini"), Some(ProviderId::Gemini));
+        assert_eq!(infer_provider("python"), None);
+    }
+
+    #[test]
+    fn test_command_after_separator() {
+        let cli = TestCli::parse_from(["run", "--max-cost", "5", "--", "claude", "-p", "fix the build"]);
+        assert_eq!(cli.run.command, vec!["claude", "-p", "fix the build"]);
+        assert_eq!(cli.run.max_cost, Some(5.0));
+    }
+
+    #[test]
+    fn test_limit_reason() {
+        let cli = TestCli::parse_from(["run", "--max-usage", "90", "--max-cost", "2.50", "--", "claude"]);
+        assert_eq!(limit_reason(&cli.run, Some(80.0), Some(1.0)), None);
+        assert_eq!(limit_reason(&cli.run, None, None), None);
+        assert!(limit_reason(&cli.run, Some(92.0), None).unwrap().contains("session usage at 92%"));
+        assert!(limit_reason(&cli.run, Some(10.0), Some(2.5)).unwrap().contains("$2.50"));
+    }
+
+    #[test]
+    fn test_cost_delta() {
+        assert_eq!(cost_delta(Some((1.0, 100)), Some((3.5, 400))), Some((2.5, 300)));
+        assert_eq!(cost_delta(None, Some((1.0, 50))), Some((1.0, 50)));
+        assert_eq!(cost_delta(Some((1.0, 100)), None), None);
+    }
+}
@@ -43,8 +43,8 @@ pub struct UsageArgs {
     #[arg(long)]
     pub status: bool,
 
-    /// Data source: auto, oauth, web, cli
-    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth"])]
+    /// Data source: auto, oauth, web, cli, local
+    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth", "local"])]
     pub source: String,
 
     /// Web fetch timeout in seconds
@@ -132,7 +132,7 @@ struct ErrorPayload {
 }
 
 /// Create a provider instance by ID
-fn create_provider(id: ProviderId) -> Box<dyn Provider> {
+pub fn create_provider(id: ProviderId) -> Box<dyn Provider> {
     match id {
         ProviderId::Claude => Box::new(ClaudeProvider::new()),
         ProviderId::Codex => Box::new(CodexProvider::new()),
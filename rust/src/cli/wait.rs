@@ -0,0 +1,325 @@
+//! Wait command implementation
+//!
+//! Blocks until a provider's usage window drops below a threshold, so agent
+//! scripts can run `codexbar wait -p claude --below 90 && claude ...`.
+//! Polls with exponential backoff and schedules a check just after the
+//! window's reset time.
+
+use chrono::{DateTime, Local, Utc};
+use clap::Args;
+use std::time::Duration;
+use thiserror::Error;
+
+use super::usage::create_provider;
+use crate::core::{FetchContext, ProviderId, RateWindow, SourceMode, UsageWindow};
+
+/// Longest pause between polls
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Extra time after a reset before checking, so the provider has caught up
+const RESET_GRACE: Duration = Duration::from_secs(30);
+
+/// Arguments for the wait command
+#[derive(Args, Debug)]
+pub struct WaitArgs {
+    /// Provider to wait for (claude, codex, ...)
+    #[arg(short, long, default_value = "claude")]
+    pub provider: String,
+
+    /// Window to watch: session, weekly or model
+    #[arg(short, long, default_value = "session")]
+    pub window: UsageWindow,
+
+    /// Wait until usage is below this percentage (default: until the window
+    /// is no longer exhausted)
+    #[arg(long, default_value = "100")]
+    pub below: f64,
+
+    /// Give up after this long (e.g. 90m, 6h, 1h30m)
+    #[arg(long = "max-wait", default_value = "12h", value_parser = parse_wait_duration)]
+    pub max_wait: Duration,
+
+    /// First poll interval; doubles while waiting, up to 15 minutes
+    #[arg(long, default_value = "1m", value_parser = parse_wait_duration)]
+    pub interval: Duration,
+
+    /// Data source: auto, web, cli, oauth, local
+    #[arg(long, default_value = "auto", value_parser = ["auto", "web", "cli", "oauth", "local"])]
+    pub source: String,
+
+    /// Web fetch timeout in seconds
+    #[arg(long = "web-timeout", default_value = "60")]
+    pub web_timeout: u64,
+
+    /// Print the final result as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Don't print progress to stderr
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+/// The window stayed above the threshold for the whole `--max-wait`
+#[derive(Debug, Error)]
+#[error("Timed out after {waited} waiting for {provider} {window} usage below {below}%")]
+pub struct WaitTimedOut {
+    pub provider: String,
+    pub window: String,
+    pub below: f64,
+    pub waited: String,
+}
+
+/// Run the wait command
+pub async fn run(args: WaitArgs) -> anyhow::Result<()> {
+    let Some(provider_id) = ProviderId::from_cli_name(&args.provider) else {
+        anyhow::bail!("Unknown provider: '{}'. Use --help to see available providers.", args.provider);
+    };
+    if args.below <= 0.0 || args.below > 100.0 {
+        anyhow::bail!("Invalid --below value: {}. Use a percentage between 0 and 100", args.below);
+    }
+
+    let provider = create_provider(provider_id);
+    let ctx = FetchContext {
+        source_mode: SourceMode::from_str(&args.source).unwrap_or(SourceMode::Auto),
+        include_credits: false,
+        web_timeout: args.web_timeout,
+        ..FetchContext::default()
+    };
+    let fetch_timeout = Duration::from_secs(args.web_timeout + 30);
+    let label = format!("{} {}", provider_id.display_name(), args.window.name());
+
+    tracing::debug!(
+        "Running wait command: provider={}, window={}, below={}, max_wait={:?}",
+        provider_id.cli_name(),
+        args.window.name(),
+        args.below,
+        args.max_wait
+    );
+
+    let started = Utc::now();
+    let deadline = started + chrono::Duration::from_std(args.max_wait)?;
+    let mut backoff = args.interval.max(Duration::from_secs(1));
+    let mut polls = 0u32;
+
+    loop {
+        let fetched = match tokio::time::timeout(fetch_timeout, provider.fetch_usage(&ctx)).await {
+            Ok(result) => result.map_err(anyhow::Error::from),
+            Err(_) => Err(anyhow::anyhow!("fetch timed out after {}s", fetch_timeout.as_secs())),
+        };
+        polls += 1;
+
+        let window = match fetched {
+            Ok(result) => match result.usage.window(args.window) {
+                Some(window) => Some(window.clone()),
+                None => anyhow::bail!("{} doesn't report a {} window", provider_id.display_name(), args.window.name()),
+            },
+            // A misconfigured provider fails right away instead of after hours
+            Err(e) if polls == 1 => return Err(e),
+            Err(e) => {
+                if !args.quiet {
+                    eprintln!("{}: {} (retrying)", label, e);
+                }
+                None
+            }
+        };
+
+        let now = Utc::now();
+        if let Some(ref window) = window {
+            if window.used_percent < args.below {
+                print_result(&args, provider_id, window, now - started, true)?;
+                return Ok(());
+            }
+        }
+
+        if now >= deadline {
+            if let Some(ref window) = window {
+                if args.json {
+                    print_result(&args, provider_id, window, now - started, false)?;
+                }
+            }
+            return Err(WaitTimedOut {
+                provider: provider_id.display_name().to_string(),
+                window: args.window.name().to_string(),
+                below: args.below,
+                waited: format_duration(now - started),
+            }
+            .into());
+        }
+
+        let resets_at = window.as_ref().and_then(|w| w.resets_at);
+        let (delay, until_reset) = next_delay(now, resets_at, backoff, deadline);
+        if !args.quiet {
+            if let Some(ref window) = window {
+                eprintln!("{}", progress_line(&label, window, args.below, now + delay));
+            }
+        }
+
+        tokio::time::sleep(delay).await;
+        // After sleeping until a reset, start over with short polls
+        backoff = if until_reset {
+            args.interval.max(Duration::from_secs(1))
+        } else {
+            (backoff * 2).min(MAX_POLL_INTERVAL.max(args.interval))
+        };
+    }
+}
+
+/// How long to sleep before the next poll: the current backoff, cut short
+/// to land just after an upcoming reset and never past the deadline.
+/// Returns whether the sleep ends at the reset.
+fn next_delay(
+    now: DateTime<Utc>,
+    resets_at: Option<DateTime<Utc>>,
+    backoff: Duration,
+    deadline: DateTime<Utc>,
+) -> (Duration, bool) {
+    let mut delay = backoff;
+    let mut until_reset = false;
+
+    if let Some(reset_in) = resets_at.and_then(|r| (r - now).to_std().ok()) {
+        let reset_delay = reset_in + RESET_GRACE;
+        if reset_delay <= delay {
+            delay = reset_delay;
+            until_reset = true;
+        }
+    }
+
+    let remaining = (deadline - now).to_std().unwrap_or_default();
+    if remaining < delay {
+        (remaining, false)
+    } else {
+        (delay, until_reset)
+    }
+}
+
+fn progress_line(label: &str, window: &RateWindow, below: f64, next_check: DateTime<Utc>) -> String {
+    let reset = window
+        .format_countdown()
+        .map(|c| format!(", resets in {}", c))
+        .unwrap_or_default();
+    format!(
+        "{}: {:.0}% used (waiting for < {:.0}%){}; next check at {}",
+        label,
+        window.used_percent,
+        below,
+        reset,
+        next_check.with_timezone(&Local).format("%H:%M:%S")
+    )
+}
+
+fn print_result(
+    args: &WaitArgs,
+    provider: ProviderId,
+    window: &RateWindow,
+    waited: chrono::Duration,
+    available: bool,
+) -> anyhow::Result<()> {
+    if args.json {
+        let payload = serde_json::json!({
+            "provider": provider.cli_name(),
+            "window": args.window.name(),
+            "available": available,
+            "used_percent": window.used_percent,
+            "below": args.below,
+            "resets_at": window.resets_at,
+            "waited_secs": waited.num_seconds(),
+        });
+        println!("{}", serde_json::to_string(&payload)?);
+    } else if !args.quiet {
+        println!(
+            "{} {}: {:.0}% used, available (waited {})",
+            provider.display_name(),
+            args.window.name(),
+            window.used_percent,
+            format_duration(waited)
+        );
+    }
+    Ok(())
+}
+
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (hours, minutes) = (secs / 3600, (secs % 3600) / 60);
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Parse durations like `90`, `45s`, `15m`, `6h`, `1d` or `1h30m`
+/// (a bare number is seconds)
+pub fn parse_wait_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration: {}. Use e.g. 90s, 15m, 6h or 1h30m", s);
+    let s = s.trim().to_lowercase();
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        total += value
+            * match c {
+                's' => 1,
+                'm' => 60,
+                'h' => 3600,
+                'd' => 86400,
+                _ => return Err(invalid()),
+            };
+    }
+    if !number.is_empty() || s.is_empty() {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wait_duration() {
+        assert_eq!(parse_wait_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_wait_duration("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_wait_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_wait_duration("1d"), Ok(Duration::from_secs(86400)));
+        assert!(parse_wait_duration("5x").is_err());
+        assert!(parse_wait_duration("h").is_err());
+        assert!(parse_wait_duration("").is_err());
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let now = Utc::now();
+        let deadline = now + chrono::Duration::hours(6);
+        let backoff = Duration::from_secs(600);
+
+        // No reset in sight: plain backoff
+        assert_eq!(next_delay(now, None, backoff, deadline), (backoff, false));
+
+        // Reset in 2 minutes: wake just after it
+        let reset = now + chrono::Duration::minutes(2);
+        assert_eq!(
+            next_delay(now, Some(reset), backoff, deadline),
+            (Duration::from_secs(120) + RESET_GRACE, true)
+        );
+
+        // Reset already passed: keep backing off
+        let past = now - chrono::Duration::minutes(1);
+        assert_eq!(next_delay(now, Some(past), backoff, deadline), (backoff, false));
+
+        // Never sleep past the deadline
+        let soon = now + chrono::Duration::seconds(100);
+        assert_eq!(next_delay(now, None, backoff, soon), (Duration::from_secs(100), false));
+    }
+}
@@ -0,0 +1,244 @@
+//! Spending Budgets
+//!
+//! Daily, weekly or monthly USD limits, either for one provider or across
+//! all providers. Spend comes from the daily series of local cost scans or,
+//! for providers without local logs, from the `CostSnapshot` they report.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::CostSnapshot;
+
+/// Budget period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    Daily,
+    /// Monday through Sunday
+    Weekly,
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Daily => "daily",
+            BudgetPeriod::Weekly => "weekly",
+            BudgetPeriod::Monthly => "monthly",
+        }
+    }
+
+    /// First day of the period containing `today`
+    pub fn start(&self, today: NaiveDate) -> NaiveDate {
+        match self {
+            BudgetPeriod::Daily => today,
+            BudgetPeriod::Weekly => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+            BudgetPeriod::Monthly => today.with_day(1).unwrap_or(today),
+        }
+    }
+
+    /// Whether a provider-reported period ("Monthly", "daily", ...) is this one
+    fn matches_label(&self, label: &str) -> bool {
+        let label = label.to_lowercase();
+        match self {
+            BudgetPeriod::Daily => label.contains("day") || label.contains("daily"),
+            BudgetPeriod::Weekly => label.contains("week"),
+            BudgetPeriod::Monthly => label.contains("month"),
+        }
+    }
+}
+
+impl std::str::FromStr for BudgetPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" | "day" => Ok(BudgetPeriod::Daily),
+            "weekly" | "week" => Ok(BudgetPeriod::Weekly),
+            "monthly" | "month" => Ok(BudgetPeriod::Monthly),
+            _ => Err(format!("Invalid budget period: {}. Use daily, weekly or monthly", s)),
+        }
+    }
+}
+
+/// A spending limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    /// Provider CLI name, or None for all providers combined
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    pub period: BudgetPeriod,
+    pub limit_usd: f64,
+}
+
+impl Budget {
+    /// "Claude monthly" / "Overall daily"
+    pub fn label(&self) -> String {
+        let scope = match self.provider {
+            Some(ref provider) => {
+                let mut chars = provider.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            None => "Overall".to_string(),
+        };
+        format!("{} {}", scope, self.period.name())
+    }
+}
+
+/// A budget checked against current spend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    pub period_start: NaiveDate,
+    pub spent_usd: f64,
+    /// Spend as a percentage of the limit (may exceed 100)
+    pub percent: f64,
+}
+
+impl BudgetStatus {
+    pub fn is_over(&self) -> bool {
+        self.spent_usd > self.budget.limit_usd
+    }
+
+    /// Highest alert percentage this budget has reached
+    pub fn crossed_threshold(&self, thresholds: &[f64]) -> Option<f64> {
+        thresholds
+            .iter()
+            .copied()
+            .filter(|t| self.percent >= *t)
+            .fold(None, |max, t| Some(max.map_or(t, |m: f64| m.max(t))))
+    }
+}
+
+/// Spend data that budgets are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct BudgetSpend {
+    /// Provider -> daily (date, cost) series from local logs
+    daily: HashMap<String, Vec<(String, f64)>>,
+    /// Provider -> cost reported by the provider
+    snapshots: HashMap<String, CostSnapshot>,
+}
+
+impl BudgetSpend {
+    pub fn add_daily(&mut self, provider: &str, series: Vec<(String, f64)>) {
+        self.daily.insert(provider.to_string(), series);
+    }
+
+    /// Provider-reported cost; only USD snapshots are used
+    pub fn add_snapshot(&mut self, provider: &str, snapshot: CostSnapshot) {
+        if snapshot.currency_code.eq_ignore_ascii_case("USD") {
+            self.snapshots.insert(provider.to_string(), snapshot);
+        }
+    }
+
+    /// Spend for one provider in the period containing `today`. Local logs
+    /// win over snapshots, which only count when their period matches.
+    pub fn provider_spent(&self, provider: &str, period: BudgetPeriod, today: NaiveDate) -> f64 {
+        if let Some(series) = self.daily.get(provider) {
+            let since = period.start(today).to_string();
+            let until = today.to_string();
+            return series
+                .iter()
+                .filter(|(date, _)| *date >= since && *date <= until)
+                .map(|(_, cost)| cost)
+                .sum();
+        }
+
+        self.snapshots
+            .get(provider)
+            .filter(|s| period.matches_label(&s.period))
+            .map_or(0.0, |s| s.used)
+    }
+
+    /// Spend a budget covers: its provider, or every provider with data
+    pub fn spent(&self, budget: &Budget, today: NaiveDate) -> f64 {
+        match budget.provider {
+            Some(ref provider) => self.provider_spent(provider, budget.period, today),
+            None => {
+                let mut providers: Vec<&String> = self.daily.keys().chain(self.snapshots.keys()).collect();
+                providers.sort();
+                providers.dedup();
+                providers
+                    .into_iter()
+                    .map(|p| self.provider_spent(p, budget.period, today))
+                    .sum()
+            }
+        }
+    }
+
+    /// Evaluate budgets with a positive limit
+    pub fn evaluate(&self, budgets: &[Budget], today: NaiveDate) -> Vec<BudgetStatus> {
+        budgets
+            .iter()
+            .filter(|b| b.limit_usd > 0.0)
+            .map(|budget| {
+                let spent_usd = self.spent(budget, today);
+                BudgetStatus {
+                    budget: budget.clone(),
+                    period_start: budget.period.start(today),
+                    spent_usd,
+                    percent: spent_usd / budget.limit_usd * 100.0,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(provider: Option<&str>, period: BudgetPeriod, limit_usd: f64) -> Budget {
+        Budget {
+            provider: provider.map(str::to_string),
+            period,
+            limit_usd,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_budgets() {
+        // Sunday; the week started Monday 2026-10-12
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut spend = BudgetSpend::default();
+        spend.add_daily(
+            "claude",
+            vec![
+                ("2026-09-30".to_string(), 50.0),
+                ("2026-10-11".to_string(), 20.0),
+                ("2026-10-12".to_string(), 10.0),
+                ("2026-10-18".to_string(), 5.0),
+            ],
+        );
+        spend.add_snapshot("cursor", CostSnapshot::new(12.0, "USD", "Monthly"));
+        spend.add_snapshot("copilot", CostSnapshot::new(99.0, "EUR", "Monthly"));
+
+        let budgets = vec![
+            budget(Some("claude"), BudgetPeriod::Daily, 10.0),
+            budget(Some("claude"), BudgetPeriod::Weekly, 10.0),
+            budget(None, BudgetPeriod::Monthly, 100.0),
+            budget(Some("cursor"), BudgetPeriod::Daily, 1.0),
+            budget(None, BudgetPeriod::Daily, 0.0),
+        ];
+        let statuses = spend.evaluate(&budgets, today);
+
+        assert_eq!(statuses.len(), 4);
+        assert!((statuses[0].spent_usd - 5.0).abs() < 1e-9);
+        assert!(!statuses[0].is_over());
+        assert_eq!(statuses[1].period_start, NaiveDate::from_ymd_opt(2026, 10, 12).unwrap());
+        assert!(statuses[1].is_over());
+        // Claude's October days plus Cursor's monthly snapshot; EUR is ignored
+        assert!((statuses[2].spent_usd - 47.0).abs() < 1e-9);
+        // A monthly snapshot says nothing about today
+        assert_eq!(statuses[3].spent_usd, 0.0);
+
+        assert_eq!(statuses[1].crossed_threshold(&[50.0, 80.0, 100.0]), Some(100.0));
+        assert_eq!(statuses[0].crossed_threshold(&[80.0, 100.0]), None);
+        assert_eq!(statuses[2].budget.label(), "Overall monthly");
+        assert_eq!(statuses[0].budget.label(), "Claude daily");
+    }
+}
@@ -0,0 +1,239 @@
+//! Cost Forecast
+//!
+//! Projects month-to-date spend to the end of the month and flags unusual
+//! days, working on the daily (date, cost) series that feeds the cost
+//! history chart. The projection uses per-weekday averages so a month
+//! with quiet weekends isn't overestimated from a busy Monday.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+
+/// Days of history used as the forecast baseline
+pub const FORECAST_BASELINE_DAYS: i64 = 28;
+
+/// Completed days of spend needed before a forecast is made
+pub const MIN_FORECAST_BASELINE_DAYS: u32 = 7;
+
+/// Projected spend for the current month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthForecast {
+    pub month_start: NaiveDate,
+    pub month_end: NaiveDate,
+    /// Spend from the first of the month through today
+    pub month_to_date_usd: f64,
+    /// Expected spend for the rest of the month
+    pub remaining_usd: f64,
+    /// Month-to-date plus remaining
+    pub projected_usd: f64,
+    /// Completed days the weekday averages were built from
+    pub baseline_days: u32,
+}
+
+/// A day whose cost is far above its trailing baseline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostAnomaly {
+    pub date: String,
+    pub cost_usd: f64,
+    /// Mean cost of the preceding days
+    pub baseline_usd: f64,
+    /// Standard deviations above the baseline
+    pub z_score: f64,
+}
+
+/// Thresholds for anomaly detection
+#[derive(Debug, Clone, Copy)]
+pub struct AnomalyOptions {
+    /// Number of preceding days in the baseline
+    pub window: usize,
+    /// Minimum preceding days before a day can be judged
+    pub min_baseline_days: usize,
+    /// Standard deviations above the mean that count as unusual
+    pub z_threshold: f64,
+    /// Days cheaper than this are never flagged
+    pub min_cost_usd: f64,
+}
+
+impl Default for AnomalyOptions {
+    fn default() -> Self {
+        Self {
+            window: 14,
+            min_baseline_days: 7,
+            z_threshold: 3.0,
+            min_cost_usd: 1.0,
+        }
+    }
+}
+
+/// Project spend to the end of `today`'s month from a daily cost series.
+/// The baseline starts at the first day with any spend, so days before
+/// usage began don't drag the averages down. Returns None when fewer than
+/// `MIN_FORECAST_BASELINE_DAYS` completed days are left to learn from.
+pub fn forecast_month_end(series: &[(String, f64)], today: NaiveDate) -> Option<MonthForecast> {
+    let days: Vec<(NaiveDate, f64)> = series
+        .iter()
+        .filter_map(|(date, cost)| Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, *cost)))
+        .collect();
+
+    let month_start = today.with_day(1)?;
+    let month_end = last_day_of_month(today)?;
+    let first_spend = days.iter().filter(|(_, cost)| *cost > 0.0).map(|(date, _)| *date).min()?;
+    let baseline_start = (today - Duration::days(FORECAST_BASELINE_DAYS)).max(first_spend);
+
+    let mut weekday_totals = [(0.0f64, 0u32); 7];
+    let mut month_to_date_usd = 0.0;
+    let mut today_usd = 0.0;
+    for &(date, cost) in &days {
+        if date >= month_start && date <= today {
+            month_to_date_usd += cost;
+        }
+        if date == today {
+            today_usd += cost;
+        } else if date >= baseline_start && date < today {
+            let slot = &mut weekday_totals[date.weekday().num_days_from_monday() as usize];
+            slot.0 += cost;
+            slot.1 += 1;
+        }
+    }
+
+    let baseline_days: u32 = weekday_totals.iter().map(|(_, n)| n).sum();
+    if baseline_days < MIN_FORECAST_BASELINE_DAYS {
+        return None;
+    }
+    let overall = weekday_totals.iter().map(|(c, _)| c).sum::<f64>() / baseline_days as f64;
+    let expected = |date: NaiveDate| {
+        let (cost, count) = weekday_totals[date.weekday().num_days_from_monday() as usize];
+        if count > 0 {
+            cost / count as f64
+        } else {
+            overall
+        }
+    };
+
+    // Today is partly spent; only the rest of its usual cost is still ahead
+    let mut remaining_usd = (expected(today) - today_usd).max(0.0);
+    let mut date = today + Duration::days(1);
+    while date <= month_end {
+        remaining_usd += expected(date);
+        date += Duration::days(1);
+    }
+
+    Some(MonthForecast {
+        month_start,
+        month_end,
+        month_to_date_usd,
+        remaining_usd,
+        projected_usd: month_to_date_usd + remaining_usd,
+        baseline_days,
+    })
+}
+
+/// Days in a date-sorted daily series whose cost is a spike against the
+/// preceding `options.window` days. Only unusually high spend is flagged.
+pub fn detect_cost_anomalies(series: &[(String, f64)], options: &AnomalyOptions) -> Vec<CostAnomaly> {
+    let mut anomalies = Vec::new();
+
+    for (i, (date, cost)) in series.iter().enumerate() {
+        let baseline = &series[i.saturating_sub(options.window)..i];
+        if baseline.len() < options.min_baseline_days.max(1) || *cost < options.min_cost_usd {
+            continue;
+        }
+
+        let n = baseline.len() as f64;
+        let mean = baseline.iter().map(|(_, c)| c).sum::<f64>() / n;
+        let variance = baseline.iter().map(|(_, c)| (c - mean).powi(2)).sum::<f64>() / n;
+        // A perfectly flat baseline would make any change infinitely unusual
+        let std_dev = variance.sqrt().max(mean * 0.1).max(0.01);
+
+        let z_score = (cost - mean) / std_dev;
+        if z_score >= options.z_threshold {
+            anomalies.push(CostAnomaly {
+                date: date.clone(),
+                cost_usd: *cost,
+                baseline_usd: mean,
+                z_score,
+            });
+        }
+    }
+
+    anomalies
+}
+
+fn last_day_of_month(date: NaiveDate) -> Option<NaiveDate> {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).map(|d| d - Duration::days(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(start: NaiveDate, costs: &[f64]) -> Vec<(String, f64)> {
+        costs
+            .iter()
+            .enumerate()
+            .map(|(i, c)| ((start + Duration::days(i as i64)).to_string(), *c))
+            .collect()
+    }
+
+    #[test]
+    fn test_forecast_is_weekday_aware() {
+        // Four weeks of $10 weekdays and free weekends, starting Monday 2026-09-21
+        let start = NaiveDate::from_ymd_opt(2026, 9, 21).unwrap();
+        let week = [10.0, 10.0, 10.0, 10.0, 10.0, 0.0, 0.0];
+        let mut costs: Vec<f64> = week.iter().cycle().take(28).copied().collect();
+        // Today (Monday 2026-10-19) has $4 so far
+        costs.push(4.0);
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+
+        let forecast = forecast_month_end(&series(start, &costs), today).unwrap();
+        assert_eq!(forecast.month_end, NaiveDate::from_ymd_opt(2026, 10, 31).unwrap());
+        assert_eq!(forecast.baseline_days, 28);
+        // Oct 1-18: 12 weekdays at $10, plus $4 today
+        assert!((forecast.month_to_date_usd - 124.0).abs() < 1e-9);
+        // $6 left today, 9 more weekdays (Oct 20-23, 26-30), weekends free
+        assert!((forecast.remaining_usd - 96.0).abs() < 1e-9);
+        assert!((forecast.projected_usd - 220.0).abs() < 1e-9);
+
+        assert_eq!(forecast_month_end(&series(today, &[4.0]), today), None);
+    }
+
+    #[test]
+    fn test_forecast_baseline_starts_at_first_spend() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let start = today - Duration::days(FORECAST_BASELINE_DAYS);
+
+        // Over three weeks of nothing, then five days of $10: too little to go on
+        let mut costs = vec![0.0; 23];
+        costs.extend([10.0; 5]);
+        costs.push(4.0);
+        assert_eq!(forecast_month_end(&series(start, &costs), today), None);
+
+        // Three weeks of nothing, then a week of $10 days
+        let mut costs = vec![0.0; 21];
+        costs.extend([10.0; 7]);
+        costs.push(4.0);
+        let forecast = forecast_month_end(&series(start, &costs), today).unwrap();
+        assert_eq!(forecast.baseline_days, 7);
+        // $6 left today, then 12 more days at $10, not diluted by the idle weeks
+        assert!((forecast.remaining_usd - 126.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detect_cost_anomalies() {
+        let start = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let costs = [5.0, 6.0, 4.0, 5.0, 5.5, 4.5, 5.0, 6.0, 40.0, 5.0, 0.5];
+        let anomalies = detect_cost_anomalies(&series(start, &costs), &AnomalyOptions::default());
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].date, "2026-10-09");
+        assert!((anomalies[0].baseline_usd - 5.125).abs() < 1e-9);
+
+        // Not enough history to judge
+        let short = detect_cost_anomalies(&series(start, &[1.0, 50.0]), &AnomalyOptions::default());
+        assert!(short.is_empty());
+    }
+}
@@ -1,181 +1,432 @@
 //! Cost Usage Pricing
 //!
-//! Model-specific token pricing for Codex (OpenAI) and Claude (Anthropic) models.
+//! Model-specific token pricing for Codex (OpenAI), Claude (Anthropic) and
+//! Gemini (Google) models.
 //! Supports tiered pricing for models with token thresholds.
+//!
+//! Prices come from the built-in table in `assets/pricing.toml`, overlaid by
+//! an optional user file (`pricing.toml` or `pricing.json` in the CodexBar
+//! config directory). Entries can carry aliases and effective-date ranges so
+//! historical days are priced at the rates that applied then.
 
 #![allow(dead_code)]
 
-use std::collections::HashMap;
-use std::sync::LazyLock;
-
-/// Codex (OpenAI) model pricing
-#[derive(Debug, Clone, Copy)]
-pub struct CodexPricing {
-    /// Cost per input token in USD
-    pub input_cost_per_token: f64,
-    /// Cost per output token in USD
-    pub output_cost_per_token: f64,
-    /// Cost per cached input token in USD
-    pub cache_read_input_cost_per_token: f64,
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use thiserror::Error;
+
+/// Built-in pricing table
+const DEFAULT_PRICING: &str = include_str!("../../assets/pricing.toml");
+
+/// Tokens per pricing unit (prices are per million tokens)
+const TOKENS_PER_UNIT: f64 = 1_000_000.0;
+
+/// Active pricing table (built-in plus user overrides)
+static PRICING: LazyLock<RwLock<PricingTable>> = LazyLock::new(|| RwLock::new(PricingTable::load()));
+
+/// Pricing errors
+#[derive(Debug, Error)]
+pub enum PricingError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse {path}: {message}")]
+    Parse { path: PathBuf, message: String },
 }
 
-/// Claude (Anthropic) model pricing with optional tiered pricing
-#[derive(Debug, Clone, Copy)]
-pub struct ClaudePricing {
-    /// Cost per input token in USD
-    pub input_cost_per_token: f64,
-    /// Cost per output token in USD
-    pub output_cost_per_token: f64,
-    /// Cost per cache creation input token in USD
-    pub cache_creation_input_cost_per_token: f64,
-    /// Cost per cache read input token in USD
-    pub cache_read_input_cost_per_token: f64,
-    /// Token threshold for tiered pricing (None = no tiering)
-    pub threshold_tokens: Option<i32>,
-    /// Cost per input token above threshold
-    pub input_cost_per_token_above_threshold: Option<f64>,
-    /// Cost per output token above threshold
-    pub output_cost_per_token_above_threshold: Option<f64>,
-    /// Cost per cache creation input token above threshold
-    pub cache_creation_input_cost_per_token_above_threshold: Option<f64>,
-    /// Cost per cache read input token above threshold
-    pub cache_read_input_cost_per_token_above_threshold: Option<f64>,
+/// Which provider's models a price applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PricingFamily {
+    Codex,
+    Claude,
+    Gemini,
 }
 
-/// Codex model pricing table
-static CODEX_PRICING: LazyLock<HashMap<&'static str, CodexPricing>> = LazyLock::new(|| {
-    let mut m = HashMap::new();
-
-    // GPT-5 pricing
-    m.insert("gpt-5", CodexPricing {
-        input_cost_per_token: 1.25e-6,
-        output_cost_per_token: 1e-5,
-        cache_read_input_cost_per_token: 1.25e-7,
-    });
-    m.insert("gpt-5-codex", CodexPricing {
-        input_cost_per_token: 1.25e-6,
-        output_cost_per_token: 1e-5,
-        cache_read_input_cost_per_token: 1.25e-7,
-    });
-    m.insert("gpt-5.1", CodexPricing {
-        input_cost_per_token: 1.25e-6,
-        output_cost_per_token: 1e-5,
-        cache_read_input_cost_per_token: 1.25e-7,
-    });
-    m.insert("gpt-5.2", CodexPricing {
-        input_cost_per_token: 1.75e-6,
-        output_cost_per_token: 1.4e-5,
-        cache_read_input_cost_per_token: 1.75e-7,
-    });
-    m.insert("gpt-5.2-codex", CodexPricing {
-        input_cost_per_token: 1.75e-6,
-        output_cost_per_token: 1.4e-5,
-        cache_read_input_cost_per_token: 1.75e-7,
-    });
-
-    m
-});
-
-/// Claude model pricing table
-static CLAUDE_PRICING: LazyLock<HashMap<&'static str, ClaudePricing>> = LazyLock::new(|| {
-    let mut m = HashMap::new();
-
-    // Haiku 4.5
-    m.insert("claude-haiku-4-5-20251001", ClaudePricing {
-        input_cost_per_token: 1e-6,
-        output_cost_per_token: 5e-6,
-        cache_creation_input_cost_per_token: 1.25e-6,
-        cache_read_input_cost_per_token: 1e-7,
-        threshold_tokens: None,
-        input_cost_per_token_above_threshold: None,
-        output_cost_per_token_above_threshold: None,
-        cache_creation_input_cost_per_token_above_threshold: None,
-        cache_read_input_cost_per_token_above_threshold: None,
-    });
-
-    // Opus 4.5
-    m.insert("claude-opus-4-5-20251101", ClaudePricing {
-        input_cost_per_token: 5e-6,
-        output_cost_per_token: 2.5e-5,
-        cache_creation_input_cost_per_token: 6.25e-6,
-        cache_read_input_cost_per_token: 5e-7,
-        threshold_tokens: None,
-        input_cost_per_token_above_threshold: None,
-        output_cost_per_token_above_threshold: None,
-        cache_creation_input_cost_per_token_above_threshold: None,
-        cache_read_input_cost_per_token_above_threshold: None,
-    });
-
-    // Sonnet 4.5 (with tiered pricing at 200k tokens)
-    m.insert("claude-sonnet-4-5", ClaudePricing {
-        input_cost_per_token: 3e-6,
-        output_cost_per_token: 1.5e-5,
-        cache_creation_input_cost_per_token: 3.75e-6,
-        cache_read_input_cost_per_token: 3e-7,
-        threshold_tokens: Some(200_000),
-        input_cost_per_token_above_threshold: Some(6e-6),
-        output_cost_per_token_above_threshold: Some(2.25e-5),
-        cache_creation_input_cost_per_token_above_threshold: Some(7.5e-6),
-        cache_read_input_cost_per_token_above_threshold: Some(6e-7),
-    });
-    m.insert("claude-sonnet-4-5-20250929", ClaudePricing {
-        input_cost_per_token: 3e-6,
-        output_cost_per_token: 1.5e-5,
-        cache_creation_input_cost_per_token: 3.75e-6,
-        cache_read_input_cost_per_token: 3e-7,
-        threshold_tokens: Some(200_000),
-        input_cost_per_token_above_threshold: Some(6e-6),
-        output_cost_per_token_above_threshold: Some(2.25e-5),
-        cache_creation_input_cost_per_token_above_threshold: Some(7.5e-6),
-        cache_read_input_cost_per_token_above_threshold: Some(6e-7),
-    });
-
-    // Opus 4
-    m.insert("claude-opus-4-20250514", ClaudePricing {
-        input_cost_per_token: 1.5e-5,
-        output_cost_per_token: 7.5e-5,
-        cache_creation_input_cost_per_token: 1.875e-5,
-        cache_read_input_cost_per_token: 1.5e-6,
-        threshold_tokens: None,
-        input_cost_per_token_above_threshold: None,
-        output_cost_per_token_above_threshold: None,
-        cache_creation_input_cost_per_token_above_threshold: None,
-        cache_read_input_cost_per_token_above_threshold: None,
-    });
-    m.insert("claude-opus-4-1", ClaudePricing {
-        input_cost_per_token: 1.5e-5,
-        output_cost_per_token: 7.5e-5,
-        cache_creation_input_cost_per_token: 1.875e-5,
-        cache_read_input_cost_per_token: 1.5e-6,
-        threshold_tokens: None,
-        input_cost_per_token_above_threshold: None,
-        output_cost_per_token_above_threshold: None,
-        cache_creation_input_cost_per_token_above_threshold: None,
-        cache_read_input_cost_per_token_above_threshold: None,
-    });
-
-    // Sonnet 4
-    m.insert("claude-sonnet-4-20250514", ClaudePricing {
-        input_cost_per_token: 3e-6,
-        output_cost_per_token: 1.5e-5,
-        cache_creation_input_cost_per_token: 3.75e-6,
-        cache_read_input_cost_per_token: 3e-7,
-        threshold_tokens: Some(200_000),
-        input_cost_per_token_above_threshold: Some(6e-6),
-        output_cost_per_token_above_threshold: Some(2.25e-5),
-        cache_creation_input_cost_per_token_above_threshold: Some(7.5e-6),
-        cache_read_input_cost_per_token_above_threshold: Some(6e-7),
-    });
-
-    m
-});
+impl PricingFamily {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PricingFamily::Codex => "codex",
+            PricingFamily::Claude => "claude",
+            PricingFamily::Gemini => "gemini",
+        }
+    }
 
-/// Cost usage pricing utilities
-pub struct CostUsagePricing;
+    /// Guess the family from a raw model name
+    pub fn for_model(model: &str) -> Self {
+        let lower = model.to_lowercase();
+        if lower.contains("claude") {
+            PricingFamily::Claude
+        } else if lower.contains("gemini") {
+            PricingFamily::Gemini
+        } else {
+            PricingFamily::Codex
+        }
+    }
 
-impl CostUsagePricing {
-    /// Normalize a Codex model name for pricing lookup
-    pub fn normalize_codex_model(raw: &str) -> String {
+    /// Built-in model whose price stands in for an unknown model of this family
+    pub fn fallback_model(&self, model: &str) -> &'static str {
+        let lower = model.to_lowercase();
+        match self {
+            PricingFamily::Codex => "gpt-5",
+            PricingFamily::Claude if lower.contains("opus") => "claude-opus-4-5",
+            PricingFamily::Claude if lower.contains("haiku") => "claude-haiku-4-5",
+            PricingFamily::Claude => "claude-sonnet-4-5",
+            PricingFamily::Gemini if lower.contains("flash-lite") => "gemini-2.5-flash-lite",
+            PricingFamily::Gemini if lower.contains("flash") => "gemini-2.5-flash",
+            PricingFamily::Gemini => "gemini-2.5-pro",
+        }
+    }
+}
+
+/// Prices above the tier threshold; missing values fall back to the base price
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TierPrices {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+}
+
+/// Pricing for one model (USD per million tokens)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Canonical model name
+    pub model: String,
+    /// Other names that resolve to this entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Input price
+    pub input: f64,
+    /// Output price
+    pub output: f64,
+    /// Cached input price (defaults to input)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+    /// Cache creation price (defaults to input)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+    /// Per-request token threshold for tiered pricing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_tokens: Option<i64>,
+    /// Prices above the threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub above_threshold: Option<TierPrices>,
+    /// First day this price applies (inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_from: Option<NaiveDate>,
+    /// Last day this price applies (inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_until: Option<NaiveDate>,
+}
+
+impl ModelPricing {
+    /// Whether this entry answers to the given name
+    pub fn matches(&self, name: &str) -> bool {
+        self.model.eq_ignore_ascii_case(name) || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether this entry's effective range covers the date
+    pub fn applies_on(&self, date: NaiveDate) -> bool {
+        self.effective_from.is_none_or(|from| from <= date)
+            && self.effective_until.is_none_or(|until| date <= until)
+    }
+
+    pub fn cache_read_price(&self) -> f64 {
+        self.cache_read.unwrap_or(self.input)
+    }
+
+    pub fn cache_write_price(&self) -> f64 {
+        self.cache_write.unwrap_or(self.input)
+    }
+
+    /// Cost in USD for one kind of token, split at the tier threshold
+    fn tiered(&self, tokens: i64, base: f64, above: Option<f64>) -> f64 {
+        let tokens = tokens.max(0);
+        let cost = match (self.threshold_tokens, above) {
+            (Some(threshold), Some(above_rate)) => {
+                let below = tokens.min(threshold);
+                let over = (tokens - threshold).max(0);
+                (below as f64) * base + (over as f64) * above_rate
+            }
+            _ => (tokens as f64) * base,
+        };
+        cost / TOKENS_PER_UNIT
+    }
+
+    /// Human-readable effective range
+    pub fn effective_label(&self) -> String {
+        match (self.effective_from, self.effective_until) {
+            (None, None) => "always".to_string(),
+            (Some(from), None) => format!("from {}", from),
+            (None, Some(until)) => format!("until {}", until),
+            (Some(from), Some(until)) => format!("{} to {}", from, until),
+        }
+    }
+}
+
+/// Contents of a pricing file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PricingFile {
+    #[serde(default)]
+    pub codex: Vec<ModelPricing>,
+    #[serde(default)]
+    pub claude: Vec<ModelPricing>,
+    #[serde(default)]
+    pub gemini: Vec<ModelPricing>,
+}
+
+impl PricingFile {
+    /// Entry lists by family
+    pub fn families(&self) -> [(PricingFamily, &Vec<ModelPricing>); 3] {
+        [
+            (PricingFamily::Codex, &self.codex),
+            (PricingFamily::Claude, &self.claude),
+            (PricingFamily::Gemini, &self.gemini),
+        ]
+    }
+
+    /// Parse TOML or JSON pricing content; format is picked from the extension
+    pub fn parse(content: &str, path: &Path) -> Result<Self, PricingError> {
+        let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+        let result = if is_json {
+            serde_json::from_str(content).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(content).map_err(|e| e.to_string())
+        };
+
+        result.map_err(|message| PricingError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// Read and parse a pricing file
+    pub fn load(path: &Path) -> Result<Self, PricingError> {
+        let content = std::fs::read_to_string(path).map_err(|source| PricingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&content, path)
+    }
+
+    /// Check entries for mistakes; returns one message per problem
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (family, entries) in self.families() {
+            for (i, entry) in entries.iter().enumerate() {
+                let label = format!("{}[{}] {}", family.name(), i, entry.model);
+
+                if entry.model.trim().is_empty() {
+                    problems.push(format!("{}: model name is empty", label));
+                }
+
+                let mut prices = vec![("input", Some(entry.input)), ("output", Some(entry.output))];
+                prices.push(("cache_read", entry.cache_read));
+                prices.push(("cache_write", entry.cache_write));
+                if let Some(tier) = entry.above_threshold {
+                    prices.push(("above_threshold.input", tier.input));
+                    prices.push(("above_threshold.output", tier.output));
+                    prices.push(("above_threshold.cache_read", tier.cache_read));
+                    prices.push(("above_threshold.cache_write", tier.cache_write));
+                }
+                for (name, price) in prices {
+                    if let Some(price) = price.filter(|p| !p.is_finite() || *p < 0.0) {
+                        problems.push(format!("{}: {} must be a non-negative number (got {})", label, name, price));
+                    }
+                }
+
+                match (entry.threshold_tokens, entry.above_threshold) {
+                    (Some(t), _) if t <= 0 => {
+                        problems.push(format!("{}: threshold_tokens must be positive", label));
+                    }
+                    (Some(_), None) => {
+                        problems.push(format!("{}: threshold_tokens is set without above_threshold prices", label));
+                    }
+                    (None, Some(_)) => {
+                        problems.push(format!("{}: above_threshold is set without threshold_tokens", label));
+                    }
+                    _ => {}
+                }
+
+                if let (Some(from), Some(until)) = (entry.effective_from, entry.effective_until) {
+                    if from > until {
+                        problems.push(format!("{}: effective_from {} is after effective_until {}", label, from, until));
+                    }
+                }
+
+                // Two entries answering to the same name must not overlap in time
+                for other in &entries[..i] {
+                    let shared = std::iter::once(&entry.model)
+                        .chain(entry.aliases.iter())
+                        .find(|name| other.matches(name));
+                    if let Some(name) = shared {
+                        if ranges_overlap(entry, other) {
+                            problems.push(format!(
+                                "{}: '{}' overlaps another entry ({}) for the same dates",
+                                label,
+                                name,
+                                other.effective_label()
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+fn ranges_overlap(a: &ModelPricing, b: &ModelPricing) -> bool {
+    let starts_before_b_ends = match (a.effective_from, b.effective_until) {
+        (Some(from), Some(until)) => from <= until,
+        _ => true,
+    };
+    let ends_after_b_starts = match (a.effective_until, b.effective_from) {
+        (Some(until), Some(from)) => until >= from,
+        _ => true,
+    };
+    starts_before_b_ends && ends_after_b_starts
+}
+
+/// Where a price entry came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PricingSource {
+    BuiltIn,
+    User(PathBuf),
+}
+
+impl std::fmt::Display for PricingSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PricingSource::BuiltIn => write!(f, "built-in"),
+            PricingSource::User(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Cost of prompt-cache reads and writes, and of the same tokens billed as
+/// regular input
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheCost {
+    pub read_usd: f64,
+    pub write_usd: f64,
+    pub uncached_usd: f64,
+}
+
+impl CacheCost {
+    /// What caching saved compared to sending the tokens uncached;
+    /// negative when writes were never read back
+    pub fn savings_usd(&self) -> f64 {
+        self.uncached_usd - self.read_usd - self.write_usd
+    }
+}
+
+/// A price entry with its family and source
+#[derive(Debug, Clone)]
+pub struct PricingEntry {
+    pub family: PricingFamily,
+    pub pricing: ModelPricing,
+    pub source: PricingSource,
+}
+
+/// Merged pricing table; user entries take precedence over built-in ones
+#[derive(Debug, Clone, Default)]
+pub struct PricingTable {
+    pub entries: Vec<PricingEntry>,
+    /// Problem loading the user file, if any (built-in prices are used instead)
+    pub user_error: Option<String>,
+}
+
+impl PricingTable {
+    /// Load the built-in table plus the user file, if present
+    pub fn load() -> Self {
+        let builtin = PricingFile::parse(DEFAULT_PRICING, Path::new("pricing.toml"))
+            .expect("built-in pricing table is valid");
+
+        let user = CostUsagePricing::user_pricing_path()
+            .filter(|p| p.exists())
+            .map(|path| PricingFile::load(&path).map(|file| (path, file)));
+
+        match user {
+            Some(Ok((path, file))) => Self::from_files(&builtin, Some((&file, path))),
+            Some(Err(e)) => {
+                tracing::warn!("Ignoring user pricing file: {}", e);
+                let mut table = Self::from_files(&builtin, None);
+                table.user_error = Some(e.to_string());
+                table
+            }
+            None => Self::from_files(&builtin, None),
+        }
+    }
+
+    /// Build a table from a built-in file and an optional user file
+    pub fn from_files(builtin: &PricingFile, user: Option<(&PricingFile, PathBuf)>) -> Self {
+        let mut entries = Vec::new();
+        let mut push = |file: &PricingFile, source: PricingSource| {
+            for (family, list) in file.families() {
+                for pricing in list {
+                    entries.push(PricingEntry {
+                        family,
+                        pricing: pricing.clone(),
+                        source: source.clone(),
+                    });
+                }
+            }
+        };
+
+        if let Some((file, path)) = user {
+            push(file, PricingSource::User(path));
+        }
+        push(builtin, PricingSource::BuiltIn);
+
+        Self {
+            entries,
+            user_error: None,
+        }
+    }
+
+    /// Whether any entry answers to the name
+    pub fn contains(&self, family: PricingFamily, name: &str) -> bool {
+        self.entries.iter().any(|e| e.family == family && e.pricing.matches(name))
+    }
+
+    /// Find the entry that prices `model` on `date`
+    pub fn resolve(&self, family: PricingFamily, model: &str, date: NaiveDate) -> Option<&PricingEntry> {
+        let key = self.normalize(family, model);
+        self.entries
+            .iter()
+            .find(|e| e.family == family && e.pricing.matches(&key) && e.pricing.applies_on(date))
+    }
+
+    /// Entry used to price `model` on `date`: its own, or the family's
+    /// fallback when the table has none
+    pub fn price(&self, family: PricingFamily, model: &str, date: NaiveDate) -> Option<&PricingEntry> {
+        self.resolve(family, model, date)
+            .or_else(|| self.resolve(family, family.fallback_model(model), date))
+    }
+
+    /// Normalize a model name for lookup in this table
+    pub fn normalize(&self, family: PricingFamily, raw: &str) -> String {
+        match family {
+            PricingFamily::Codex => self.normalize_codex_model(raw),
+            PricingFamily::Claude => self.normalize_claude_model(raw),
+            PricingFamily::Gemini => self.normalize_gemini_model(raw),
+        }
+    }
+
+    fn normalize_codex_model(&self, raw: &str) -> String {
         let mut trimmed = raw.trim().to_string();
 
         // Remove "openai/" prefix
@@ -186,7 +437,7 @@ impl CostUsagePricing {
         // Check if base model (without -codex suffix) exists in pricing
         if let Some(idx) = trimmed.find("-codex") {
             let base = &trimmed[..idx];
-            if CODEX_PRICING.contains_key(base) {
+            if self.contains(PricingFamily::Codex, base) {
                 return base.to_string();
             }
         }
@@ -194,8 +445,7 @@ impl CostUsagePricing {
         trimmed
     }
 
-    /// Normalize a Claude model name for pricing lookup
-    pub fn normalize_claude_model(raw: &str) -> String {
+    fn normalize_claude_model(&self, raw: &str) -> String {
         let mut trimmed = raw.trim().to_string();
 
         // Remove "anthropic." prefix
@@ -221,7 +471,7 @@ impl CostUsagePricing {
         let date_pattern = regex_lite::Regex::new(r"-\d{8}$").unwrap();
         if let Some(mat) = date_pattern.find(&trimmed) {
             let base = &trimmed[..mat.start()];
-            if CLAUDE_PRICING.contains_key(base) {
+            if self.contains(PricingFamily::Claude, base) {
                 return base.to_string();
             }
         }
@@ -229,73 +479,263 @@ impl CostUsagePricing {
         trimmed
     }
 
-    /// Calculate cost for Codex usage in USD
+    fn normalize_gemini_model(&self, raw: &str) -> String {
+        let trimmed = raw.trim();
+
+        // Remove "models/" and "google/" prefixes
+        let trimmed = trimmed.strip_prefix("models/").unwrap_or(trimmed);
+        let trimmed = trimmed.strip_prefix("google/").unwrap_or(trimmed);
+
+        trimmed.to_string()
+    }
+
+    /// Codex cost in USD; cached tokens are a subset of input tokens
     pub fn codex_cost_usd(
+        &self,
         model: &str,
-        input_tokens: i32,
-        cached_input_tokens: i32,
-        output_tokens: i32,
+        date: NaiveDate,
+        input_tokens: i64,
+        cached_input_tokens: i64,
+        output_tokens: i64,
     ) -> Option<f64> {
-        let key = Self::normalize_codex_model(model);
-        let pricing = CODEX_PRICING.get(key.as_str())?;
+        let pricing = &self.price(PricingFamily::Codex, model, date)?.pricing;
 
         let cached = cached_input_tokens.max(0).min(input_tokens.max(0));
         let non_cached = (input_tokens.max(0) - cached).max(0);
 
-        let cost = (non_cached as f64) * pricing.input_cost_per_token
-            + (cached as f64) * pricing.cache_read_input_cost_per_token
-            + (output_tokens.max(0) as f64) * pricing.output_cost_per_token;
+        let cost = (non_cached as f64) * pricing.input
+            + (cached as f64) * pricing.cache_read_price()
+            + (output_tokens.max(0) as f64) * pricing.output;
+
+        Some(cost / TOKENS_PER_UNIT)
+    }
+
+    /// Gemini cost in USD for a single request; cached tokens are a subset
+    /// of input tokens
+    pub fn gemini_cost_usd(
+        &self,
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        cached_input_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        let pricing = &self.price(PricingFamily::Gemini, model, date)?.pricing;
+        let tier = pricing.above_threshold.unwrap_or_default();
+
+        let cached = cached_input_tokens.max(0).min(input_tokens.max(0));
+        let non_cached = (input_tokens.max(0) - cached).max(0);
+
+        let cost = pricing.tiered(non_cached, pricing.input, tier.input)
+            + pricing.tiered(cached, pricing.cache_read_price(), tier.cache_read)
+            + pricing.tiered(output_tokens, pricing.output, tier.output);
 
         Some(cost)
     }
 
-    /// Calculate cost for Claude usage in USD
+    /// Cache read/write cost at base (below-threshold) rates
+    pub fn cache_cost_usd(
+        &self,
+        family: PricingFamily,
+        model: &str,
+        date: NaiveDate,
+        cache_read_tokens: i64,
+        cache_write_tokens: i64,
+    ) -> Option<CacheCost> {
+        let pricing = &self.price(family, model, date)?.pricing;
+        let read = cache_read_tokens.max(0) as f64;
+        let write = cache_write_tokens.max(0) as f64;
+
+        Some(CacheCost {
+            read_usd: read * pricing.cache_read_price() / TOKENS_PER_UNIT,
+            write_usd: write * pricing.cache_write_price() / TOKENS_PER_UNIT,
+            uncached_usd: (read + write) * pricing.input / TOKENS_PER_UNIT,
+        })
+    }
+
+    /// Claude cost in USD for a single request
     pub fn claude_cost_usd(
+        &self,
         model: &str,
-        input_tokens: i32,
-        cache_read_input_tokens: i32,
-        cache_creation_input_tokens: i32,
-        output_tokens: i32,
+        date: NaiveDate,
+        input_tokens: i64,
+        cache_read_input_tokens: i64,
+        cache_creation_input_tokens: i64,
+        output_tokens: i64,
     ) -> Option<f64> {
-        let key = Self::normalize_claude_model(model);
-        let pricing = CLAUDE_PRICING.get(key.as_str())?;
-
-        /// Calculate tiered cost
-        fn tiered(tokens: i32, base: f64, above: Option<f64>, threshold: Option<i32>) -> f64 {
-            let tokens = tokens.max(0);
-            match (threshold, above) {
-                (Some(thresh), Some(above_rate)) => {
-                    let below = tokens.min(thresh);
-                    let over = (tokens - thresh).max(0);
-                    (below as f64) * base + (over as f64) * above_rate
-                }
-                _ => (tokens as f64) * base,
-            }
+        let pricing = &self.price(PricingFamily::Claude, model, date)?.pricing;
+        let tier = pricing.above_threshold.unwrap_or_default();
+
+        let cost = pricing.tiered(input_tokens, pricing.input, tier.input)
+            + pricing.tiered(cache_read_input_tokens, pricing.cache_read_price(), tier.cache_read)
+            + pricing.tiered(cache_creation_input_tokens, pricing.cache_write_price(), tier.cache_write)
+            + pricing.tiered(output_tokens, pricing.output, tier.output);
+
+        Some(cost)
+    }
+}
+
+/// Cost usage pricing utilities
+pub struct CostUsagePricing;
+
+impl CostUsagePricing {
+    /// Path of the user pricing file (`pricing.toml`, or `pricing.json` if that exists instead)
+    pub fn user_pricing_path() -> Option<PathBuf> {
+        let dir = dirs::config_dir()?.join("CodexBar");
+        let json = dir.join("pricing.json");
+        let toml = dir.join("pricing.toml");
+        if !toml.exists() && json.exists() {
+            Some(json)
+        } else {
+            Some(toml)
+        }
+    }
+
+    /// Snapshot of the active pricing table
+    pub fn table() -> PricingTable {
+        PRICING.read().map(|t| t.clone()).unwrap_or_default()
+    }
+
+    /// Re-read the user pricing file
+    pub fn reload() {
+        let table = PricingTable::load();
+        if let Ok(mut guard) = PRICING.write() {
+            *guard = table;
         }
+    }
+
+    /// Normalize a Codex model name for pricing lookup
+    pub fn normalize_codex_model(raw: &str) -> String {
+        Self::with_table(|t| t.normalize(PricingFamily::Codex, raw)).unwrap_or_else(|| raw.trim().to_string())
+    }
+
+    /// Normalize a Claude model name for pricing lookup
+    pub fn normalize_claude_model(raw: &str) -> String {
+        Self::with_table(|t| t.normalize(PricingFamily::Claude, raw)).unwrap_or_else(|| raw.trim().to_string())
+    }
+
+    /// Normalize a Gemini model name for pricing lookup
+    pub fn normalize_gemini_model(raw: &str) -> String {
+        Self::with_table(|t| t.normalize(PricingFamily::Gemini, raw)).unwrap_or_else(|| raw.trim().to_string())
+    }
+
+    /// Calculate cost for Codex usage in USD at today's prices
+    pub fn codex_cost_usd(
+        model: &str,
+        input_tokens: i64,
+        cached_input_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        let today = Utc::now().date_naive();
+        Self::codex_cost_usd_on(model, today, input_tokens, cached_input_tokens, output_tokens)
+    }
+
+    /// Calculate cost for Codex usage in USD at the prices in effect on `date`
+    pub fn codex_cost_usd_on(
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        cached_input_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        Self::with_table(|t| t.codex_cost_usd(model, date, input_tokens, cached_input_tokens, output_tokens))?
+    }
 
-        let cost = tiered(
+    /// Calculate cost for Claude usage in USD at today's prices
+    pub fn claude_cost_usd(
+        model: &str,
+        input_tokens: i64,
+        cache_read_input_tokens: i64,
+        cache_creation_input_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        let today = Utc::now().date_naive();
+        Self::claude_cost_usd_on(
+            model,
+            today,
             input_tokens,
-            pricing.input_cost_per_token,
-            pricing.input_cost_per_token_above_threshold,
-            pricing.threshold_tokens,
-        ) + tiered(
             cache_read_input_tokens,
-            pricing.cache_read_input_cost_per_token,
-            pricing.cache_read_input_cost_per_token_above_threshold,
-            pricing.threshold_tokens,
-        ) + tiered(
             cache_creation_input_tokens,
-            pricing.cache_creation_input_cost_per_token,
-            pricing.cache_creation_input_cost_per_token_above_threshold,
-            pricing.threshold_tokens,
-        ) + tiered(
             output_tokens,
-            pricing.output_cost_per_token,
-            pricing.output_cost_per_token_above_threshold,
-            pricing.threshold_tokens,
-        );
+        )
+    }
 
-        Some(cost)
+    /// Calculate cost for Claude usage in USD at the prices in effect on `date`
+    pub fn claude_cost_usd_on(
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        cache_read_input_tokens: i64,
+        cache_creation_input_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        Self::with_table(|t| {
+            t.claude_cost_usd(
+                model,
+                date,
+                input_tokens,
+                cache_read_input_tokens,
+                cache_creation_input_tokens,
+                output_tokens,
+            )
+        })?
+    }
+
+    /// Calculate cost for one Gemini request in USD at the prices in effect on `date`
+    pub fn gemini_cost_usd_on(
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        cached_input_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        Self::with_table(|t| t.gemini_cost_usd(model, date, input_tokens, cached_input_tokens, output_tokens))?
+    }
+
+    /// Cost of one request for a model of any family, with cache reads and
+    /// writes counted separately from `input_tokens`. Used for tools that
+    /// proxy several vendors.
+    pub fn estimate_cost_usd_on(
+        model: &str,
+        date: NaiveDate,
+        input_tokens: i64,
+        cache_read_tokens: i64,
+        cache_write_tokens: i64,
+        output_tokens: i64,
+    ) -> Option<f64> {
+        let (input, read) = (input_tokens.max(0), cache_read_tokens.max(0));
+        match PricingFamily::for_model(model) {
+            PricingFamily::Claude => {
+                Self::claude_cost_usd_on(model, date, input, read, cache_write_tokens, output_tokens)
+            }
+            // These count cached tokens as part of input and have no write price
+            PricingFamily::Codex => {
+                Self::codex_cost_usd_on(model, date, input + cache_write_tokens.max(0) + read, read, output_tokens)
+            }
+            PricingFamily::Gemini => {
+                Self::gemini_cost_usd_on(model, date, input + cache_write_tokens.max(0) + read, read, output_tokens)
+            }
+        }
+    }
+
+    /// Cache read/write cost at the prices in effect on `date`
+    pub fn cache_cost_usd_on(
+        family: PricingFamily,
+        model: &str,
+        date: NaiveDate,
+        cache_read_tokens: i64,
+        cache_write_tokens: i64,
+    ) -> Option<CacheCost> {
+        Self::with_table(|t| t.cache_cost_usd(family, model, date, cache_read_tokens, cache_write_tokens))?
+    }
+
+    /// Whether `model` has its own price on `date` rather than the family fallback
+    pub fn is_priced(family: PricingFamily, model: &str, date: NaiveDate) -> bool {
+        Self::with_table(|t| t.resolve(family, model, date).is_some()).unwrap_or(false)
+    }
+
+    fn with_table<R>(f: impl FnOnce(&PricingTable) -> R) -> Option<R> {
+        PRICING.read().ok().map(|t| f(&t))
     }
 
     /// Format model name for display (e.g., "claude-3.5-sonnet" → "Sonnet 3.5")
@@ -319,6 +759,12 @@ impl CostUsagePricing {
             "GPT-5"
         } else if lower.contains("gpt-4") {
             "GPT-4"
+        } else if lower.contains("gemini") && lower.contains("pro") {
+            "Gemini Pro"
+        } else if lower.contains("gemini") && lower.contains("flash-lite") {
+            "Gemini Flash-Lite"
+        } else if lower.contains("gemini") && lower.contains("flash") {
+            "Gemini Flash"
         } else {
             return model.to_string();
         };
@@ -330,6 +776,7 @@ impl CostUsagePricing {
     }
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,10 +818,142 @@ mod tests {
         assert!(cost.is_some());
     }
 
+    #[test]
+    fn test_cache_cost() {
+        // Sonnet 4.5: $3 input, $0.30 cache read, $3.75 cache write per 1M
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let cost = CostUsagePricing::cache_cost_usd_on(
+            PricingFamily::Claude,
+            "claude-sonnet-4-5",
+            day,
+            1_000_000,
+            1_000_000,
+        )
+        .unwrap();
+        assert!((cost.read_usd - 0.30).abs() < 1e-9);
+        assert!((cost.write_usd - 3.75).abs() < 1e-9);
+        assert!((cost.savings_usd() - 1.95).abs() < 1e-9);
+    }
+
     #[test]
     fn test_format_model_name() {
         assert_eq!(CostUsagePricing::format_model_name("claude-3.5-sonnet"), "Sonnet 3.5");
         assert_eq!(CostUsagePricing::format_model_name("claude-opus-4"), "Opus 4");
         assert_eq!(CostUsagePricing::format_model_name("gpt-5"), "GPT-5 5");
     }
+
+    fn builtin() -> PricingFile {
+        PricingFile::parse(DEFAULT_PRICING, Path::new("pricing.toml")).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_table_is_valid() {
+        let file = builtin();
+        assert!(!file.codex.is_empty());
+        assert!(!file.claude.is_empty());
+        assert!(!file.gemini.is_empty());
+        assert_eq!(file.validate(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_user_override_with_effective_dates() {
+        let user = PricingFile::parse(
+            r#"
+            [[codex]]
+            model = "gpt-5"
+            input = 2.0
+            output = 20.0
+            effective_until = "2025-12-31"
+
+            [[codex]]
+            model = "gpt-6"
+            aliases = ["gpt-6-preview"]
+            input = 3.0
+            output = 30.0
+            "#,
+            Path::new("pricing.toml"),
+        )
+        .unwrap();
+        let table = PricingTable::from_files(&builtin(), Some((&user, PathBuf::from("pricing.toml"))));
+
+        let old_day = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let new_day = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+
+        // Historical day uses the user's dated price, later days fall through to built-in
+        let old = table.codex_cost_usd("gpt-5", old_day, 1_000_000, 0, 0).unwrap();
+        let new = table.codex_cost_usd("gpt-5", new_day, 1_000_000, 0, 0).unwrap();
+        assert!((old - 2.0).abs() < 1e-9);
+        assert!((new - 1.25).abs() < 1e-9);
+
+        // New model via alias
+        let entry = table.resolve(PricingFamily::Codex, "gpt-6-preview", new_day).unwrap();
+        assert_eq!(entry.pricing.model, "gpt-6");
+        assert!(matches!(entry.source, PricingSource::User(_)));
+    }
+
+    #[test]
+    fn test_unknown_model_uses_family_fallback() {
+        let table = PricingTable::from_files(&builtin(), None);
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        assert!(table.resolve(PricingFamily::Claude, "claude-opus-9", day).is_none());
+        let entry = table.price(PricingFamily::Claude, "claude-opus-9", day).unwrap();
+        assert_eq!(entry.pricing.model, "claude-opus-4-5");
+        let entry = table.price(PricingFamily::Gemini, "gemini-9-flash", day).unwrap();
+        assert_eq!(entry.pricing.model, "gemini-2.5-flash");
+
+        // Older models have their own prices
+        let cost = table.codex_cost_usd("gpt-4o", day, 1_000_000, 0, 0).unwrap();
+        assert!((cost - 2.5).abs() < 1e-9);
+        let entry = table.resolve(PricingFamily::Claude, "claude-3-5-sonnet-20241022", day).unwrap();
+        assert_eq!(entry.pricing.model, "claude-3-5-sonnet");
+    }
+
+    #[test]
+    fn test_claude_tiered_cost() {
+        let table = PricingTable::from_files(&builtin(), None);
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let cost = table
+            .claude_cost_usd("claude-sonnet-4-5-20250929", day, 300_000, 0, 0, 0)
+            .unwrap();
+        // 200k at $3/M + 100k at $6/M
+        assert!((cost - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gemini_cost() {
+        let table = PricingTable::from_files(&builtin(), None);
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        // 1M input of which 400k cached, 100k output on 2.5 Flash
+        let cost = table
+            .gemini_cost_usd("models/gemini-2.5-flash", day, 1_000_000, 400_000, 100_000)
+            .unwrap();
+        assert!((cost - (0.6 * 0.30 + 0.4 * 0.03 + 0.1 * 2.5)).abs() < 1e-9);
+
+        // 2.5 Pro switches to the higher tier above 200k tokens
+        let cost = table.gemini_cost_usd("gemini-2.5-pro", day, 300_000, 0, 0).unwrap();
+        assert!((cost - (0.2 * 1.25 + 0.1 * 2.5)).abs() < 1e-9);
+        assert_eq!(PricingFamily::for_model("gemini-2.5-pro"), PricingFamily::Gemini);
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let file = PricingFile::parse(
+            r#"{
+                "claude": [
+                    {"model": "claude-x", "input": -1.0, "output": 5.0, "threshold_tokens": 1000},
+                    {"model": "claude-y", "aliases": ["claude-x"], "input": 1.0, "output": 5.0}
+                ]
+            }"#,
+            Path::new("pricing.json"),
+        )
+        .unwrap();
+
+        let problems = file.validate();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].contains("input must be a non-negative number"));
+        assert!(problems[1].contains("without above_threshold"));
+        assert!(problems[2].contains("overlaps"));
+    }
 }
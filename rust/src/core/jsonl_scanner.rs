@@ -1,14 +1,15 @@
 //! JSONL Scanner with Caching
 //!
-//! Incremental log file parsing for Codex and Claude session logs.
+//! Incremental log file parsing for Codex and Claude session logs, plus the
+//! Gemini CLI's JSON chat recordings and OpenCode's per-message storage.
 //! Supports file-level caching to avoid re-parsing unchanged files.
 
 #![allow(dead_code)]
 
 use crate::core::{CostUsagePricing, ProviderId};
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
@@ -20,8 +21,11 @@ pub struct CostUsageCache {
     pub last_scan_unix_ms: i64,
     /// Per-file usage data
     pub files: HashMap<String, CostUsageFileUsage>,
-    /// Aggregated daily data: day_key -> model -> [input, cached, output]
-    pub days: HashMap<String, HashMap<String, Vec<i32>>>,
+    /// Aggregated daily data: day_key -> model -> packed token counts
+    pub days: HashMap<String, HashMap<String, Vec<i64>>>,
+    /// Fingerprint of the pricing table used for precomputed costs
+    #[serde(default)]
+    pub pricing_fingerprint: Option<String>,
 }
 
 /// Per-file usage tracking
@@ -31,35 +35,131 @@ pub struct CostUsageFileUsage {
     pub mtime_unix_ms: i64,
     /// File size in bytes
     pub size: i64,
-    /// Daily usage data extracted from this file
-    pub days: HashMap<String, HashMap<String, Vec<i32>>>,
+    /// Daily usage data extracted from this file: day_key -> model -> packed
+    /// counts ([input, cached, output] for Codex, [input, cache_read,
+    /// cache_creation, output, cost_nanos] for Claude and OpenCode, [input,
+    /// cached, output, cost_nanos] for Gemini)
+    pub days: HashMap<String, HashMap<String, Vec<i64>>>,
     /// Bytes parsed so far (for incremental parsing)
     pub parsed_bytes: Option<i64>,
     /// Last model seen (for delta calculations)
     pub last_model: Option<String>,
     /// Last token totals (for delta calculations)
     pub last_totals: Option<CodexTotals>,
+    /// Working directory the session ran in
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Hashes of message id + request id pairs counted from this file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub message_hashes: Vec<u64>,
+    /// Timestamp of the first counted usage event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_activity: Option<String>,
+    /// Timestamp of the last counted usage event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_activity: Option<String>,
+    /// Hourly usage: UTC hour key (YYYY-MM-DDTHH) -> model -> packed counts,
+    /// laid out like `days`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hours: HashMap<String, HashMap<String, Vec<i64>>>,
+}
+
+impl CostUsageFileUsage {
+    /// Entries written before hourly buckets existed must be re-parsed
+    pub fn needs_hourly_backfill(&self) -> bool {
+        self.hours.is_empty() && !self.days.is_empty()
+    }
 }
 
 /// Running totals for Codex token counting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodexTotals {
-    pub input: i32,
-    pub cached: i32,
-    pub output: i32,
+    pub input: i64,
+    pub cached: i64,
+    pub output: i64,
 }
 
 /// Result of parsing a Codex file
 #[derive(Debug)]
 pub struct CodexParseResult {
     /// Daily usage: day_key -> model -> [input, cached, output]
-    pub days: HashMap<String, HashMap<String, Vec<i32>>>,
+    pub days: HashMap<String, HashMap<String, Vec<i64>>>,
     /// Bytes parsed
     pub parsed_bytes: i64,
     /// Last model seen
     pub last_model: Option<String>,
     /// Last totals seen
     pub last_totals: Option<CodexTotals>,
+    /// Working directory from session metadata
+    pub project: Option<String>,
+    /// Timestamps of the first and last usage events parsed
+    pub first_activity: Option<String>,
+    pub last_activity: Option<String>,
+    /// Hourly usage: UTC hour key -> model -> packed counts
+    pub hours: HashMap<String, HashMap<String, Vec<i64>>>,
+}
+
+/// Result of parsing a Claude file
+#[derive(Debug)]
+pub struct ClaudeParseResult {
+    /// Daily usage: day_key -> model -> [input, cache_read, cache_creation, output, cost_nanos]
+    pub days: HashMap<String, HashMap<String, Vec<i64>>>,
+    /// Bytes parsed
+    pub parsed_bytes: i64,
+    /// Working directory from the most recent event
+    pub project: Option<String>,
+    /// Hashes of the messages counted from this file
+    pub message_hashes: Vec<u64>,
+    /// Timestamps of the first and last usage events parsed
+    pub first_activity: Option<String>,
+    pub last_activity: Option<String>,
+    /// Hourly usage: UTC hour key -> model -> packed counts
+    pub hours: HashMap<String, HashMap<String, Vec<i64>>>,
+}
+
+/// Result of parsing a Gemini chat recording
+#[derive(Debug, Default)]
+pub struct GeminiParseResult {
+    /// Daily usage: day_key -> model -> [input, cached, output, cost_nanos]
+    pub days: HashMap<String, HashMap<String, Vec<i64>>>,
+    /// Timestamps of the first and last usage events parsed
+    pub first_activity: Option<String>,
+    pub last_activity: Option<String>,
+    /// Hourly usage: UTC hour key -> model -> packed counts
+    pub hours: HashMap<String, HashMap<String, Vec<i64>>>,
+}
+
+/// Result of parsing one OpenCode session's messages
+#[derive(Debug, Default)]
+pub struct OpenCodeParseResult {
+    /// Daily usage: day_key -> vendor/model -> [input, cache_read, cache_write, output, cost_nanos]
+    pub days: HashMap<String, HashMap<String, Vec<i64>>>,
+    /// Working directory of the most recent message
+    pub project: Option<String>,
+    /// Timestamps of the first and last usage events parsed
+    pub first_activity: Option<String>,
+    pub last_activity: Option<String>,
+    /// Hourly usage: UTC hour key -> vendor/model -> packed counts
+    pub hours: HashMap<String, HashMap<String, Vec<i64>>>,
+}
+
+/// A rate-limit window as logged by the Codex CLI
+#[derive(Debug, Clone)]
+pub struct CodexLoggedWindow {
+    pub used_percent: f64,
+    pub window_minutes: Option<u32>,
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+/// Most recent rate limits found in Codex session logs
+#[derive(Debug, Clone)]
+pub struct CodexLoggedRateLimits {
+    /// Timestamp of the event the limits came from
+    pub observed_at: DateTime<Utc>,
+    pub primary: Option<CodexLoggedWindow>,
+    pub secondary: Option<CodexLoggedWindow>,
+    /// Session file the event was read from
+    pub source_file: PathBuf,
 }
 
 /// Day range for scanning
@@ -83,6 +183,16 @@ impl CostUsageDayRange {
         }
     }
 
+    /// Range that accepts every day, used when parsing files into the cache
+    pub fn unbounded() -> Self {
+        Self {
+            since_key: "0000-01-01".to_string(),
+            until_key: "9999-12-31".to_string(),
+            scan_since_key: "0000-01-01".to_string(),
+            scan_until_key: "9999-12-31".to_string(),
+        }
+    }
+
     pub fn day_key(date: NaiveDate) -> String {
         date.format("%Y-%m-%d").to_string()
     }
@@ -132,11 +242,43 @@ impl JsonlScanner {
             if default_path.exists() && !roots.contains(&default_path) {
                 roots.push(default_path);
             }
+
+            let xdg_path = home.join(".config").join("claude").join("projects");
+            if xdg_path.exists() && !roots.contains(&xdg_path) {
+                roots.push(xdg_path);
+            }
         }
 
         roots
     }
 
+    /// Get the Gemini CLI's temp directory, which holds one folder of chat
+    /// recordings per project (`<project hash>/chats/session-*.json`)
+    pub fn default_gemini_tmp_root() -> Option<PathBuf> {
+        // GEMINI_CLI_HOME replaces the home directory the CLI uses
+        if let Ok(home) = std::env::var("GEMINI_CLI_HOME") {
+            let home = home.trim();
+            if !home.is_empty() {
+                return Some(PathBuf::from(home).join(".gemini").join("tmp"));
+            }
+        }
+
+        dirs::home_dir().map(|h| h.join(".gemini").join("tmp"))
+    }
+
+    /// Get OpenCode's storage directory. OpenCode uses the XDG data
+    /// directory on every platform.
+    pub fn default_opencode_storage_root() -> Option<PathBuf> {
+        let data_home = std::env::var("XDG_DATA_HOME")
+            .ok()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))?;
+
+        Some(data_home.join("opencode").join("storage"))
+    }
+
     /// List Codex session files in the given date range
     pub fn list_codex_session_files(
         root: &Path,
@@ -183,7 +325,6 @@ impl JsonlScanner {
         initial_totals: Option<CodexTotals>,
     ) -> std::io::Result<CodexParseResult> {
         let file = File::open(file_path)?;
-        let file_size = file.metadata()?.len() as i64;
 
         let mut reader = BufReader::new(file);
         if start_offset > 0 {
@@ -192,15 +333,26 @@ impl JsonlScanner {
 
         let mut current_model = initial_model;
         let mut previous_totals = initial_totals;
-        let mut days: HashMap<String, HashMap<String, Vec<i32>>> = HashMap::new();
+        let mut project = None;
+        let mut first_activity: Option<String> = None;
+        let mut last_activity: Option<String> = None;
+        let mut days: HashMap<String, HashMap<String, Vec<i64>>> = HashMap::new();
+        let mut hours: HashMap<String, HashMap<String, Vec<i64>>> = HashMap::new();
         let mut parsed_bytes = start_offset;
 
         let mut line = String::new();
         while reader.read_line(&mut line)? > 0 {
+            // A line still being written is read again once it is complete
+            if !line.ends_with('\n') {
+                break;
+            }
             parsed_bytes += line.len() as i64;
 
             // Quick check for relevant lines
-            if !line.contains("\"type\":\"event_msg\"") && !line.contains("\"type\":\"turn_context\"") {
+            if !line.contains("\"type\":\"event_msg\"")
+                && !line.contains("\"type\":\"turn_context\"")
+                && !line.contains("\"type\":\"session_meta\"")
+            {
                 line.clear();
                 continue;
             }
@@ -230,6 +382,12 @@ impl JsonlScanner {
                         continue;
                     }
 
+                    if msg_type == "session_meta" || msg_type == "turn_context" {
+                        if let Some(cwd) = obj.pointer("/payload/cwd").and_then(|v| v.as_str()) {
+                            project = Some(cwd.to_string());
+                        }
+                    }
+
                     if msg_type == "turn_context" {
                         // Extract model from turn_context
                         if let Some(payload) = obj.get("payload") {
@@ -264,13 +422,13 @@ impl JsonlScanner {
                             let (delta_input, delta_cached, delta_output) = if let Some(total) =
                                 info.and_then(|i| i.get("total_token_usage"))
                             {
-                                let input = total.get("input_tokens").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                                let input = total.get("input_tokens").and_then(|v| v.as_i64()).unwrap_or(0);
                                 let cached = total
                                     .get("cached_input_tokens")
                                     .or(total.get("cache_read_input_tokens"))
                                     .and_then(|v| v.as_i64())
-                                    .unwrap_or(0) as i32;
-                                let output = total.get("output_tokens").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                                    .unwrap_or(0);
+                                let output = total.get("output_tokens").and_then(|v| v.as_i64()).unwrap_or(0);
 
                                 let delta_input = (input - previous_totals.as_ref().map_or(0, |t| t.input)).max(0);
                                 let delta_cached = (cached - previous_totals.as_ref().map_or(0, |t| t.cached)).max(0);
@@ -280,13 +438,13 @@ impl JsonlScanner {
 
                                 (delta_input, delta_cached, delta_output)
                             } else if let Some(last) = info.and_then(|i| i.get("last_token_usage")) {
-                                let input = last.get("input_tokens").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                                let input = last.get("input_tokens").and_then(|v| v.as_i64()).unwrap_or(0);
                                 let cached = last
                                     .get("cached_input_tokens")
                                     .or(last.get("cache_read_input_tokens"))
                                     .and_then(|v| v.as_i64())
-                                    .unwrap_or(0) as i32;
-                                let output = last.get("output_tokens").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+                                    .unwrap_or(0);
+                                let output = last.get("output_tokens").and_then(|v| v.as_i64()).unwrap_or(0);
 
                                 (input.max(0), cached.max(0), output.max(0))
                             } else {
@@ -303,11 +461,14 @@ impl JsonlScanner {
                             let norm_model = CostUsagePricing::normalize_codex_model(&model);
                             let cached_clamp = delta_cached.min(delta_input);
 
-                            let day_models = days.entry(day_key.to_string()).or_default();
-                            let packed = day_models.entry(norm_model).or_insert_with(|| vec![0, 0, 0]);
-                            packed[0] += delta_input;
-                            packed[1] += cached_clamp;
-                            packed[2] += delta_output;
+                            first_activity.get_or_insert_with(|| ts.to_string());
+                            last_activity = Some(ts.to_string());
+
+                            let counts = [delta_input, cached_clamp, delta_output];
+                            Self::add_packed(&mut days, day_key, &norm_model, &counts);
+                            if let Some(hour_key) = utc_hour_key(ts) {
+                                Self::add_packed(&mut hours, &hour_key, &norm_model, &counts);
+                            }
                         }
                     }
                 }
@@ -318,12 +479,802 @@ impl JsonlScanner {
 
         Ok(CodexParseResult {
             days,
-            parsed_bytes: file_size.max(parsed_bytes),
+            parsed_bytes,
             last_model: current_model,
             last_totals: previous_totals,
+            project,
+            first_activity,
+            last_activity,
+            hours,
+        })
+    }
+
+    /// Find the newest `token_count` event carrying rate limits in Codex
+    /// session logs from the last `lookback_days` days
+    pub fn latest_codex_rate_limits(root: &Path, today: NaiveDate, lookback_days: i64) -> Option<CodexLoggedRateLimits> {
+        let since_key = CostUsageDayRange::day_key(today - chrono::Duration::days(lookback_days));
+        let until_key = CostUsageDayRange::day_key(today + chrono::Duration::days(1));
+
+        // Newest files first; the first file with an event holds the latest one
+        let mut files: Vec<(PathBuf, i64)> = Self::list_codex_session_files(root, &since_key, &until_key)
+            .into_iter()
+            .filter_map(|path| Self::file_stamp(&path).map(|(mtime, _)| (path, mtime)))
+            .collect();
+        files.sort_by_key(|f| std::cmp::Reverse(f.1));
+
+        files.into_iter().find_map(|(path, _)| Self::last_rate_limits_in_file(&path))
+    }
+
+    fn last_rate_limits_in_file(path: &Path) -> Option<CodexLoggedRateLimits> {
+        let file = File::open(path).ok()?;
+        let mut latest: Option<CodexLoggedRateLimits> = None;
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if !line.contains("\"rate_limits\"") || !line.contains("\"token_count\"") {
+                continue;
+            }
+            let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let Some(limits) = obj.pointer("/payload/rate_limits").filter(|v| v.is_object()) else {
+                continue;
+            };
+            let Some(observed_at) = obj
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|ts| ts.with_timezone(&Utc))
+            else {
+                continue;
+            };
+
+            let primary = limits.get("primary").and_then(|w| Self::parse_logged_window(w, observed_at));
+            let secondary = limits.get("secondary").and_then(|w| Self::parse_logged_window(w, observed_at));
+            if primary.is_none() && secondary.is_none() {
+                continue;
+            }
+
+            if latest.as_ref().is_none_or(|l| observed_at >= l.observed_at) {
+                latest = Some(CodexLoggedRateLimits {
+                    observed_at,
+                    primary,
+                    secondary,
+                    source_file: path.to_path_buf(),
+                });
+            }
+        }
+
+        latest
+    }
+
+    /// Parse a logged window; older CLIs log `resets_in_seconds`, newer ones `resets_at`
+    fn parse_logged_window(window: &serde_json::Value, observed_at: DateTime<Utc>) -> Option<CodexLoggedWindow> {
+        let used_percent = window.get("used_percent").and_then(|v| v.as_f64())?;
+        let window_minutes = window
+            .get("window_minutes")
+            .and_then(|v| v.as_u64())
+            .and_then(|m| u32::try_from(m).ok());
+        let resets_at = window
+            .get("resets_at")
+            .and_then(|v| v.as_i64())
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .or_else(|| {
+                window
+                    .get("resets_in_seconds")
+                    .and_then(|v| v.as_i64())
+                    .map(|secs| observed_at + chrono::Duration::seconds(secs))
+            });
+
+        Some(CodexLoggedWindow {
+            used_percent,
+            window_minutes,
+            resets_at,
         })
     }
 
+    /// List Claude session files under a projects root, skipping files not
+    /// modified since `modified_since_key` (a file can't hold newer events
+    /// than its mtime)
+    pub fn list_claude_project_files(root: &Path, modified_since_key: &str) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        Self::collect_claude_files(root, modified_since_key, &mut files);
+        files
+    }
+
+    fn collect_claude_files(dir: &Path, modified_since_key: &str, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::collect_claude_files(&path, modified_since_key, files);
+            } else if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("jsonl")) {
+                let modified_key = entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .map(|t| CostUsageDayRange::day_key(chrono::DateTime::<Utc>::from(t).date_naive()))
+                    .unwrap_or_default();
+                if modified_key.as_str() >= modified_since_key {
+                    files.push(path);
+                }
+            }
+        }
+    }
+
+    /// Parse a Claude JSONL file, bucketing assistant usage by each event's timestamp.
+    ///
+    /// Streaming responses repeat the same usage on several lines and resumed
+    /// sessions replay earlier messages, so each message id + request id pair
+    /// is counted once; `seen` carries the pairs already counted elsewhere.
+    pub fn parse_claude_file(
+        file_path: &Path,
+        range: &CostUsageDayRange,
+        start_offset: i64,
+        seen: &mut HashSet<u64>,
+    ) -> std::io::Result<ClaudeParseResult> {
+        let file = File::open(file_path)?;
+
+        let mut reader = BufReader::new(file);
+        if start_offset > 0 {
+            reader.seek(SeekFrom::Start(start_offset as u64))?;
+        }
+
+        let mut project = None;
+        let mut message_hashes = Vec::new();
+        let mut first_activity: Option<String> = None;
+        let mut last_activity: Option<String> = None;
+        let mut days: HashMap<String, HashMap<String, Vec<i64>>> = HashMap::new();
+        let mut hours: HashMap<String, HashMap<String, Vec<i64>>> = HashMap::new();
+        let mut parsed_bytes = start_offset;
+
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            // A line still being written is read again once it is complete
+            if !line.ends_with('\n') {
+                break;
+            }
+            parsed_bytes += line.len() as i64;
+
+            // Quick check for relevant lines
+            if !line.contains("\"usage\"") && !line.contains("\"cwd\"") {
+                line.clear();
+                continue;
+            }
+
+            if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) {
+                if let Some(cwd) = obj.get("cwd").and_then(|v| v.as_str()) {
+                    project = Some(cwd.to_string());
+                }
+
+                if obj.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+                    line.clear();
+                    continue;
+                }
+
+                let timestamp = obj.get("timestamp").and_then(|v| v.as_str()).unwrap_or("");
+                let (Some(day_key), Some(message)) = (timestamp.get(..10), obj.get("message")) else {
+                    line.clear();
+                    continue;
+                };
+
+                if !CostUsageDayRange::is_in_range(day_key, &range.scan_since_key, &range.scan_until_key) {
+                    line.clear();
+                    continue;
+                }
+
+                let Some(usage) = message.get("usage") else {
+                    line.clear();
+                    continue;
+                };
+
+                let model = message.get("model").and_then(|v| v.as_str()).unwrap_or("unknown");
+                let tokens = |key: &str| usage.get(key).and_then(|v| v.as_i64()).unwrap_or(0).max(0);
+                let input = tokens("input_tokens");
+                let cache_read = tokens("cache_read_input_tokens");
+                let cache_creation = tokens("cache_creation_input_tokens");
+                let output = tokens("output_tokens");
+
+                if input == 0 && cache_read == 0 && cache_creation == 0 && output == 0 {
+                    line.clear();
+                    continue;
+                }
+
+                let message_id = message.get("id").and_then(|v| v.as_str());
+                let request_id = obj.get("requestId").and_then(|v| v.as_str());
+                if let (Some(message_id), Some(request_id)) = (message_id, request_id) {
+                    let hash = stable_hash(&format!("{}:{}", message_id, request_id));
+                    if !seen.insert(hash) {
+                        line.clear();
+                        continue;
+                    }
+                    message_hashes.push(hash);
+                }
+
+                // Tiered prices apply per request, so cost is computed per event
+                let cost = CostUsageDayRange::parse_day_key(day_key)
+                    .and_then(|day| {
+                        CostUsagePricing::claude_cost_usd_on(model, day, input, cache_read, cache_creation, output)
+                    })
+                    .unwrap_or(0.0);
+
+                first_activity.get_or_insert_with(|| timestamp.to_string());
+                last_activity = Some(timestamp.to_string());
+
+                let norm_model = CostUsagePricing::normalize_claude_model(model);
+                let counts = [input, cache_read, cache_creation, output, (cost * 1e9).round() as i64];
+                Self::add_packed(&mut days, day_key, &norm_model, &counts);
+                if let Some(hour_key) = utc_hour_key(timestamp) {
+                    Self::add_packed(&mut hours, &hour_key, &norm_model, &counts);
+                }
+            }
+
+            line.clear();
+        }
+
+        Ok(ClaudeParseResult {
+            days,
+            parsed_bytes,
+            project,
+            message_hashes,
+            first_activity,
+            last_activity,
+            hours,
+        })
+    }
+
+    /// List Gemini chat recordings modified on or after the given day
+    pub fn list_gemini_session_files(root: &Path, modified_since_key: &str) -> Vec<PathBuf> {
+        let Ok(projects) = fs::read_dir(root) else {
+            return Vec::new();
+        };
+
+        let mut files = Vec::new();
+        for project in projects.flatten() {
+            let Ok(entries) = fs::read_dir(project.path().join("chats")) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !name.starts_with("session-") || !name.ends_with(".json") {
+                    continue;
+                }
+                let modified_key = entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .map(|t| CostUsageDayRange::day_key(chrono::DateTime::<Utc>::from(t).date_naive()))
+                    .unwrap_or_default();
+                if modified_key.as_str() >= modified_since_key {
+                    files.push(path);
+                }
+            }
+        }
+        files
+    }
+
+    /// Parse a Gemini chat recording. These are single JSON documents that
+    /// the CLI rewrites as the conversation grows, so the whole file is read.
+    ///
+    /// `input` counts include cached tokens; tool-use prompt tokens are
+    /// billed as input and thinking tokens as output.
+    pub fn parse_gemini_file(file_path: &Path, range: &CostUsageDayRange) -> std::io::Result<GeminiParseResult> {
+        let content = fs::read_to_string(file_path)?;
+        let session: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        let mut result = GeminiParseResult::default();
+        let messages = session.get("messages").and_then(|v| v.as_array());
+
+        for message in messages.into_iter().flatten() {
+            if message.get("type").and_then(|v| v.as_str()) != Some("gemini") {
+                continue;
+            }
+            let Some(tokens) = message.get("tokens") else {
+                continue;
+            };
+
+            let timestamp = message.get("timestamp").and_then(|v| v.as_str()).unwrap_or("");
+            let Some(day_key) = timestamp.get(..10) else {
+                continue;
+            };
+            if !CostUsageDayRange::is_in_range(day_key, &range.scan_since_key, &range.scan_until_key) {
+                continue;
+            }
+
+            let count = |key: &str| tokens.get(key).and_then(|v| v.as_i64()).unwrap_or(0).max(0);
+            let input = count("input") + count("tool");
+            let cached = count("cached").min(input);
+            let output = count("output") + count("thoughts");
+            if input == 0 && output == 0 {
+                continue;
+            }
+
+            let model = message.get("model").and_then(|v| v.as_str()).unwrap_or("unknown");
+            let norm_model = CostUsagePricing::normalize_gemini_model(model);
+
+            // Tiered prices apply per request, so cost is computed per message
+            let cost = CostUsageDayRange::parse_day_key(day_key)
+                .and_then(|day| CostUsagePricing::gemini_cost_usd_on(&norm_model, day, input, cached, output))
+                .unwrap_or(0.0);
+
+            result.first_activity.get_or_insert_with(|| timestamp.to_string());
+            result.last_activity = Some(timestamp.to_string());
+
+            let counts = [input, cached, output, (cost * 1e9).round() as i64];
+            Self::add_packed(&mut result.days, day_key, &norm_model, &counts);
+            if let Some(hour_key) = utc_hour_key(timestamp) {
+                Self::add_packed(&mut result.hours, &hour_key, &norm_model, &counts);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Bring cached Gemini file usage up to date. Recordings are rewritten
+    /// rather than appended to, so any change re-parses the whole file.
+    /// Returns the keys of the files in range.
+    pub fn refresh_gemini_cache(root: &Path, range: &CostUsageDayRange, cache: &mut CostUsageCache) -> Vec<String> {
+        // Costs are precomputed per message, so a pricing change invalidates them
+        let fingerprint = Self::pricing_fingerprint();
+        if cache.pricing_fingerprint.as_deref() != Some(fingerprint.as_str()) {
+            cache.files.clear();
+            cache.pricing_fingerprint = Some(fingerprint);
+        }
+
+        let files = Self::list_gemini_session_files(root, &range.scan_since_key);
+        let mut keys = Vec::with_capacity(files.len());
+
+        for path in files {
+            let key = path.to_string_lossy().to_string();
+            let Some((mtime_unix_ms, size)) = Self::file_stamp(&path) else {
+                continue;
+            };
+
+            let cached = cache.files.get(&key);
+            if cached.is_some_and(|c| c.mtime_unix_ms == mtime_unix_ms && c.size == size) {
+                keys.push(key);
+                continue;
+            }
+
+            // A recording caught mid-write may not parse; keep the previous result
+            let Ok(parsed) = Self::parse_gemini_file(&path, &CostUsageDayRange::unbounded()) else {
+                if cached.is_some() {
+                    keys.push(key);
+                }
+                continue;
+            };
+
+            cache.files.insert(
+                key.clone(),
+                CostUsageFileUsage {
+                    mtime_unix_ms,
+                    size,
+                    days: parsed.days,
+                    parsed_bytes: None,
+                    last_model: None,
+                    last_totals: None,
+                    project: None,
+                    message_hashes: Vec::new(),
+                    first_activity: parsed.first_activity,
+                    last_activity: parsed.last_activity,
+                    hours: parsed.hours,
+                },
+            );
+            keys.push(key);
+        }
+
+        Self::finish_refresh(cache, &[root]);
+        keys
+    }
+
+    /// List OpenCode session message directories (`message/<session id>`)
+    /// with a message modified on or after the given day, along with the
+    /// newest message mtime (ms) and the total size of their messages
+    pub fn list_opencode_session_dirs(storage: &Path, modified_since_key: &str) -> Vec<(PathBuf, i64, i64)> {
+        let Ok(sessions) = fs::read_dir(storage.join("message")) else {
+            return Vec::new();
+        };
+
+        let mut dirs = Vec::new();
+        for session in sessions.flatten() {
+            let dir = session.path();
+            let Ok(messages) = fs::read_dir(&dir) else {
+                continue;
+            };
+
+            let (mut mtime, mut size) = (0i64, 0i64);
+            for message in messages.flatten() {
+                let path = message.path();
+                if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+                    continue;
+                }
+                if let Some((file_mtime, file_size)) = Self::file_stamp(&path) {
+                    mtime = mtime.max(file_mtime);
+                    size += file_size;
+                }
+            }
+
+            let modified_key = Utc
+                .timestamp_millis_opt(mtime)
+                .single()
+                .map(|t| CostUsageDayRange::day_key(t.date_naive()))
+                .unwrap_or_default();
+            if size > 0 && modified_key.as_str() >= modified_since_key {
+                dirs.push((dir, mtime, size));
+            }
+        }
+        dirs
+    }
+
+    /// Parse the message files of one OpenCode session. Assistant messages
+    /// carry token counts and the cost OpenCode computed; when that cost is
+    /// zero (subscriptions, unknown prices) it is estimated from our table.
+    pub fn parse_opencode_session(dir: &Path, range: &CostUsageDayRange) -> std::io::Result<OpenCodeParseResult> {
+        let mut result = OpenCodeParseResult::default();
+        let (mut first, mut last) = (i64::MAX, i64::MIN);
+        let mut project_at = i64::MIN;
+
+        for entry in fs::read_dir(dir)?.flatten() {
+            let path = entry.path();
+            if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+                continue;
+            }
+            // Messages still being written may not parse yet
+            let Ok(message) = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string()))
+            else {
+                continue;
+            };
+
+            if message.get("role").and_then(|v| v.as_str()) != Some("assistant") {
+                continue;
+            }
+            let Some(tokens) = message.get("tokens") else {
+                continue;
+            };
+            let Some(created) = message.pointer("/time/created").and_then(|v| v.as_i64()) else {
+                continue;
+            };
+            let Some(at) = Utc.timestamp_millis_opt(created).single() else {
+                continue;
+            };
+            let day = at.date_naive();
+            let day_key = CostUsageDayRange::day_key(day);
+            if !CostUsageDayRange::is_in_range(&day_key, &range.scan_since_key, &range.scan_until_key) {
+                continue;
+            }
+
+            let count = |pointer: &str| tokens.pointer(pointer).and_then(|v| v.as_i64()).unwrap_or(0).max(0);
+            let input = count("/input");
+            let cache_read = count("/cache/read");
+            let cache_write = count("/cache/write");
+            let output = count("/output") + count("/reasoning");
+            if input == 0 && cache_read == 0 && cache_write == 0 && output == 0 {
+                continue;
+            }
+
+            let model = message.get("modelID").and_then(|v| v.as_str()).unwrap_or("unknown");
+            let cost = message
+                .get("cost")
+                .and_then(|v| v.as_f64())
+                .filter(|c| *c > 0.0)
+                .or_else(|| CostUsagePricing::estimate_cost_usd_on(model, day, input, cache_read, cache_write, output))
+                .unwrap_or(0.0);
+
+            // OpenCode proxies many vendors, so keep the vendor with the model
+            let key = match message.get("providerID").and_then(|v| v.as_str()) {
+                Some(vendor) if !vendor.is_empty() => format!("{}/{}", vendor, model),
+                _ => model.to_string(),
+            };
+
+            if let Some(cwd) = message.pointer("/path/cwd").and_then(|v| v.as_str()) {
+                if created >= project_at {
+                    project_at = created;
+                    result.project = Some(cwd.to_string());
+                }
+            }
+            first = first.min(created);
+            last = last.max(created);
+
+            let timestamp = at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+            let counts = [input, cache_read, cache_write, output, (cost * 1e9).round() as i64];
+            Self::add_packed(&mut result.days, &day_key, &key, &counts);
+            if let Some(hour_key) = utc_hour_key(&timestamp) {
+                Self::add_packed(&mut result.hours, &hour_key, &key, &counts);
+            }
+        }
+
+        let stamp = |ms: i64| {
+            Utc.timestamp_millis_opt(ms)
+                .single()
+                .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        };
+        if first <= last {
+            result.first_activity = stamp(first);
+            result.last_activity = stamp(last);
+        }
+        Ok(result)
+    }
+
+    /// Bring cached OpenCode session usage up to date. Each session
+    /// directory is one cache entry and is re-read when any message in it
+    /// changes. Returns the keys of the sessions in range.
+    pub fn refresh_opencode_cache(storage: &Path, range: &CostUsageDayRange, cache: &mut CostUsageCache) -> Vec<String> {
+        // Estimated costs are precomputed per message, so a pricing change invalidates them
+        let fingerprint = Self::pricing_fingerprint();
+        if cache.pricing_fingerprint.as_deref() != Some(fingerprint.as_str()) {
+            cache.files.clear();
+            cache.pricing_fingerprint = Some(fingerprint);
+        }
+
+        let dirs = Self::list_opencode_session_dirs(storage, &range.scan_since_key);
+        let mut keys = Vec::with_capacity(dirs.len());
+
+        for (dir, mtime_unix_ms, size) in dirs {
+            let key = dir.to_string_lossy().to_string();
+            let cached = cache.files.get(&key);
+            if cached.is_some_and(|c| c.mtime_unix_ms == mtime_unix_ms && c.size == size) {
+                keys.push(key);
+                continue;
+            }
+
+            let Ok(parsed) = Self::parse_opencode_session(&dir, &CostUsageDayRange::unbounded()) else {
+                continue;
+            };
+
+            cache.files.insert(
+                key.clone(),
+                CostUsageFileUsage {
+                    mtime_unix_ms,
+                    size,
+                    days: parsed.days,
+                    parsed_bytes: None,
+                    last_model: None,
+                    last_totals: None,
+                    project: parsed.project,
+                    message_hashes: Vec::new(),
+                    first_activity: parsed.first_activity,
+                    last_activity: parsed.last_activity,
+                    hours: parsed.hours,
+                },
+            );
+            keys.push(key);
+        }
+
+        Self::finish_refresh(cache, &[storage]);
+        keys
+    }
+
+    /// Bring cached Codex file usage up to date for every session file in the
+    /// range, re-parsing only new bytes of files that grew. Returns the keys of
+    /// the files in range.
+    pub fn refresh_codex_cache(roots: &[PathBuf], range: &CostUsageDayRange, cache: &mut CostUsageCache) -> Vec<String> {
+        let mut keys = Vec::new();
+        for root in roots {
+            Self::refresh_codex_root(root, range, cache, &mut keys);
+        }
+
+        let roots: Vec<&Path> = roots.iter().map(PathBuf::as_path).collect();
+        Self::finish_refresh(cache, &roots);
+        keys
+    }
+
+    /// Refresh the Codex session files under one root, adding their keys
+    fn refresh_codex_root(root: &Path, range: &CostUsageDayRange, cache: &mut CostUsageCache, keys: &mut Vec<String>) {
+        let files = Self::list_codex_session_files(root, &range.scan_since_key, &range.scan_until_key);
+
+        for path in files {
+            let key = path.to_string_lossy().to_string();
+            let Some((mtime_unix_ms, size)) = Self::file_stamp(&path) else {
+                continue;
+            };
+
+            let cached = cache.files.get(&key).filter(|c| !c.needs_hourly_backfill());
+            if cached.is_some_and(|c| c.mtime_unix_ms == mtime_unix_ms && c.size == size) {
+                keys.push(key);
+                continue;
+            }
+
+            // Append-only growth: continue from where the last parse stopped
+            let resume = cached.filter(|c| size > c.size && c.parsed_bytes.is_some());
+            let (offset, model, totals) = match resume {
+                Some(c) => (c.parsed_bytes.unwrap_or(0), c.last_model.clone(), c.last_totals.clone()),
+                None => (0, None, None),
+            };
+
+            let Ok(parsed) = Self::parse_codex_file(&path, &CostUsageDayRange::unbounded(), offset, model, totals) else {
+                continue;
+            };
+
+            let mut usage = match resume {
+                Some(c) => c.clone(),
+                None => CostUsageFileUsage {
+                    mtime_unix_ms,
+                    size,
+                    days: HashMap::new(),
+                    parsed_bytes: None,
+                    last_model: None,
+                    last_totals: None,
+                    project: None,
+                    message_hashes: Vec::new(),
+                    first_activity: None,
+                    last_activity: None,
+                    hours: HashMap::new(),
+                },
+            };
+            Self::merge_days(&mut usage.days, parsed.days);
+            Self::merge_days(&mut usage.hours, parsed.hours);
+            usage.mtime_unix_ms = mtime_unix_ms;
+            usage.size = size;
+            usage.parsed_bytes = Some(parsed.parsed_bytes);
+            usage.last_model = parsed.last_model;
+            usage.last_totals = parsed.last_totals;
+            usage.project = parsed.project.or(usage.project);
+            usage.first_activity = usage.first_activity.or(parsed.first_activity);
+            usage.last_activity = parsed.last_activity.or(usage.last_activity);
+
+            cache.files.insert(key.clone(), usage);
+            keys.push(key);
+        }
+    }
+
+    /// Bring cached Claude file usage up to date. Unchanged files are reused,
+    /// files that grew are parsed from their last offset and anything else is
+    /// re-parsed. Older files are processed first so they keep ownership of
+    /// messages that later (resumed) sessions replay. Returns the keys of the
+    /// files in range.
+    pub fn refresh_claude_cache(roots: &[PathBuf], range: &CostUsageDayRange, cache: &mut CostUsageCache) -> Vec<String> {
+        // Costs are precomputed per message, so a pricing change invalidates them
+        let fingerprint = Self::pricing_fingerprint();
+        if cache.pricing_fingerprint.as_deref() != Some(fingerprint.as_str()) {
+            cache.files.clear();
+            cache.pricing_fingerprint = Some(fingerprint);
+        }
+
+        let mut files: Vec<(PathBuf, i64, i64)> = roots
+            .iter()
+            .flat_map(|root| Self::list_claude_project_files(root, &range.scan_since_key))
+            .filter_map(|path| Self::file_stamp(&path).map(|(mtime, size)| (path, mtime, size)))
+            .collect();
+        files.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        // Drop entries that must be re-parsed so their messages can be claimed again
+        let mut pending = Vec::new();
+        for (path, mtime_unix_ms, size) in &files {
+            let key = path.to_string_lossy().to_string();
+            let Some(cached) = cache.files.get(&key) else {
+                pending.push((key, None));
+                continue;
+            };
+            let backfill = cached.needs_hourly_backfill();
+            if cached.mtime_unix_ms == *mtime_unix_ms && cached.size == *size && !backfill {
+                continue;
+            }
+            if *size > cached.size && cached.parsed_bytes.is_some() && !backfill {
+                pending.push((key, cached.parsed_bytes));
+            } else {
+                cache.files.remove(&key);
+                pending.push((key, None));
+            }
+        }
+
+        let mut seen: HashSet<u64> = cache
+            .files
+            .values()
+            .flat_map(|usage| usage.message_hashes.iter().copied())
+            .collect();
+
+        for (key, offset) in pending {
+            let path = PathBuf::from(&key);
+            let Some((mtime_unix_ms, size)) = Self::file_stamp(&path) else {
+                continue;
+            };
+            let start = offset.unwrap_or(0);
+            let Ok(parsed) = Self::parse_claude_file(&path, &CostUsageDayRange::unbounded(), start, &mut seen) else {
+                continue;
+            };
+
+            let usage = cache.files.entry(key).or_insert_with(|| CostUsageFileUsage {
+                mtime_unix_ms,
+                size,
+                days: HashMap::new(),
+                parsed_bytes: None,
+                last_model: None,
+                last_totals: None,
+                project: None,
+                message_hashes: Vec::new(),
+                first_activity: None,
+                last_activity: None,
+                hours: HashMap::new(),
+            });
+            Self::merge_days(&mut usage.days, parsed.days);
+            Self::merge_days(&mut usage.hours, parsed.hours);
+            usage.mtime_unix_ms = mtime_unix_ms;
+            usage.size = size;
+            usage.parsed_bytes = Some(parsed.parsed_bytes);
+            usage.project = parsed.project.or(usage.project.take());
+            usage.message_hashes.extend(parsed.message_hashes);
+            usage.first_activity = usage.first_activity.take().or(parsed.first_activity);
+            usage.last_activity = parsed.last_activity.or(usage.last_activity.take());
+        }
+
+        let roots: Vec<&Path> = roots.iter().map(PathBuf::as_path).collect();
+        Self::finish_refresh(cache, &roots);
+        files
+            .into_iter()
+            .map(|(path, _, _)| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Fingerprint of the active pricing table
+    fn pricing_fingerprint() -> String {
+        let table = CostUsagePricing::table();
+        let description: String = table
+            .entries
+            .iter()
+            .map(|e| format!("{:?}{:?}", e.family, e.pricing))
+            .collect();
+        format!("{:016x}", stable_hash(&description))
+    }
+
+    /// Drop vanished files under the scanned roots, rebuild the aggregate and
+    /// stamp the scan time. Entries under other roots are left alone.
+    fn finish_refresh(cache: &mut CostUsageCache, roots: &[&Path]) {
+        cache.files.retain(|key, _| {
+            let path = Path::new(key);
+            !roots.iter().any(|root| path.starts_with(root)) || path.exists()
+        });
+
+        let mut days = HashMap::new();
+        for usage in cache.files.values() {
+            Self::merge_days(&mut days, usage.days.clone());
+        }
+        cache.days = days;
+        cache.last_scan_unix_ms = Utc::now().timestamp_millis();
+    }
+
+    /// Add packed per-day counts from `from` into `into`
+    pub fn merge_days(
+        into: &mut HashMap<String, HashMap<String, Vec<i64>>>,
+        from: HashMap<String, HashMap<String, Vec<i64>>>,
+    ) {
+        for (day, models) in from {
+            let day_models = into.entry(day).or_default();
+            for (model, packed) in models {
+                let existing = day_models.entry(model).or_insert_with(|| vec![0; packed.len()]);
+                if existing.len() < packed.len() {
+                    existing.resize(packed.len(), 0);
+                }
+                for (slot, value) in existing.iter_mut().zip(packed) {
+                    *slot += value;
+                }
+            }
+        }
+    }
+
+    /// Add packed counts to a key -> model -> counts map
+    fn add_packed(map: &mut HashMap<String, HashMap<String, Vec<i64>>>, key: &str, model: &str, counts: &[i64]) {
+        let packed = map
+            .entry(key.to_string())
+            .or_default()
+            .entry(model.to_string())
+            .or_insert_with(|| vec![0; counts.len()]);
+        for (slot, value) in packed.iter_mut().zip(counts) {
+            *slot += value;
+        }
+    }
+
+    /// File modification time (ms) and size, used to detect changes
+    fn file_stamp(path: &Path) -> Option<(i64, i64)> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?;
+        let mtime = chrono::DateTime::<Utc>::from(modified).timestamp_millis();
+        Some((mtime, metadata.len() as i64))
+    }
+
     /// Load cache from disk
     pub fn load_cache(provider: ProviderId, cache_root: Option<&Path>) -> CostUsageCache {
         let cache_path = Self::cache_path(provider, cache_root);
@@ -360,11 +1311,36 @@ impl JsonlScanner {
     }
 }
 
+/// FNV-1a hash; stable across builds, unlike `DefaultHasher`
+pub(crate) fn stable_hash(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// UTC hour key (YYYY-MM-DDTHH) for a logged RFC 3339 timestamp
+pub(crate) fn utc_hour_key(timestamp: &str) -> Option<String> {
+    if timestamp.ends_with('Z') {
+        return timestamp.get(..13).map(str::to_string);
+    }
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc).format("%Y-%m-%dT%H").to_string())
+}
+
 use chrono::Datelike;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_utc_hour_key() {
+        assert_eq!(utc_hour_key("2026-10-18T23:59:01.123Z").as_deref(), Some("2026-10-18T23"));
+        assert_eq!(utc_hour_key("2026-10-18T23:30:00+02:00").as_deref(), Some("2026-10-18T21"));
+        assert_eq!(utc_hour_key("not a time"), None);
+    }
 
     #[test]
     fn test_day_range() {
@@ -394,4 +1370,225 @@ mod tests {
         assert_eq!(date.month(), 1);
         assert_eq!(date.day(), 15);
     }
+
+    #[test]
+    fn test_last_rate_limits_in_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rollout.jsonl");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"timestamp":"2026-01-15T10:00:00Z","type":"event_msg","payload":{"type":"token_count","rate_limits":{"primary":{"used_percent":10.0,"window_minutes":300,"resets_in_seconds":600}}}}"#,
+                "\n",
+                r#"{"timestamp":"2026-01-15T11:00:00Z","type":"event_msg","payload":{"type":"token_count","rate_limits":{"primary":{"used_percent":42.5,"window_minutes":300,"resets_at":1768478400},"secondary":{"used_percent":7.0,"window_minutes":10080}}}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let limits = JsonlScanner::last_rate_limits_in_file(&path).unwrap();
+
+        let primary = limits.primary.unwrap();
+        assert_eq!(primary.used_percent, 42.5);
+        assert_eq!(primary.window_minutes, Some(300));
+        assert_eq!(primary.resets_at, DateTime::from_timestamp(1768478400, 0));
+        assert_eq!(limits.secondary.unwrap().window_minutes, Some(10080));
+        assert_eq!(limits.observed_at.to_rfc3339(), "2026-01-15T11:00:00+00:00");
+    }
+
+    #[test]
+    fn test_merge_days() {
+        let mut into: HashMap<String, HashMap<String, Vec<i64>>> = HashMap::new();
+        let mut from: HashMap<String, HashMap<String, Vec<i64>>> = HashMap::new();
+        from.entry("2026-01-15".to_string())
+            .or_default()
+            .insert("gpt-5".to_string(), vec![10, 2, 5]);
+
+        JsonlScanner::merge_days(&mut into, from.clone());
+        JsonlScanner::merge_days(&mut into, from);
+
+        assert_eq!(into["2026-01-15"]["gpt-5"], vec![20, 4, 10]);
+    }
+
+    #[test]
+    fn test_finish_refresh_prunes_scanned_roots_only() {
+        let usage = |tokens: i64| CostUsageFileUsage {
+            mtime_unix_ms: 0,
+            size: 0,
+            days: HashMap::from([(
+                "2026-01-15".to_string(),
+                HashMap::from([("gpt-5".to_string(), vec![tokens, 0, 0])]),
+            )]),
+            parsed_bytes: None,
+            last_model: None,
+            last_totals: None,
+            project: None,
+            message_hashes: Vec::new(),
+            first_activity: None,
+            last_activity: None,
+            hours: HashMap::new(),
+        };
+        let scanned = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let mut cache = CostUsageCache::default();
+        let gone = scanned.path().join("gone.jsonl").to_string_lossy().to_string();
+        let kept = other.path().join("kept.jsonl").to_string_lossy().to_string();
+        cache.files.insert(gone.clone(), usage(10));
+        cache.files.insert(kept.clone(), usage(5));
+
+        JsonlScanner::finish_refresh(&mut cache, &[scanned.path()]);
+
+        assert!(!cache.files.contains_key(&gone));
+        assert!(cache.files.contains_key(&kept));
+        assert_eq!(cache.days["2026-01-15"]["gpt-5"], vec![5, 0, 0]);
+    }
+
+    #[test]
+    fn test_parse_codex_file_resumes_before_partial_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rollout.jsonl");
+        let event = |hour: u32, input: i64, output: i64| {
+            format!(
+                r#"{{"timestamp":"2026-01-15T{:02}:00:00Z","type":"event_msg","payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":{},"cached_input_tokens":0,"output_tokens":{}}}}}}}}}"#,
+                hour, input, output
+            )
+        };
+        let complete = format!("{}\n", event(10, 100, 10));
+        let partial = event(11, 300, 10);
+        let (head, tail) = partial.split_at(40);
+        fs::write(&path, format!("{}{}", complete, head)).unwrap();
+
+        let range = CostUsageDayRange::unbounded();
+        let parsed = JsonlScanner::parse_codex_file(&path, &range, 0, None, None).unwrap();
+        assert_eq!(parsed.parsed_bytes, complete.len() as i64);
+        assert_eq!(parsed.days["2026-01-15"]["gpt-5"], vec![100, 0, 10]);
+
+        // The writer finishes the line; the next poll picks up the whole event
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{}", tail).unwrap();
+        drop(file);
+
+        let resumed =
+            JsonlScanner::parse_codex_file(&path, &range, parsed.parsed_bytes, parsed.last_model, parsed.last_totals)
+                .unwrap();
+        assert_eq!(resumed.days["2026-01-15"]["gpt-5"], vec![200, 0, 0]);
+        assert_eq!(resumed.parsed_bytes, fs::metadata(&path).unwrap().len() as i64);
+    }
+
+    #[test]
+    fn test_parse_claude_file_buckets_by_event_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"type":"assistant","timestamp":"2026-01-14T23:59:00Z","cwd":"/work/app","message":{"model":"claude-sonnet-4-5-20250929","usage":{"input_tokens":100,"output_tokens":10}}}"#,
+                "\n",
+                r#"{"type":"assistant","timestamp":"2026-01-15T00:01:00Z","message":{"model":"claude-sonnet-4-5-20250929","usage":{"input_tokens":50,"cache_read_input_tokens":1000,"output_tokens":5}}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let mut seen = HashSet::new();
+        let parsed = JsonlScanner::parse_claude_file(&path, &CostUsageDayRange::unbounded(), 0, &mut seen).unwrap();
+
+        assert_eq!(parsed.days.len(), 2);
+        assert_eq!(parsed.days["2026-01-14"]["claude-sonnet-4-5"][0], 100);
+        assert_eq!(parsed.days["2026-01-15"]["claude-sonnet-4-5"][1], 1000);
+        assert_eq!(parsed.project.as_deref(), Some("/work/app"));
+    }
+
+    #[test]
+    fn test_parse_claude_file_dedups_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = r#"{"type":"assistant","timestamp":"2026-01-15T10:00:00Z","requestId":"req_1","message":{"id":"msg_1","model":"claude-sonnet-4-5","usage":{"input_tokens":100,"output_tokens":10}}}"#;
+        let original = dir.path().join("original.jsonl");
+        let resumed = dir.path().join("resumed.jsonl");
+        fs::write(&original, format!("{}\n{}\n", line, line)).unwrap();
+        fs::write(&resumed, format!("{}\n", line)).unwrap();
+
+        let range = CostUsageDayRange::unbounded();
+        let mut seen = HashSet::new();
+        let first = JsonlScanner::parse_claude_file(&original, &range, 0, &mut seen).unwrap();
+        let second = JsonlScanner::parse_claude_file(&resumed, &range, 0, &mut seen).unwrap();
+
+        assert_eq!(first.days["2026-01-15"]["claude-sonnet-4-5"][0], 100);
+        assert_eq!(first.message_hashes.len(), 1);
+        assert!(second.days.is_empty());
+    }
+
+    #[test]
+    fn test_parse_gemini_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let chats = dir.path().join("0123abcd").join("chats");
+        fs::create_dir_all(&chats).unwrap();
+        let path = chats.join("session-2026-01-15T10-00-1a2b3c4d.json");
+        fs::write(
+            &path,
+            r#"{
+                "sessionId": "1a2b3c4d",
+                "projectHash": "0123abcd",
+                "messages": [
+                    {"id": "u1", "timestamp": "2026-01-15T10:00:00.000Z", "type": "user", "content": "hi"},
+                    {"id": "g1", "timestamp": "2026-01-15T10:00:05.000Z", "type": "gemini", "model": "gemini-2.5-flash",
+                     "tokens": {"input": 1000, "output": 100, "cached": 400, "thoughts": 50, "tool": 0, "total": 1150}},
+                    {"id": "g2", "timestamp": "2026-01-16T09:00:00.000Z", "type": "gemini", "model": "gemini-2.5-flash",
+                     "tokens": {"input": 2000, "output": 10, "cached": 0, "thoughts": 0, "tool": 20, "total": 2030}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let files = JsonlScanner::list_gemini_session_files(dir.path(), "2000-01-01");
+        let parsed = JsonlScanner::parse_gemini_file(&path, &CostUsageDayRange::unbounded()).unwrap();
+
+        assert_eq!(files, vec![path]);
+        let day = &parsed.days["2026-01-15"]["gemini-2.5-flash"];
+        assert_eq!(&day[..3], &[1000, 400, 150]);
+        // 600 input at $0.30, 400 cached at $0.03, 150 output at $2.50 per 1M
+        assert_eq!(day[3], 180_000 + 12_000 + 375_000);
+        assert_eq!(parsed.days["2026-01-16"]["gemini-2.5-flash"][0], 2020);
+        assert_eq!(parsed.first_activity.as_deref(), Some("2026-01-15T10:00:05.000Z"));
+        assert_eq!(parsed.hours.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_opencode_session() {
+        let storage = tempfile::tempdir().unwrap();
+        let session = storage.path().join("message").join("ses_abc");
+        fs::create_dir_all(&session).unwrap();
+        // 2026-01-15T10:00:00Z and 2026-01-15T11:00:00Z
+        fs::write(
+            session.join("msg_1.json"),
+            r#"{"id":"msg_1","sessionID":"ses_abc","role":"user","time":{"created":1768471200000}}"#,
+        )
+        .unwrap();
+        fs::write(
+            session.join("msg_2.json"),
+            r#"{"id":"msg_2","sessionID":"ses_abc","role":"assistant","time":{"created":1768471200000},
+                "providerID":"openrouter","modelID":"qwen3-coder","cost":0.25,"path":{"cwd":"/work/app","root":"/work/app"},
+                "tokens":{"input":1000,"output":100,"reasoning":20,"cache":{"read":500,"write":0}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            session.join("msg_3.json"),
+            r#"{"id":"msg_3","sessionID":"ses_abc","role":"assistant","time":{"created":1768474800000},
+                "providerID":"anthropic","modelID":"claude-sonnet-4-5","cost":0,
+                "tokens":{"input":100000,"output":0,"reasoning":0,"cache":{"read":0,"write":0}}}"#,
+        )
+        .unwrap();
+
+        let dirs = JsonlScanner::list_opencode_session_dirs(storage.path(), "2000-01-01");
+        let parsed = JsonlScanner::parse_opencode_session(&session, &CostUsageDayRange::unbounded()).unwrap();
+
+        assert_eq!(dirs.len(), 1);
+        let day = &parsed.days["2026-01-15"];
+        assert_eq!(day["openrouter/qwen3-coder"], vec![1000, 500, 0, 120, 250_000_000]);
+        // No cost from OpenCode (subscription), so it is estimated: 100k Sonnet input at $3/1M
+        assert_eq!(day["anthropic/claude-sonnet-4-5"][4], 300_000_000);
+        assert_eq!(parsed.project.as_deref(), Some("/work/app"));
+        assert_eq!(parsed.first_activity.as_deref(), Some("2026-01-15T10:00:00.000Z"));
+        assert_eq!(parsed.hours.len(), 2);
+    }
 }
@@ -0,0 +1,204 @@
+//! Agent Log Roots
+//!
+//! Where Codex and Claude logs are read from: the default home locations,
+//! extra directories from settings (e.g. separate work and personal config
+//! dirs) and, on Windows, the homes inside running WSL distributions. Each
+//! root carries a label so costs can be split per environment.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::{JsonlScanner, ProviderId};
+
+/// Label for the roots found in the local home directory
+pub const LOCAL_ROOT_LABEL: &str = "Local";
+
+/// An extra log directory configured in settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRoot {
+    /// "codex" or "claude"
+    pub provider: String,
+    /// Codex home or Claude config dir (`sessions`/`projects` is appended
+    /// unless the path already points at it)
+    pub path: PathBuf,
+    /// Name costs from this root are shown under (defaults to the path)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl LogRoot {
+    /// Directory holding the log files
+    pub fn logs_dir(&self) -> Option<PathBuf> {
+        let leaf = match ProviderId::from_cli_name(&self.provider)? {
+            ProviderId::Codex => "sessions",
+            ProviderId::Claude => "projects",
+            _ => return None,
+        };
+        if self.path.file_name().is_some_and(|name| name == leaf) {
+            Some(self.path.clone())
+        } else {
+            Some(self.path.join(leaf))
+        }
+    }
+}
+
+/// A log directory and the environment label its costs belong to
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabeledRoot {
+    pub path: PathBuf,
+    pub label: String,
+}
+
+/// Every log directory to scan, per provider
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogRoots {
+    pub codex: Vec<LabeledRoot>,
+    pub claude: Vec<LabeledRoot>,
+}
+
+impl LogRoots {
+    /// Default home locations only
+    pub fn local() -> Self {
+        let mut roots = Self::default();
+        if let Some(path) = JsonlScanner::default_codex_sessions_root() {
+            roots.add(ProviderId::Codex, path, LOCAL_ROOT_LABEL);
+        }
+        for path in JsonlScanner::default_claude_projects_roots() {
+            roots.add(ProviderId::Claude, path, LOCAL_ROOT_LABEL);
+        }
+        roots
+    }
+
+    /// Default locations, configured roots and, if `include_wsl`, WSL homes.
+    /// Directories that don't exist are left out.
+    pub fn discover(configured: &[LogRoot], include_wsl: bool) -> Self {
+        let mut roots = Self::local();
+
+        for root in configured {
+            let (Some(provider), Some(path)) = (ProviderId::from_cli_name(&root.provider), root.logs_dir()) else {
+                tracing::warn!("Ignoring log root {} for '{}'", root.path.display(), root.provider);
+                continue;
+            };
+            let label = root.label.clone().unwrap_or_else(|| root.path.display().to_string());
+            roots.add(provider, path, &label);
+        }
+
+        if include_wsl {
+            for (distro, home) in wsl_homes() {
+                let label = format!("WSL: {}", distro);
+                roots.add(ProviderId::Codex, home.join(".codex").join("sessions"), &label);
+                roots.add(ProviderId::Claude, home.join(".claude").join("projects"), &label);
+                roots.add(ProviderId::Claude, home.join(".config").join("claude").join("projects"), &label);
+            }
+        }
+
+        roots
+    }
+
+    /// Add an existing directory once; the first label given for it wins
+    fn add(&mut self, provider: ProviderId, path: PathBuf, label: &str) {
+        let list = match provider {
+            ProviderId::Codex => &mut self.codex,
+            ProviderId::Claude => &mut self.claude,
+            _ => return,
+        };
+        if path.is_dir() && !list.iter().any(|r| r.path == path) {
+            list.push(LabeledRoot {
+                path,
+                label: label.to_string(),
+            });
+        }
+    }
+
+    /// Roots for a provider
+    pub fn for_provider(&self, provider: ProviderId) -> &[LabeledRoot] {
+        match provider {
+            ProviderId::Codex => &self.codex,
+            ProviderId::Claude => &self.claude,
+            _ => &[],
+        }
+    }
+
+    /// Label of the root a log file lives under
+    pub fn label_for(&self, file: &Path) -> Option<&str> {
+        self.codex
+            .iter()
+            .chain(&self.claude)
+            .filter(|root| file.starts_with(&root.path))
+            .max_by_key(|root| root.path.components().count())
+            .map(|root| root.label.as_str())
+    }
+}
+
+/// (distribution, home directory) for each user in running WSL distributions
+#[cfg(windows)]
+fn wsl_homes() -> Vec<(String, PathBuf)> {
+    // Both prefixes list the same running distributions; older builds only have wsl$
+    for prefix in [r"\\wsl.localhost\", r"\\wsl$\"] {
+        let Ok(distros) = std::fs::read_dir(prefix) else {
+            continue;
+        };
+
+        let mut homes = Vec::new();
+        for distro in distros.flatten() {
+            let name = distro.file_name().to_string_lossy().to_string();
+            if name.starts_with("docker-desktop") {
+                continue;
+            }
+            let root = distro.path();
+            if let Ok(users) = std::fs::read_dir(root.join("home")) {
+                homes.extend(users.flatten().map(|user| (name.clone(), user.path())));
+            }
+            homes.push((name, root.join("root")));
+        }
+        return homes;
+    }
+    Vec::new()
+}
+
+#[cfg(not(windows))]
+fn wsl_homes() -> Vec<(String, PathBuf)> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_roots_and_labels() {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path();
+        let work = base.join("work-codex");
+        let personal = base.join("personal-claude").join("projects");
+        std::fs::create_dir_all(work.join("sessions")).unwrap();
+        std::fs::create_dir_all(&personal).unwrap();
+
+        let configured = vec![
+            LogRoot {
+                provider: "codex".to_string(),
+                path: work.clone(),
+                label: Some("Work".to_string()),
+            },
+            LogRoot {
+                provider: "claude".to_string(),
+                path: personal.clone(),
+                label: None,
+            },
+            LogRoot {
+                provider: "cursor".to_string(),
+                path: base.to_path_buf(),
+                label: None,
+            },
+        ];
+        let roots = LogRoots::discover(&configured, false);
+
+        let codex = roots.codex.iter().find(|r| r.path == work.join("sessions")).unwrap();
+        assert_eq!(codex.label, "Work");
+        assert!(roots.claude.iter().any(|r| r.path == personal));
+
+        let file = work.join("sessions").join("2026").join("rollout.jsonl");
+        assert_eq!(roots.label_for(&file), Some("Work"));
+        assert_eq!(roots.label_for(Path::new("/nowhere/file.jsonl")), None);
+    }
+}
@@ -3,30 +3,40 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+mod budget;
+mod cost_forecast;
 mod cost_pricing;
 mod credential_migration;
 mod credentials;
 mod fetch_plan;
 mod jsonl_scanner;
+mod log_roots;
 mod openai_dashboard;
+mod project_attribution;
 mod provider;
 mod rate_window;
 mod redactor;
+mod session_blocks;
 mod session_quota;
 mod token_accounts;
 mod usage_pace;
 mod usage_snapshot;
 mod widget_snapshot;
 
+pub use budget::*;
+pub use cost_forecast::*;
 pub use cost_pricing::*;
 pub use credential_migration::*;
 pub use credentials::*;
 pub use fetch_plan::*;
 pub use jsonl_scanner::*;
+pub use log_roots::*;
 pub use openai_dashboard::*;
+pub use project_attribution::*;
 pub use provider::*;
 pub use rate_window::*;
 pub use redactor::*;
+pub use session_blocks::*;
 pub use session_quota::*;
 pub use token_accounts::*;
 pub use usage_pace::*;
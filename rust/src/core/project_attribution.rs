@@ -0,0 +1,180 @@
+//! Project Attribution
+//!
+//! Maps the working directory recorded in session logs to a project name.
+//! Directories are resolved to their git repository root so sessions started
+//! in subfolders count towards the same repo, and user-defined aliases can
+//! rename projects or merge several paths into one.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Resolves session working directories to project names
+#[derive(Debug, Default)]
+pub struct ProjectResolver {
+    /// (normalized path or name, project name), longest keys first
+    aliases: Vec<(String, String)>,
+    /// Resolved names keyed by the raw working directory
+    cache: HashMap<String, String>,
+}
+
+impl ProjectResolver {
+    /// Create a resolver from alias settings.
+    ///
+    /// Keys are directory paths (matched as path prefixes) or bare repository
+    /// names; values are the project name to report. Mapping several keys to
+    /// the same name merges them.
+    pub fn new(aliases: &HashMap<String, String>) -> Self {
+        let mut aliases: Vec<(String, String)> = aliases
+            .iter()
+            .filter(|(key, name)| !key.trim().is_empty() && !name.trim().is_empty())
+            .map(|(key, name)| (normalize_path(key), name.trim().to_string()))
+            .collect();
+        aliases.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            aliases,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Project name for a working directory: an alias if one matches, else
+    /// the git repository root, else the directory itself. Relative names
+    /// (undecoded Claude folders) are never looked up on disk.
+    pub fn resolve(&mut self, cwd: &str) -> String {
+        if let Some(name) = self.cache.get(cwd) {
+            return name.clone();
+        }
+
+        let root = Some(Path::new(cwd))
+            .filter(|dir| dir.has_root())
+            .and_then(find_git_root)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| cwd.to_string());
+        let name = self
+            .alias_for(cwd)
+            .or_else(|| self.alias_for(&root))
+            .unwrap_or_else(|| normalize_path(&root));
+
+        self.cache.insert(cwd.to_string(), name.clone());
+        name
+    }
+
+    fn alias_for(&self, path: &str) -> Option<String> {
+        let normalized = normalize_path(path);
+        let dir_name = normalized.rsplit('/').next().unwrap_or(&normalized);
+
+        self.aliases
+            .iter()
+            .find(|(key, _)| {
+                if key.contains('/') {
+                    is_path_prefix(key, &normalized)
+                } else {
+                    key.eq_ignore_ascii_case(dir_name)
+                }
+            })
+            .map(|(_, name)| name.clone())
+    }
+
+    /// Working directory encoded in a Claude project folder name
+    /// (`-home-me-app` or `C--Users-me-app`), used when a session file has
+    /// no `cwd` line. Dashes inside folder names are ambiguous, so the
+    /// decoded path is only trusted if it exists; otherwise the folder name
+    /// is returned as-is.
+    pub fn claude_project_from_path(session_file: &Path) -> Option<String> {
+        let encoded = session_file.parent()?.file_name()?.to_string_lossy().to_string();
+
+        let decoded = if let Some(rest) = encoded.strip_prefix('-') {
+            format!("/{}", rest.replace('-', "/"))
+        } else if encoded.get(1..3) == Some("--") {
+            format!("{}:/{}", &encoded[..1], encoded[3..].replace('-', "/"))
+        } else {
+            return None;
+        };
+
+        if Path::new(&decoded).is_dir() {
+            Some(decoded)
+        } else {
+            Some(encoded)
+        }
+    }
+}
+
+/// Walk up from `dir` to the directory containing `.git`
+pub fn find_git_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|candidate| candidate.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Forward slashes, no trailing separator
+fn normalize_path(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        path
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Whether `prefix` is `path` or one of its parent directories
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+    let matches = |a: &str, b: &str| {
+        if cfg!(windows) {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    };
+
+    match path.get(..prefix.len()) {
+        Some(head) if matches(head, prefix) => {
+            path.len() == prefix.len() || path[prefix.len()..].starts_with('/')
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_root_and_aliases() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path().join("webapp");
+        let nested = repo.join("src").join("api");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+
+        let mut resolver = ProjectResolver::new(&HashMap::new());
+        let root = normalize_path(&repo.to_string_lossy());
+        assert_eq!(resolver.resolve(&nested.to_string_lossy()), root);
+
+        let mut aliases = HashMap::new();
+        aliases.insert("webapp".to_string(), "Web".to_string());
+        aliases.insert("/srv/legacy-web".to_string(), "Web".to_string());
+        let mut resolver = ProjectResolver::new(&aliases);
+        assert_eq!(resolver.resolve(&nested.to_string_lossy()), "Web");
+        assert_eq!(resolver.resolve("/srv/legacy-web/tools"), "Web");
+        assert_eq!(resolver.resolve("/srv/legacy-website"), "/srv/legacy-website");
+    }
+
+    #[test]
+    fn test_relative_name_skips_git_lookup() {
+        // Tests run inside a git checkout, which a relative lookup would find
+        let mut resolver = ProjectResolver::new(&HashMap::new());
+        assert_eq!(resolver.resolve("-home-me-app"), "-home-me-app");
+    }
+
+    #[test]
+    fn test_claude_project_from_path() {
+        let file = Path::new("/home/me/.claude/projects/-nonexistent-dir-app/abc.jsonl");
+        assert_eq!(
+            ProjectResolver::claude_project_from_path(file).as_deref(),
+            Some("-nonexistent-dir-app")
+        );
+        let codex = Path::new("/home/me/.codex/sessions/2026/10/18/rollout.jsonl");
+        assert_eq!(ProjectResolver::claude_project_from_path(codex), None);
+    }
+}
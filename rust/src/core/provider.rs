@@ -176,6 +176,8 @@ pub enum SourceMode {
     Web,
     /// Use CLI probe
     Cli,
+    /// Use local log files only (offline estimate)
+    Local,
 }
 
 impl SourceMode {
@@ -186,6 +188,7 @@ impl SourceMode {
             "oauth" => Some(SourceMode::OAuth),
             "web" => Some(SourceMode::Web),
             "cli" => Some(SourceMode::Cli),
+            "local" => Some(SourceMode::Local),
             _ => None,
         }
     }
@@ -430,6 +433,7 @@ mod tests {
         assert_eq!(SourceMode::from_str("oauth"), Some(SourceMode::OAuth));
         assert_eq!(SourceMode::from_str("web"), Some(SourceMode::Web));
         assert_eq!(SourceMode::from_str("cli"), Some(SourceMode::Cli));
+        assert_eq!(SourceMode::from_str("local"), Some(SourceMode::Local));
         assert_eq!(SourceMode::from_str("AUTO"), Some(SourceMode::Auto));
         assert_eq!(SourceMode::from_str("invalid"), None);
     }
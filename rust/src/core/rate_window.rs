@@ -87,6 +87,43 @@ impl RateWindow {
     }
 }
 
+/// Which of a snapshot's windows to look at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageWindow {
+    /// Primary window (e.g. Claude's 5-hour session)
+    Session,
+    /// Secondary window (weekly or monthly)
+    Weekly,
+    /// Model-specific window (e.g. Opus)
+    Model,
+}
+
+impl UsageWindow {
+    pub const ALL: [UsageWindow; 3] = [UsageWindow::Session, UsageWindow::Weekly, UsageWindow::Model];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UsageWindow::Session => "session",
+            UsageWindow::Weekly => "weekly",
+            UsageWindow::Model => "model",
+        }
+    }
+}
+
+impl std::str::FromStr for UsageWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "session" | "primary" => Ok(UsageWindow::Session),
+            "weekly" | "week" | "secondary" => Ok(UsageWindow::Weekly),
+            "model" | "opus" | "tertiary" => Ok(UsageWindow::Model),
+            _ => Err(format!("Invalid window: {}. Use session, weekly or model", s)),
+        }
+    }
+}
+
 impl Default for RateWindow {
     fn default() -> Self {
         Self::new(0.0)
@@ -0,0 +1,341 @@
+//! Claude Session Blocks
+//!
+//! Reconstructs Claude's rolling 5-hour billing blocks from local session
+//! logs. A block starts at the hour of the first message after the previous
+//! block ended and lasts five hours; blocks give an offline estimate of the
+//! session window when OAuth and cookies are unavailable.
+
+#![allow(dead_code)]
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::core::{stable_hash, CostUsageDayRange, CostUsagePricing, JsonlScanner, RateWindow};
+
+/// Length of a Claude billing block
+pub const SESSION_BLOCK_HOURS: i64 = 5;
+
+/// One assistant message with usage
+#[derive(Debug, Clone)]
+pub struct UsageEvent {
+    pub timestamp: DateTime<Utc>,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cost_usd: f64,
+}
+
+impl UsageEvent {
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+}
+
+/// A reconstructed 5-hour block
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionBlock {
+    /// Block start (the hour of its first message)
+    pub start: DateTime<Utc>,
+    /// Block end (start + 5h)
+    pub end: DateTime<Utc>,
+    /// Timestamp of the last message in the block
+    pub last_activity: DateTime<Utc>,
+    /// Whether the block is still running
+    pub is_active: bool,
+    pub message_count: u32,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cost_usd: f64,
+    /// Models used in the block
+    pub models: Vec<String>,
+}
+
+impl SessionBlock {
+    fn starting_with(event: &UsageEvent) -> Self {
+        let start = event
+            .timestamp
+            .duration_trunc(Duration::hours(1))
+            .unwrap_or(event.timestamp);
+
+        Self {
+            start,
+            end: start + Duration::hours(SESSION_BLOCK_HOURS),
+            last_activity: event.timestamp,
+            is_active: false,
+            message_count: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd: 0.0,
+            models: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, event: &UsageEvent) {
+        self.last_activity = event.timestamp;
+        self.message_count += 1;
+        self.input_tokens += event.input_tokens;
+        self.output_tokens += event.output_tokens;
+        self.cache_read_tokens += event.cache_read_tokens;
+        self.cache_creation_tokens += event.cache_creation_tokens;
+        self.cost_usd += event.cost_usd;
+        if !self.models.contains(&event.model) {
+            self.models.push(event.model.clone());
+        }
+    }
+
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+
+    /// Cost per hour since the block started (active blocks only)
+    pub fn burn_rate_per_hour(&self, now: DateTime<Utc>) -> Option<f64> {
+        if !self.is_active {
+            return None;
+        }
+        let elapsed_minutes = (now - self.start).num_minutes().max(1) as f64;
+        Some(self.cost_usd / elapsed_minutes * 60.0)
+    }
+
+    /// Projected cost at the end of the block if the current burn rate holds
+    pub fn projected_cost_usd(&self, now: DateTime<Utc>) -> Option<f64> {
+        let rate = self.burn_rate_per_hour(now)?;
+        let remaining_hours = (self.end - now).num_minutes().max(0) as f64 / 60.0;
+        Some(self.cost_usd + rate * remaining_hours)
+    }
+
+    /// Time left until the block ends
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        (self.end - now).max(Duration::zero())
+    }
+}
+
+/// Session block reconstruction
+pub struct SessionBlocks;
+
+impl SessionBlocks {
+    /// Read Claude usage events newer than `since` from all project roots,
+    /// counting each message id + request id once
+    pub fn load_claude_events(roots: &[PathBuf], since: DateTime<Utc>) -> Vec<UsageEvent> {
+        let since_key = CostUsageDayRange::day_key(since.date_naive());
+
+        let mut files: Vec<(PathBuf, std::time::SystemTime)> = roots
+            .iter()
+            .flat_map(|root| JsonlScanner::list_claude_project_files(root, &since_key))
+            .filter_map(|path| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect();
+        files.sort_by_key(|f| f.1);
+
+        let mut seen = HashSet::new();
+        let mut events = Vec::new();
+        for (path, _) in files {
+            Self::read_events(&path, since, &mut seen, &mut events);
+        }
+
+        events.sort_by_key(|e| e.timestamp);
+        events
+    }
+
+    fn read_events(path: &Path, since: DateTime<Utc>, seen: &mut HashSet<u64>, events: &mut Vec<UsageEvent>) {
+        let Ok(file) = File::open(path) else {
+            return;
+        };
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if !line.contains("\"usage\"") {
+                continue;
+            }
+            let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if obj.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+                continue;
+            }
+
+            let Some(timestamp) = obj
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|ts| ts.with_timezone(&Utc))
+            else {
+                continue;
+            };
+            if timestamp < since {
+                continue;
+            }
+
+            let Some(message) = obj.get("message") else {
+                continue;
+            };
+            let Some(usage) = message.get("usage") else {
+                continue;
+            };
+
+            let message_id = message.get("id").and_then(|v| v.as_str());
+            let request_id = obj.get("requestId").and_then(|v| v.as_str());
+            if let (Some(message_id), Some(request_id)) = (message_id, request_id) {
+                if !seen.insert(stable_hash(&format!("{}:{}", message_id, request_id))) {
+                    continue;
+                }
+            }
+
+            let model = message.get("model").and_then(|v| v.as_str()).unwrap_or("unknown");
+            let tokens = |key: &str| usage.get(key).and_then(|v| v.as_i64()).unwrap_or(0).max(0);
+            let input = tokens("input_tokens");
+            let output = tokens("output_tokens");
+            let cache_read = tokens("cache_read_input_tokens");
+            let cache_creation = tokens("cache_creation_input_tokens");
+
+            let cost = CostUsagePricing::claude_cost_usd_on(
+                model,
+                timestamp.date_naive(),
+                input,
+                cache_read,
+                cache_creation,
+                output,
+            )
+            .unwrap_or(0.0);
+
+            events.push(UsageEvent {
+                timestamp,
+                model: CostUsagePricing::normalize_claude_model(model),
+                input_tokens: input,
+                output_tokens: output,
+                cache_read_tokens: cache_read,
+                cache_creation_tokens: cache_creation,
+                cost_usd: cost,
+            });
+        }
+    }
+
+    /// Group time-sorted events into 5-hour blocks
+    pub fn build_blocks(events: &[UsageEvent], now: DateTime<Utc>) -> Vec<SessionBlock> {
+        let mut blocks: Vec<SessionBlock> = Vec::new();
+
+        for event in events {
+            let starts_new = match blocks.last() {
+                Some(block) => {
+                    event.timestamp >= block.end
+                        || event.timestamp - block.last_activity >= Duration::hours(SESSION_BLOCK_HOURS)
+                }
+                None => true,
+            };
+            if starts_new {
+                blocks.push(SessionBlock::starting_with(event));
+            }
+            if let Some(block) = blocks.last_mut() {
+                block.add(event);
+            }
+        }
+
+        if let Some(block) = blocks.last_mut() {
+            block.is_active = now < block.end;
+        }
+
+        blocks
+    }
+
+    /// Blocks from the last `days` days of Claude logs
+    pub fn claude_blocks(days: u32, now: DateTime<Utc>) -> Vec<SessionBlock> {
+        let roots = JsonlScanner::default_claude_projects_roots();
+        // Start a block early so one spanning the cutoff is complete
+        let since = now - Duration::days(days as i64) - Duration::hours(SESSION_BLOCK_HOURS);
+        let events = Self::load_claude_events(&roots, since);
+        Self::build_blocks(&events, now)
+    }
+
+    /// Largest token total of any finished block, used as the implied limit
+    pub fn max_completed_tokens(blocks: &[SessionBlock]) -> Option<i64> {
+        blocks
+            .iter()
+            .filter(|b| !b.is_active)
+            .map(|b| b.total_tokens())
+            .filter(|t| *t > 0)
+            .max()
+    }
+
+    /// Session window estimated from local logs. Usage is the active block's
+    /// tokens relative to the busiest finished block, capped at 100%, so it
+    /// is a relative estimate rather than Anthropic's own percentage. Only
+    /// used when local logs are asked for explicitly.
+    pub fn estimated_session_window(blocks: &[SessionBlock]) -> RateWindow {
+        let window_minutes = Some((SESSION_BLOCK_HOURS * 60) as u32);
+        let description = Some("Estimated from local logs".to_string());
+
+        match blocks.last().filter(|b| b.is_active) {
+            Some(active) => {
+                let percent = Self::max_completed_tokens(blocks)
+                    .map(|limit| (active.total_tokens() as f64 / limit as f64 * 100.0).clamp(0.0, 100.0))
+                    .unwrap_or(0.0);
+                RateWindow::with_details(percent, window_minutes, Some(active.end), description)
+            }
+            None => RateWindow::with_details(0.0, window_minutes, None, description),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(hour: u32, minute: u32, tokens: i64) -> UsageEvent {
+        UsageEvent {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 10, hour, minute, 0).unwrap(),
+            model: "claude-sonnet-4-5".to_string(),
+            input_tokens: tokens,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            cost_usd: tokens as f64 / 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_build_blocks() {
+        let events = vec![event(9, 15, 1000), event(11, 0, 500), event(14, 10, 200), event(20, 0, 100)];
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 21, 0, 0).unwrap();
+        let blocks = SessionBlocks::build_blocks(&events, now);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].start, Utc.with_ymd_and_hms(2026, 3, 10, 9, 0, 0).unwrap());
+        assert_eq!(blocks[0].total_tokens(), 1500);
+        assert_eq!(blocks[1].start, Utc.with_ymd_and_hms(2026, 3, 10, 14, 0, 0).unwrap());
+        assert!(!blocks[1].is_active);
+        assert!(blocks[2].is_active);
+    }
+
+    #[test]
+    fn test_projection_and_estimate() {
+        let events = vec![event(9, 0, 1000), event(15, 0, 500)];
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 16, 0, 0).unwrap();
+        let blocks = SessionBlocks::build_blocks(&events, now);
+
+        let active = blocks.last().unwrap();
+        // $0.50 over 60 minutes, 4 hours left
+        let projected = active.projected_cost_usd(now).unwrap();
+        assert!((projected - 2.5).abs() < 1e-9);
+
+        let window = SessionBlocks::estimated_session_window(&blocks);
+        assert!((window.used_percent - 50.0).abs() < 1e-9);
+        assert_eq!(window.resets_at, Some(active.end));
+
+        // A block busier than any finished one is capped
+        let events = vec![event(9, 0, 1000), event(15, 0, 5000)];
+        let blocks = SessionBlocks::build_blocks(&events, now);
+        let window = SessionBlocks::estimated_session_window(&blocks);
+        assert_eq!(window.used_percent, 100.0);
+    }
+}
@@ -1,13 +1,16 @@
 //! Session Quota Notifications
 //!
-//! Monitors session quota changes and sends notifications when:
-//! - Quota becomes depleted (0% remaining)
-//! - Quota is restored (becomes available again)
+//! Monitors usage windows and reports when:
+//! - Session quota becomes depleted (0% remaining)
+//! - Session quota is restored (becomes available again)
+//! - A weekly/model window has reset
 
 #![allow(dead_code)]
-#![allow(unused_imports)]
 
-use crate::core::ProviderId;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::core::{ProviderId, RateWindow, UsageWindow};
 
 /// Threshold for considering quota as depleted (0.01%)
 const DEPLETED_THRESHOLD: f64 = 0.0001;
@@ -60,71 +63,133 @@ impl SessionQuotaLogic {
     }
 }
 
-/// Session quota notifier that tracks state and sends notifications
+/// Something worth telling the user about a usage window
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuotaEvent {
+    /// Session quota just ran out
+    Depleted { provider: ProviderId, used_percent: f64 },
+    /// Depleted session quota is available again
+    Restored { provider: ProviderId, used_percent: f64 },
+    /// A weekly or model window passed its reset time
+    Reset {
+        provider: ProviderId,
+        window: UsageWindow,
+        /// Usage before the reset
+        used_percent: f64,
+    },
+}
+
+/// Last known state of one provider window
+#[derive(Debug, Clone)]
+struct TrackedWindow {
+    remaining: f64,
+    resets_at: Option<DateTime<Utc>>,
+    /// Reset time passed and was handled
+    reset_seen: bool,
+}
+
+/// Reset times reported by successive refreshes drift by a few seconds for
+/// providers that send a countdown; treat those as the same reset
+fn same_reset(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).num_seconds().abs() < 120,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Tracks every provider window between refreshes: reports session
+/// depletion and restore transitions, and on a clock tick the resets
+/// themselves
 pub struct SessionQuotaNotifier {
-    /// Previous remaining percentages by provider
-    previous_remaining: std::collections::HashMap<ProviderId, f64>,
+    windows: HashMap<(ProviderId, UsageWindow), TrackedWindow>,
 }
 
 impl SessionQuotaNotifier {
     /// Create a new notifier
     pub fn new() -> Self {
         Self {
-            previous_remaining: std::collections::HashMap::new(),
+            windows: HashMap::new(),
         }
     }
 
-    /// Update quota for a provider and send notification if state changed
-    pub fn update(&mut self, provider: ProviderId, current_remaining: Option<f64>) {
-        let previous = self.previous_remaining.get(&provider).copied();
-        let transition = SessionQuotaLogic::transition(previous, current_remaining);
+    /// Record a refreshed window; returns a session depleted or restored
+    /// transition. The first reading of a window never reports one.
+    pub fn update(&mut self, provider: ProviderId, kind: UsageWindow, window: &RateWindow) -> Option<QuotaEvent> {
+        let remaining = window.remaining_percent();
+        let previous = self
+            .windows
+            .get(&(provider, kind))
+            .filter(|p| same_reset(p.resets_at, window.resets_at))
+            .cloned();
 
-        if transition != SessionQuotaTransition::None {
-            self.post_notification(transition, provider);
+        // Data from before a reset we already handled is stale
+        if let Some(previous) = previous.as_ref().filter(|p| p.reset_seen) {
+            self.windows.insert((provider, kind), previous.clone());
+            return None;
         }
 
-        // Store current as previous for next comparison
-        if let Some(remaining) = current_remaining {
-            self.previous_remaining.insert(provider, remaining);
+        let previous_remaining = self.windows.get(&(provider, kind)).map(|p| p.remaining);
+        let transition = SessionQuotaLogic::transition(previous_remaining, Some(remaining));
+        self.windows.insert(
+            (provider, kind),
+            TrackedWindow {
+                remaining,
+                resets_at: window.resets_at,
+                reset_seen: false,
+            },
+        );
+
+        if kind != UsageWindow::Session {
+            return None;
+        }
+        match transition {
+            SessionQuotaTransition::Depleted => Some(QuotaEvent::Depleted {
+                provider,
+                used_percent: window.used_percent,
+            }),
+            SessionQuotaTransition::Restored => Some(QuotaEvent::Restored {
+                provider,
+                used_percent: window.used_percent,
+            }),
+            SessionQuotaTransition::None => None,
         }
     }
 
-    /// Post a notification for the given transition
-    fn post_notification(&self, transition: SessionQuotaTransition, provider: ProviderId) {
-        let provider_name = provider.display_name();
-
-        let (title, body) = match transition {
-            SessionQuotaTransition::None => return,
-            SessionQuotaTransition::Depleted => (
-                format!("{} session depleted", provider_name),
-                "0% left. Will notify when it's available again.".to_string(),
-            ),
-            SessionQuotaTransition::Restored => (
-                format!("{} session restored", provider_name),
-                "Session quota is available again.".to_string(),
-            ),
-        };
-
-        tracing::info!(
-            provider = %provider.cli_name(),
-            transition = ?transition,
-            "Session quota notification: {} - {}",
-            title,
-            body
-        );
+    /// Resets due at `now`, each reported once
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<QuotaEvent> {
+        let mut events = Vec::new();
+        for (&(provider, kind), tracked) in self.windows.iter_mut() {
+            let Some(resets_at) = tracked.resets_at.filter(|_| !tracked.reset_seen) else {
+                continue;
+            };
+            if now < resets_at {
+                continue;
+            }
+            let used_percent = 100.0 - tracked.remaining;
 
-        // Note: Actual toast notification is handled by NotificationManager.check_session_transition()
-        // This function is for logging and integration with the notification system
+            if kind == UsageWindow::Session {
+                if SessionQuotaLogic::is_depleted(Some(tracked.remaining)) {
+                    events.push(QuotaEvent::Restored { provider, used_percent: 0.0 });
+                }
+            } else if used_percent >= 1.0 {
+                events.push(QuotaEvent::Reset { provider, window: kind, used_percent });
+            }
+            // Assume a fresh window until the next refresh says otherwise
+            tracked.remaining = 100.0;
+            tracked.reset_seen = true;
+        }
+        events
     }
 
     /// Reset tracking for a provider
     pub fn reset(&mut self, provider: ProviderId) {
-        self.previous_remaining.remove(&provider);
+        self.windows.retain(|(p, _), _| *p != provider);
     }
 
     /// Reset all tracking
     pub fn reset_all(&mut self) {
-        self.previous_remaining.clear();
+        self.windows.clear();
     }
 }
 
@@ -137,6 +202,7 @@ impl Default for SessionQuotaNotifier {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
 
     #[test]
     fn test_is_depleted() {
@@ -193,4 +259,52 @@ mod tests {
             SessionQuotaTransition::Restored
         );
     }
+
+    fn window(used: f64, resets_at: DateTime<Utc>) -> RateWindow {
+        RateWindow::with_details(used, None, Some(resets_at), None)
+    }
+
+    #[test]
+    fn test_notifier_session_transitions() {
+        let mut notifier = SessionQuotaNotifier::new();
+        let resets_at = Utc::now() + Duration::hours(3);
+        let p = ProviderId::Claude;
+
+        assert_eq!(notifier.update(p, UsageWindow::Session, &window(100.0, resets_at)), None);
+        assert_eq!(
+            notifier.update(p, UsageWindow::Session, &window(20.0, resets_at)),
+            Some(QuotaEvent::Restored { provider: p, used_percent: 20.0 })
+        );
+        assert_eq!(
+            notifier.update(p, UsageWindow::Session, &window(100.0, resets_at + Duration::seconds(30))),
+            Some(QuotaEvent::Depleted { provider: p, used_percent: 100.0 })
+        );
+        // Weekly windows don't report transitions
+        assert_eq!(notifier.update(p, UsageWindow::Weekly, &window(100.0, resets_at)), None);
+        assert_eq!(notifier.update(p, UsageWindow::Weekly, &window(10.0, resets_at)), None);
+    }
+
+    #[test]
+    fn test_notifier_resets() {
+        let mut notifier = SessionQuotaNotifier::new();
+        let now = Utc::now();
+        let resets_at = now + Duration::minutes(30);
+        let p = ProviderId::Codex;
+        notifier.update(p, UsageWindow::Weekly, &window(60.0, resets_at));
+        notifier.update(p, UsageWindow::Session, &window(100.0, resets_at));
+
+        assert!(notifier.due(now).is_empty());
+        assert!(notifier.due(now + Duration::minutes(20)).is_empty());
+
+        let events = notifier.due(now + Duration::minutes(31));
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&QuotaEvent::Reset { provider: p, window: UsageWindow::Weekly, used_percent: 60.0 }));
+        assert!(events.contains(&QuotaEvent::Restored { provider: p, used_percent: 0.0 }));
+        assert!(notifier.due(now + Duration::minutes(40)).is_empty());
+
+        // A stale refresh from before the reset doesn't report again, and
+        // fresh data after it doesn't repeat the restore
+        assert_eq!(notifier.update(p, UsageWindow::Session, &window(100.0, resets_at)), None);
+        assert_eq!(notifier.update(p, UsageWindow::Session, &window(5.0, resets_at + Duration::hours(5))), None);
+    }
 }
@@ -3,7 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::RateWindow;
+use super::{RateWindow, UsageWindow};
 
 /// A snapshot of usage data for a provider at a point in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +49,15 @@ impl UsageSnapshot {
         }
     }
 
+    /// Window by kind, if the provider reports it
+    pub fn window(&self, kind: UsageWindow) -> Option<&RateWindow> {
+        match kind {
+            UsageWindow::Session => Some(&self.primary),
+            UsageWindow::Weekly => self.secondary.as_ref(),
+            UsageWindow::Model => self.model_specific.as_ref(),
+        }
+    }
+
     /// Builder pattern: set secondary window
     pub fn with_secondary(mut self, secondary: RateWindow) -> Self {
         self.secondary = Some(secondary);
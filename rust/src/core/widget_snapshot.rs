@@ -5,7 +5,7 @@
 
 #![allow(dead_code)]
 
-use crate::core::{ProviderId, RateWindow};
+use crate::core::{ProviderId, RateWindow, UsageWindow};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -124,6 +124,9 @@ pub struct WidgetProviderEntry {
     /// Login method/plan info (e.g., "Claude Pro", "Claude Max")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub login_method: Option<String>,
+    /// Status page level (e.g. "operational", "major")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
 }
 
 impl WidgetProviderEntry {
@@ -140,6 +143,16 @@ impl WidgetProviderEntry {
             daily_usage: Vec::new(),
             account_email: None,
             login_method: None,
+            status: None,
+        }
+    }
+
+    /// Rate window for a usage window kind
+    pub fn window(&self, kind: UsageWindow) -> Option<&RateWindow> {
+        match kind {
+            UsageWindow::Session => self.primary.as_ref(),
+            UsageWindow::Weekly => self.secondary.as_ref(),
+            UsageWindow::Model => self.tertiary.as_ref(),
         }
     }
 
@@ -187,6 +200,11 @@ impl WidgetProviderEntry {
         self.login_method = Some(method.into());
         self
     }
+
+    pub fn with_status(mut self, status: impl Into<String>) -> Self {
+        self.status = Some(status.into());
+        self
+    }
 }
 
 /// Complete widget snapshot with all provider data
@@ -1,13 +1,19 @@
-//! Local cost-usage scanner for Codex and Claude
+//! Local cost-usage scanner for Codex, Claude, Gemini and OpenCode
 //!
-//! Scans local JSONL log files to aggregate token usage and calculate costs
+//! Scans local session logs to aggregate token usage and calculate costs.
+//! Parsing and caching live in `core::jsonl_scanner`; prices come from
+//! `core::cost_pricing`.
 
-use chrono::{DateTime, Duration, NaiveDate, Utc};
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use crate::core::{
+    CacheCost, CostUsageCache, CostUsageDayRange, CostUsagePricing, JsonlScanner, LogRoots, PricingFamily,
+    ProjectResolver, ProviderId, LOCAL_ROOT_LABEL,
+};
 
 /// Cost summary from scanning local logs
 #[derive(Debug, Clone, Default)]
@@ -18,8 +24,10 @@ pub struct CostSummary {
     pub input_tokens: u64,
     /// Total output tokens
     pub output_tokens: u64,
-    /// Total cached input tokens
+    /// Total cached input tokens (cache reads and writes)
     pub cached_tokens: u64,
+    /// Prompt-cache breakdown and cost impact
+    pub cache: CacheUsage,
     /// Number of sessions/conversations scanned
     pub sessions_count: u32,
     /// Cost breakdown by model
@@ -28,334 +36,765 @@ pub struct CostSummary {
     pub period_start: Option<NaiveDate>,
     /// Period end date
     pub period_end: Option<NaiveDate>,
+    /// Per-session, per-day, per-model records backing the totals
+    pub entries: Vec<CostEntry>,
+    /// One record per session with usage in the period
+    pub sessions: Vec<SessionCost>,
+    /// Usage by local day of week and hour of day
+    pub heatmap: UsageHeatmap,
+    /// Tokens from models without their own price, estimated at the
+    /// family's fallback rate
+    pub unpriced_tokens: u64,
+    /// Models counted in `unpriced_tokens`, sorted
+    pub unpriced_models: Vec<String>,
+    /// Position of each bucket in `entries`
+    bucket_index: HashMap<BucketKey, usize>,
 }
 
 impl CostSummary {
     pub fn format_total(&self) -> String {
         format!("${:.2}", self.total_cost_usd)
     }
-}
 
-/// Codex token pricing (per 1M tokens, as of 2024)
-struct CodexPricing;
+    /// Share of input tokens served from the cache
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        self.cache.hit_ratio(self.input_tokens, self.cached_tokens)
+    }
 
-impl CodexPricing {
-    fn cost_usd(model: &str, input: u64, cached: u64, output: u64) -> f64 {
-        // Default to GPT-4o pricing
-        let (input_price, cached_price, output_price) = match model.to_lowercase().as_str() {
-            m if m.contains("gpt-4o-mini") => (0.15, 0.075, 0.60),
-            m if m.contains("gpt-4o") => (2.50, 1.25, 10.00),
-            m if m.contains("gpt-4-turbo") => (10.00, 5.00, 30.00),
-            m if m.contains("gpt-4") => (30.00, 15.00, 60.00),
-            m if m.contains("o1-mini") => (3.00, 1.50, 12.00),
-            m if m.contains("o1") => (15.00, 7.50, 60.00),
-            _ => (2.50, 1.25, 10.00), // Default to GPT-4o
+    /// Add a usage record and fold it into the totals
+    fn record(&mut self, entry: CostEntry) {
+        self.input_tokens += entry.input_tokens;
+        self.cached_tokens += entry.cached_tokens;
+        self.output_tokens += entry.output_tokens;
+        self.total_cost_usd += entry.cost_usd;
+        self.cache.add(&entry.cache);
+        *self.by_model.entry(entry.model.clone()).or_insert(0.0) += entry.cost_usd;
+        if !entry.priced {
+            self.unpriced_tokens += entry.input_tokens + entry.cached_tokens + entry.output_tokens;
+            if let Err(at) = self.unpriced_models.binary_search(&entry.model) {
+                self.unpriced_models.insert(at, entry.model.clone());
+            }
+        }
+
+        match self.bucket_index.entry(entry.bucket_key()) {
+            Entry::Occupied(slot) => {
+                let existing = &mut self.entries[*slot.get()];
+                existing.input_tokens += entry.input_tokens;
+                existing.cached_tokens += entry.cached_tokens;
+                existing.output_tokens += entry.output_tokens;
+                existing.cost_usd += entry.cost_usd;
+                existing.cache.add(&entry.cache);
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(self.entries.len());
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    /// Summary restricted to projects whose name contains `needle`
+    /// (case-insensitive). The heatmap is not tracked per project and is
+    /// left empty.
+    pub fn filter_project(&self, needle: &str) -> CostSummary {
+        let needle = needle.to_lowercase();
+        let mut filtered = CostSummary {
+            period_start: self.period_start,
+            period_end: self.period_end,
+            ..CostSummary::default()
         };
 
-        let input_cost = (input as f64 / 1_000_000.0) * input_price;
-        let cached_cost = (cached as f64 / 1_000_000.0) * cached_price;
-        let output_cost = (output as f64 / 1_000_000.0) * output_price;
+        let mut sessions = HashSet::new();
+        for entry in &self.entries {
+            let matches = entry
+                .project
+                .as_deref()
+                .is_some_and(|p| p.to_lowercase().contains(&needle));
+            if matches {
+                sessions.insert(entry.session_id.clone());
+                filtered.record(entry.clone());
+            }
+        }
+        filtered.sessions_count = sessions.len() as u32;
+        filtered.sessions = self
+            .sessions
+            .iter()
+            .filter(|s| sessions.contains(&s.session_id))
+            .cloned()
+            .collect();
+        filtered
+    }
+
+    /// Aggregate entries into groups, sorted chronologically for time
+    /// groupings and by cost (highest first) otherwise
+    pub fn group_by(&self, grouping: CostGrouping) -> Vec<CostGroup> {
+        let mut groups: HashMap<String, CostGroup> = HashMap::new();
+
+        for entry in &self.entries {
+            let key = grouping.key_for(entry);
+            let group = groups.entry(key.clone()).or_insert_with(|| CostGroup {
+                key,
+                ..CostGroup::default()
+            });
+            group.input_tokens += entry.input_tokens;
+            group.cached_tokens += entry.cached_tokens;
+            group.output_tokens += entry.output_tokens;
+            group.cost_usd += entry.cost_usd;
+            group.cache.add(&entry.cache);
+        }
 
-        input_cost + cached_cost + output_cost
+        let mut result: Vec<CostGroup> = groups.into_values().collect();
+        if grouping.is_chronological() {
+            result.sort_by(|a, b| a.key.cmp(&b.key));
+        } else {
+            result.sort_by(|a, b| {
+                b.cost_usd
+                    .partial_cmp(&a.cost_usd)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.key.cmp(&b.key))
+            });
+        }
+        result
     }
 }
 
-/// Claude token pricing (per 1M tokens, as of 2024)
-struct ClaudePricing;
+/// Usage for one session on one day with one model
+#[derive(Debug, Clone)]
+pub struct CostEntry {
+    /// Day the usage is attributed to
+    pub date: NaiveDate,
+    /// Model name as reported in the log
+    pub model: String,
+    /// Project the session ran in (git root or alias), if known
+    pub project: Option<String>,
+    /// Session identifier (log file stem)
+    pub session_id: String,
+    /// Label of the log root the session was read from
+    pub environment: String,
+    pub input_tokens: u64,
+    pub cached_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    pub cache: CacheUsage,
+    /// False when the cost is a fallback estimate for an unknown model
+    pub priced: bool,
+}
 
-impl ClaudePricing {
-    fn cost_usd(model: &str, input: u64, cache_create: u64, cache_read: u64, output: u64) -> f64 {
-        let (input_price, cache_create_price, cache_read_price, output_price) = match model.to_lowercase().as_str() {
-            m if m.contains("opus") => (15.00, 18.75, 1.50, 75.00),
-            m if m.contains("sonnet") => (3.00, 3.75, 0.30, 15.00),
-            m if m.contains("haiku") => (0.25, 0.30, 0.03, 1.25),
-            _ => (3.00, 3.75, 0.30, 15.00), // Default to Sonnet
-        };
+/// Prompt-cache reads and writes with their cost impact
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheUsage {
+    pub read_tokens: u64,
+    pub write_tokens: u64,
+    pub read_cost_usd: f64,
+    pub write_cost_usd: f64,
+    /// Cost of the same tokens as regular input minus the cache cost
+    pub savings_usd: f64,
+}
+
+impl CacheUsage {
+    fn from_cost(read_tokens: u64, write_tokens: u64, cost: CacheCost) -> Self {
+        Self {
+            read_tokens,
+            write_tokens,
+            read_cost_usd: cost.read_usd,
+            write_cost_usd: cost.write_usd,
+            savings_usd: cost.savings_usd(),
+        }
+    }
 
-        let input_cost = (input as f64 / 1_000_000.0) * input_price;
-        let cache_create_cost = (cache_create as f64 / 1_000_000.0) * cache_create_price;
-        let cache_read_cost = (cache_read as f64 / 1_000_000.0) * cache_read_price;
-        let output_cost = (output as f64 / 1_000_000.0) * output_price;
+    fn add(&mut self, other: &CacheUsage) {
+        self.read_tokens += other.read_tokens;
+        self.write_tokens += other.write_tokens;
+        self.read_cost_usd += other.read_cost_usd;
+        self.write_cost_usd += other.write_cost_usd;
+        self.savings_usd += other.savings_usd;
+    }
 
-        input_cost + cache_create_cost + cache_read_cost + output_cost
+    /// Cache reads as a share of all input (`input_tokens` excludes cached
+    /// tokens, `cached_tokens` includes reads and writes)
+    pub fn hit_ratio(&self, input_tokens: u64, cached_tokens: u64) -> Option<f64> {
+        let total = input_tokens + cached_tokens;
+        (total > 0).then(|| self.read_tokens as f64 / total as f64)
     }
 }
 
-/// JSONL event structures for Codex
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct CodexEvent {
-    #[serde(rename = "type")]
-    event_type: Option<String>,
-    event_msg: Option<CodexEventMsg>,
+/// What makes two usage records land in the same `CostEntry`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BucketKey {
+    date: NaiveDate,
+    model: String,
+    session_id: String,
+    project: Option<String>,
+    environment: String,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct CodexEventMsg {
-    #[serde(rename = "type")]
-    msg_type: Option<String>,
-    input_tokens: Option<u64>,
-    cached_input_tokens: Option<u64>,
-    output_tokens: Option<u64>,
+impl CostEntry {
+    fn bucket_key(&self) -> BucketKey {
+        BucketKey {
+            date: self.date,
+            model: self.model.clone(),
+            session_id: self.session_id.clone(),
+            project: self.project.clone(),
+            environment: self.environment.clone(),
+        }
+    }
 }
 
-/// JSONL event structures for Claude
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct ClaudeEvent {
-    #[serde(rename = "type")]
-    event_type: Option<String>,
-    message: Option<ClaudeMessage>,
+/// Usage and cost of one session (log file) within the scanned period
+#[derive(Debug, Clone)]
+pub struct SessionCost {
+    /// Session identifier (log file stem)
+    pub session_id: String,
+    /// Path of the session log file
+    pub path: PathBuf,
+    /// Project the session ran in, if known
+    pub project: Option<String>,
+    /// First and last usage events in the session
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Cost per model, highest first
+    pub models: Vec<(String, f64)>,
+    pub input_tokens: u64,
+    pub cached_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct ClaudeMessage {
-    model: Option<String>,
-    usage: Option<ClaudeUsage>,
+impl SessionCost {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.cached_tokens + self.output_tokens
+    }
+
+    /// Time between the first and last usage events
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.ended_at? - self.started_at?)
+    }
+
+    /// Model with the highest cost
+    pub fn main_model(&self) -> Option<&str> {
+        self.models.first().map(|(model, _)| model.as_str())
+    }
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct ClaudeUsage {
-    input_tokens: Option<u64>,
-    output_tokens: Option<u64>,
-    cache_creation_input_tokens: Option<u64>,
-    cache_read_input_tokens: Option<u64>,
+/// Tokens and cost in one heatmap cell
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HourBucket {
+    pub tokens: u64,
+    pub cost_usd: f64,
 }
 
-/// Cost usage scanner
-pub struct CostScanner {
-    days: u32,
+/// Short weekday names for heatmap rows, Monday first
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Usage by day of week (Monday first) and hour of day, in local time
+#[derive(Debug, Clone, Default)]
+pub struct UsageHeatmap {
+    pub cells: [[HourBucket; 24]; 7],
 }
 
-impl CostScanner {
-    /// Create a new scanner for the last N days
-    pub fn new(days: u32) -> Self {
-        Self { days }
+impl UsageHeatmap {
+    /// Add usage at a local time
+    pub fn add(&mut self, at: DateTime<Local>, tokens: u64, cost_usd: f64) {
+        let cell = &mut self.cells[at.weekday().num_days_from_monday() as usize][at.hour() as usize];
+        cell.tokens += tokens;
+        cell.cost_usd += cost_usd;
     }
 
-    /// Scan Codex local logs
-    pub fn scan_codex(&self) -> CostSummary {
-        let sessions_dir = self.get_codex_sessions_dir();
-        if !sessions_dir.exists() {
-            return CostSummary::default();
-        }
-
-        let mut summary = CostSummary::default();
-        let today = Utc::now().date_naive();
-        let start_date = today - Duration::days(self.days as i64);
+    pub fn cell(&self, weekday: usize, hour: usize) -> HourBucket {
+        self.cells[weekday][hour]
+    }
 
-        summary.period_start = Some(start_date);
-        summary.period_end = Some(today);
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().flatten().all(|c| c.tokens == 0)
+    }
 
-        // Iterate through date-based directory structure
-        for days_ago in 0..self.days {
-            let date = today - Duration::days(days_ago as i64);
-            let year = date.format("%Y").to_string();
-            let month = date.format("%m").to_string();
-            let day = date.format("%d").to_string();
+    /// Busiest cell by tokens as (weekday, hour)
+    pub fn peak(&self) -> Option<(usize, usize)> {
+        (0..7)
+            .flat_map(|d| (0..24).map(move |h| (d, h)))
+            .filter(|&(d, h)| self.cells[d][h].tokens > 0)
+            .max_by_key(|&(d, h)| self.cells[d][h].tokens)
+    }
+}
 
-            let day_dir = sessions_dir.join(&year).join(&month).join(&day);
-            if !day_dir.exists() {
-                continue;
-            }
+/// How to group cost entries for reporting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostGrouping {
+    Day,
+    Week,
+    Month,
+    Model,
+    /// Model vendor (OpenCode proxies many)
+    Provider,
+    Project,
+    Session,
+    /// Log root label (local, WSL distribution, configured directory)
+    Environment,
+}
 
-            if let Ok(entries) = fs::read_dir(&day_dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.extension().map_or(false, |e| e == "jsonl") {
-                        self.parse_codex_file(&path, &mut summary);
-                    }
-                }
+impl CostGrouping {
+    /// Group key for an entry
+    pub fn key_for(&self, entry: &CostEntry) -> String {
+        match self {
+            CostGrouping::Day => entry.date.format("%Y-%m-%d").to_string(),
+            CostGrouping::Week => {
+                let week = entry.date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
             }
+            CostGrouping::Month => entry.date.format("%Y-%m").to_string(),
+            CostGrouping::Model => entry.model.clone(),
+            CostGrouping::Provider => model_vendor(&entry.model).to_string(),
+            CostGrouping::Project => entry
+                .project
+                .clone()
+                .unwrap_or_else(|| "(unknown)".to_string()),
+            CostGrouping::Session => entry.session_id.clone(),
+            CostGrouping::Environment => entry.environment.clone(),
         }
+    }
 
-        summary
+    /// Whether groups are time buckets that read best in date order
+    pub fn is_chronological(&self) -> bool {
+        matches!(self, CostGrouping::Day | CostGrouping::Week | CostGrouping::Month)
     }
 
-    /// Scan Claude local logs
-    pub fn scan_claude(&self) -> CostSummary {
-        let projects_dir = self.get_claude_projects_dir();
-        if !projects_dir.exists() {
-            return CostSummary::default();
+    /// Name used in CLI arguments and output headers
+    pub fn name(&self) -> &'static str {
+        match self {
+            CostGrouping::Day => "day",
+            CostGrouping::Week => "week",
+            CostGrouping::Month => "month",
+            CostGrouping::Model => "model",
+            CostGrouping::Provider => "provider",
+            CostGrouping::Project => "project",
+            CostGrouping::Session => "session",
+            CostGrouping::Environment => "environment",
         }
+    }
+}
 
-        let mut summary = CostSummary::default();
-        let today = Utc::now().date_naive();
-        let start_date = today - Duration::days(self.days as i64);
-        let cutoff = Utc::now() - Duration::days(self.days as i64);
+impl std::str::FromStr for CostGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" | "daily" => Ok(CostGrouping::Day),
+            "week" | "weekly" => Ok(CostGrouping::Week),
+            "month" | "monthly" => Ok(CostGrouping::Month),
+            "model" => Ok(CostGrouping::Model),
+            "provider" | "vendor" => Ok(CostGrouping::Provider),
+            "project" | "repo" => Ok(CostGrouping::Project),
+            "session" => Ok(CostGrouping::Session),
+            "environment" | "env" => Ok(CostGrouping::Environment),
+            _ => Err(format!(
+                "Invalid grouping: {}. Use day, week, month, model, provider, project, session or environment",
+                s
+            )),
+        }
+    }
+}
+
+/// Aggregated cost for one group
+#[derive(Debug, Clone, Default)]
+pub struct CostGroup {
+    pub key: String,
+    pub input_tokens: u64,
+    pub cached_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    pub cache: CacheUsage,
+}
 
-        summary.period_start = Some(start_date);
-        summary.period_end = Some(today);
+impl CostGroup {
+    /// Total tokens across input, cached and output
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.cached_tokens + self.output_tokens
+    }
 
-        // Walk through projects directory
-        self.scan_claude_dir(&projects_dir, &cutoff, &mut summary);
+    /// Share of input tokens served from the cache
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        self.cache.hit_ratio(self.input_tokens, self.cached_tokens)
+    }
+}
 
-        summary
+/// Cost usage scanner
+///
+/// Thin wrapper over [`JsonlScanner`] that keeps the incremental per-file
+/// cache up to date and prices usage with [`CostUsagePricing`].
+pub struct CostScanner {
+    since: NaiveDate,
+    until: NaiveDate,
+    project_aliases: HashMap<String, String>,
+    roots: LogRoots,
+}
+
+impl CostScanner {
+    /// Create a new scanner for the last N days (including today)
+    pub fn new(days: u32) -> Self {
+        let today = Utc::now().date_naive();
+        let since = today - Duration::days(days.saturating_sub(1) as i64);
+        Self::with_range(since, today)
     }
 
-    fn get_codex_sessions_dir(&self) -> PathBuf {
-        if let Ok(codex_home) = std::env::var("CODEX_HOME") {
-            let trimmed = codex_home.trim();
-            if !trimmed.is_empty() {
-                return PathBuf::from(trimmed).join("sessions");
-            }
+    /// Create a scanner for an inclusive date range
+    pub fn with_range(since: NaiveDate, until: NaiveDate) -> Self {
+        Self {
+            since,
+            until,
+            project_aliases: HashMap::new(),
+            roots: LogRoots::local(),
         }
+    }
 
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".codex")
-            .join("sessions")
+    /// Rename or merge projects (path or repo name -> project name)
+    pub fn with_project_aliases(mut self, aliases: HashMap<String, String>) -> Self {
+        self.project_aliases = aliases;
+        self
     }
 
-    fn get_claude_projects_dir(&self) -> PathBuf {
-        if let Ok(claude_config) = std::env::var("CLAUDE_CONFIG_DIR") {
-            let trimmed = claude_config.trim();
-            if !trimmed.is_empty() {
-                return PathBuf::from(trimmed).join("projects");
-            }
+    /// Scan these Codex and Claude log directories instead of the defaults
+    pub fn with_log_roots(mut self, roots: LogRoots) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    /// Scan Codex local logs
+    pub fn scan_codex(&self) -> CostSummary {
+        if self.roots.codex.is_empty() {
+            return CostSummary::default();
         }
 
-        // Try ~/.claude/projects first
-        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-        let claude_dir = home.join(".claude").join("projects");
-        if claude_dir.exists() {
-            return claude_dir;
+        let range = CostUsageDayRange::new(self.since, self.until);
+        let roots: Vec<PathBuf> = self.roots.codex.iter().map(|r| r.path.clone()).collect();
+        let mut cache = JsonlScanner::load_cache(ProviderId::Codex, None);
+        let keys = JsonlScanner::refresh_codex_cache(&roots, &range, &mut cache);
+        JsonlScanner::save_cache(ProviderId::Codex, &cache, None);
+
+        self.summarize(&cache, &keys, &range, codex_tokens_and_cost, |_| None)
+    }
+
+    /// Scan Claude local logs
+    pub fn scan_claude(&self) -> CostSummary {
+        let roots: Vec<PathBuf> = self.roots.claude.iter().map(|r| r.path.clone()).collect();
+        if roots.is_empty() {
+            return CostSummary::default();
         }
 
-        // Fallback to ~/.config/claude/projects
-        home.join(".config").join("claude").join("projects")
+        let range = CostUsageDayRange::new(self.since, self.until);
+        let mut cache = JsonlScanner::load_cache(ProviderId::Claude, None);
+        let keys = JsonlScanner::refresh_claude_cache(&roots, &range, &mut cache);
+        JsonlScanner::save_cache(ProviderId::Claude, &cache, None);
+
+        self.summarize(
+            &cache,
+            &keys,
+            &range,
+            claude_tokens_and_cost,
+            ProjectResolver::claude_project_from_path,
+        )
     }
 
-    fn parse_codex_file(&self, path: &PathBuf, summary: &mut CostSummary) {
-        let file = match File::open(path) {
-            Ok(f) => f,
-            Err(_) => return,
+    /// Scan Gemini CLI chat recordings
+    pub fn scan_gemini(&self) -> CostSummary {
+        let Some(root) = JsonlScanner::default_gemini_tmp_root().filter(|r| r.exists()) else {
+            return CostSummary::default();
         };
 
-        let reader = BufReader::new(file);
-        let mut current_model = String::from("gpt-4o");
-        let mut session_cost = 0.0;
-        let mut has_tokens = false;
-
-        for line in reader.lines().flatten() {
-            if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) {
-                // Check for model in turn_context
-                if let Some(model) = event.get("model").and_then(|m| m.as_str()) {
-                    current_model = model.to_string();
-                }
+        let range = CostUsageDayRange::new(self.since, self.until);
+        let mut cache = JsonlScanner::load_cache(ProviderId::Gemini, None);
+        let keys = JsonlScanner::refresh_gemini_cache(&root, &range, &mut cache);
+        JsonlScanner::save_cache(ProviderId::Gemini, &cache, None);
 
-                // Check for token_count events
-                if let Some(event_msg) = event.get("event_msg") {
-                    if event_msg.get("type").and_then(|t| t.as_str()) == Some("token_count") {
-                        let input = event_msg.get("input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                        let cached = event_msg.get("cached_input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                        let output = event_msg.get("output_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
+        // Recordings only carry a hash of the project path
+        self.summarize(&cache, &keys, &range, gemini_tokens_and_cost, |_| None)
+    }
 
-                        summary.input_tokens += input;
-                        summary.cached_tokens += cached;
-                        summary.output_tokens += output;
+    /// Scan OpenCode's message storage
+    pub fn scan_opencode(&self) -> CostSummary {
+        let Some(storage) = JsonlScanner::default_opencode_storage_root().filter(|r| r.exists()) else {
+            return CostSummary::default();
+        };
 
-                        let cost = CodexPricing::cost_usd(&current_model, input, cached, output);
-                        session_cost += cost;
-                        has_tokens = true;
+        let range = CostUsageDayRange::new(self.since, self.until);
+        let mut cache = JsonlScanner::load_cache(ProviderId::OpenCode, None);
+        let keys = JsonlScanner::refresh_opencode_cache(&storage, &range, &mut cache);
+        JsonlScanner::save_cache(ProviderId::OpenCode, &cache, None);
 
-                        *summary.by_model.entry(current_model.clone()).or_insert(0.0) += cost;
-                    }
-                }
-            }
-        }
+        self.summarize(&cache, &keys, &range, opencode_tokens_and_cost, |_| None)
+    }
 
-        if has_tokens {
-            summary.total_cost_usd += session_cost;
-            summary.sessions_count += 1;
+    /// Scan a provider's local logs, or None if it keeps none
+    pub fn scan(&self, provider: ProviderId) -> Option<CostSummary> {
+        match provider {
+            ProviderId::Codex => Some(self.scan_codex()),
+            ProviderId::Claude => Some(self.scan_claude()),
+            ProviderId::Gemini => Some(self.scan_gemini()),
+            ProviderId::OpenCode => Some(self.scan_opencode()),
+            _ => None,
         }
     }
 
-    fn scan_claude_dir(&self, dir: &PathBuf, cutoff: &DateTime<Utc>, summary: &mut CostSummary) {
-        let entries = match fs::read_dir(dir) {
-            Ok(e) => e,
-            Err(_) => return,
+    /// Turn cached per-file usage into entries for the requested range
+    fn summarize(
+        &self,
+        cache: &CostUsageCache,
+        keys: &[String],
+        range: &CostUsageDayRange,
+        tokens_and_cost: fn(&str, NaiveDate, &[i64]) -> PackedUsage,
+        project_from_path: fn(&Path) -> Option<String>,
+    ) -> CostSummary {
+        let mut summary = CostSummary {
+            period_start: Some(self.since),
+            period_end: Some(self.until),
+            ..CostSummary::default()
         };
+        let mut projects = ProjectResolver::new(&self.project_aliases);
 
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                self.scan_claude_dir(&path, cutoff, summary);
-            } else if path.extension().map_or(false, |e| e == "jsonl") {
-                // Check file modification time
-                if let Ok(metadata) = fs::metadata(&path) {
-                    if let Ok(modified) = metadata.modified() {
-                        let modified_dt: DateTime<Utc> = modified.into();
-                        if modified_dt >= *cutoff {
-                            self.parse_claude_file(&path, summary);
-                        }
+        for key in keys {
+            let Some(usage) = cache.files.get(key) else {
+                continue;
+            };
+            let session_id = session_id_from_path(Path::new(key));
+            let environment = self.roots.label_for(Path::new(key)).unwrap_or(LOCAL_ROOT_LABEL).to_string();
+            let project = usage
+                .project
+                .clone()
+                .or_else(|| project_from_path(Path::new(key)))
+                .map(|cwd| projects.resolve(&cwd));
+            let mut session = SessionCost {
+                session_id: session_id.clone(),
+                path: PathBuf::from(key),
+                project: project.clone(),
+                started_at: parse_timestamp(usage.first_activity.as_deref()),
+                ended_at: parse_timestamp(usage.last_activity.as_deref())
+                    .or_else(|| Utc.timestamp_millis_opt(usage.mtime_unix_ms).single()),
+                models: Vec::new(),
+                input_tokens: 0,
+                cached_tokens: 0,
+                output_tokens: 0,
+                cost_usd: 0.0,
+            };
+            let mut model_costs: HashMap<String, f64> = HashMap::new();
+            let mut first_day: Option<NaiveDate> = None;
+
+            for (day_key, models) in &usage.days {
+                if !CostUsageDayRange::is_in_range(day_key, &range.since_key, &range.until_key) {
+                    continue;
+                }
+                let Some(date) = CostUsageDayRange::parse_day_key(day_key) else {
+                    continue;
+                };
+
+                first_day = Some(first_day.map_or(date, |d| d.min(date)));
+
+                for (model, packed) in models {
+                    let packed = tokens_and_cost(model, date, packed);
+                    session.input_tokens += packed.input;
+                    session.cached_tokens += packed.cached;
+                    session.output_tokens += packed.output;
+                    session.cost_usd += packed.cost_usd;
+                    *model_costs.entry(model.clone()).or_insert(0.0) += packed.cost_usd;
+                    if !packed.priced {
+                        warn_unpriced(model);
                     }
+                    summary.record(CostEntry {
+                        date,
+                        model: model.clone(),
+                        project: project.clone(),
+                        session_id: session_id.clone(),
+                        environment: environment.clone(),
+                        input_tokens: packed.input,
+                        cached_tokens: packed.cached,
+                        output_tokens: packed.output,
+                        cost_usd: packed.cost_usd,
+                        cache: packed.cache,
+                        priced: packed.priced,
+                    });
                 }
             }
-        }
-    }
 
-    fn parse_claude_file(&self, path: &PathBuf, summary: &mut CostSummary) {
-        let file = match File::open(path) {
-            Ok(f) => f,
-            Err(_) => return,
-        };
-
-        let reader = BufReader::new(file);
-        let mut session_cost = 0.0;
-        let mut has_tokens = false;
-
-        for line in reader.lines().flatten() {
-            if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) {
-                // Look for assistant messages with usage
-                if event.get("type").and_then(|t| t.as_str()) == Some("assistant") {
-                    if let Some(message) = event.get("message") {
-                        let model = message.get("model")
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("claude-3-5-sonnet");
-
-                        if let Some(usage) = message.get("usage") {
-                            let input = usage.get("input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                            let output = usage.get("output_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                            let cache_create = usage.get("cache_creation_input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                            let cache_read = usage.get("cache_read_input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-
-                            summary.input_tokens += input;
-                            summary.output_tokens += output;
-                            summary.cached_tokens += cache_create + cache_read;
-
-                            let cost = ClaudePricing::cost_usd(model, input, cache_create, cache_read, output);
-                            session_cost += cost;
-                            has_tokens = true;
-
-                            *summary.by_model.entry(model.to_string()).or_insert(0.0) += cost;
-                        }
-                    }
+            let Some(first_day) = first_day else {
+                continue;
+            };
+
+            for (hour_key, models) in &usage.hours {
+                let Some(day_key) = hour_key.get(..10) else {
+                    continue;
+                };
+                if !CostUsageDayRange::is_in_range(day_key, &range.since_key, &range.until_key) {
+                    continue;
+                }
+                let Some(at) = parse_hour_key(hour_key) else {
+                    continue;
+                };
+
+                for (model, packed) in models {
+                    let packed = tokens_and_cost(model, at.date_naive(), packed);
+                    let tokens = packed.input + packed.cached + packed.output;
+                    summary.heatmap.add(at.with_timezone(&Local), tokens, packed.cost_usd);
                 }
             }
-        }
+            if session.started_at.is_none() {
+                session.started_at = first_day.and_hms_opt(0, 0, 0).map(|t| t.and_utc());
+            }
+            session.models = model_costs.into_iter().collect();
+            session.models.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-        if has_tokens {
-            summary.total_cost_usd += session_cost;
             summary.sessions_count += 1;
+            summary.sessions.push(session);
         }
+
+        summary
+    }
+}
+
+/// Token counts and cost unpacked from a cache entry
+pub(crate) struct PackedUsage {
+    pub(crate) input: u64,
+    pub(crate) cached: u64,
+    pub(crate) output: u64,
+    pub(crate) cost_usd: f64,
+    pub(crate) cache: CacheUsage,
+    /// Whether the model has its own price
+    pub(crate) priced: bool,
+}
+
+/// Log once per model that its cost is a fallback estimate
+fn warn_unpriced(model: &str) {
+    static WARNED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+    if let Ok(mut warned) = WARNED.lock() {
+        if warned.insert(model.to_string()) {
+            tracing::warn!("No price for model {}; estimating its cost at the family fallback rate", model);
+        }
+    }
+}
+
+/// Unpack Codex counts ([input, cached, output], input includes cached)
+pub(crate) fn codex_tokens_and_cost(model: &str, date: NaiveDate, packed: &[i64]) -> PackedUsage {
+    let get = |i: usize| packed.get(i).copied().unwrap_or(0).max(0);
+    let (input, cached, output) = (get(0), get(1), get(2));
+
+    let cache_cost = CostUsagePricing::cache_cost_usd_on(PricingFamily::Codex, model, date, cached, 0);
+
+    PackedUsage {
+        input: (input - cached).max(0) as u64,
+        cached: cached as u64,
+        output: output as u64,
+        cost_usd: CostUsagePricing::codex_cost_usd_on(model, date, input, cached, output).unwrap_or(0.0),
+        cache: CacheUsage::from_cost(cached as u64, 0, cache_cost.unwrap_or_default()),
+        priced: CostUsagePricing::is_priced(PricingFamily::Codex, model, date),
+    }
+}
+
+/// Unpack Claude counts ([input, cache_read, cache_creation, output, cost_nanos])
+pub(crate) fn claude_tokens_and_cost(model: &str, date: NaiveDate, packed: &[i64]) -> PackedUsage {
+    let get = |i: usize| packed.get(i).copied().unwrap_or(0).max(0);
+    let (read, write) = (get(1), get(2));
+    let cache_cost = CostUsagePricing::cache_cost_usd_on(PricingFamily::Claude, model, date, read, write);
+
+    PackedUsage {
+        input: get(0) as u64,
+        cached: (read + write) as u64,
+        output: get(3) as u64,
+        cost_usd: get(4) as f64 / 1e9,
+        cache: CacheUsage::from_cost(read as u64, write as u64, cache_cost.unwrap_or_default()),
+        priced: CostUsagePricing::is_priced(PricingFamily::Claude, model, date),
+    }
+}
+
+/// Unpack Gemini counts ([input, cached, output, cost_nanos], input includes cached)
+pub(crate) fn gemini_tokens_and_cost(model: &str, date: NaiveDate, packed: &[i64]) -> PackedUsage {
+    let get = |i: usize| packed.get(i).copied().unwrap_or(0).max(0);
+    let (input, cached, output) = (get(0), get(1), get(2));
+
+    let cache_cost = CostUsagePricing::cache_cost_usd_on(PricingFamily::Gemini, model, date, cached, 0);
+
+    PackedUsage {
+        input: (input - cached).max(0) as u64,
+        cached: cached as u64,
+        output: output as u64,
+        cost_usd: get(3) as f64 / 1e9,
+        cache: CacheUsage::from_cost(cached as u64, 0, cache_cost.unwrap_or_default()),
+        priced: CostUsagePricing::is_priced(PricingFamily::Gemini, model, date),
+    }
+}
+
+/// Unpack OpenCode counts (laid out like Claude's; the model key is vendor/model)
+pub(crate) fn opencode_tokens_and_cost(model: &str, date: NaiveDate, packed: &[i64]) -> PackedUsage {
+    let get = |i: usize| packed.get(i).copied().unwrap_or(0).max(0);
+    let (read, write) = (get(1), get(2));
+    let bare_model = model.split_once('/').map_or(model, |(_, m)| m);
+    let family = PricingFamily::for_model(bare_model);
+    let cache_cost = CostUsagePricing::cache_cost_usd_on(family, bare_model, date, read, write);
+
+    PackedUsage {
+        input: get(0) as u64,
+        cached: (read + write) as u64,
+        output: get(3) as u64,
+        cost_usd: get(4) as f64 / 1e9,
+        cache: CacheUsage::from_cost(read as u64, write as u64, cache_cost.unwrap_or_default()),
+        priced: CostUsagePricing::is_priced(family, bare_model, date),
     }
 }
 
+/// Vendor of a model: the prefix of a vendor/model key, otherwise guessed
+/// from the model name
+pub fn model_vendor(model: &str) -> &str {
+    if let Some((vendor, _)) = model.split_once('/') {
+        return vendor;
+    }
+    match PricingFamily::for_model(model) {
+        PricingFamily::Claude => "anthropic",
+        PricingFamily::Gemini => "google",
+        PricingFamily::Codex => "openai",
+    }
+}
+
+/// Parse an RFC 3339 timestamp from the cache
+fn parse_timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Parse a UTC hour key (YYYY-MM-DDTHH) from the cache
+fn parse_hour_key(key: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(&format!("{}:00", key), "%Y-%m-%dT%H:%M")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Session identifier for a log file (its file stem)
+fn session_id_from_path(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
 /// Check if any cost usage sources are available
 #[allow(dead_code)]
 pub fn has_cost_usage_sources() -> bool {
-    let scanner = CostScanner::new(1);
-    scanner.get_codex_sessions_dir().exists() || scanner.get_claude_projects_dir().exists()
+    JsonlScanner::default_codex_sessions_root().is_some_and(|r| r.exists())
+        || !JsonlScanner::default_claude_projects_roots().is_empty()
+        || JsonlScanner::default_gemini_tmp_root().is_some_and(|r| r.exists())
+        || JsonlScanner::default_opencode_storage_root().is_some_and(|r| r.exists())
+}
+
+/// Scan a provider's local logs for the last N days
+pub fn scan_provider_costs(
+    provider: &str,
+    days: u32,
+    project_aliases: HashMap<String, String>,
+    roots: LogRoots,
+) -> CostSummary {
+    let scanner = CostScanner::new(days)
+        .with_project_aliases(project_aliases)
+        .with_log_roots(roots);
+    ProviderId::from_cli_name(provider)
+        .and_then(|id| scanner.scan(id))
+        .unwrap_or_default()
 }
 
-/// Get daily cost history for the last N days
-/// Returns Vec of (date_string, cost_usd) sorted by date
-pub fn get_daily_cost_history(provider: &str, days: u32) -> Vec<(String, f64)> {
-    let scanner = CostScanner::new(days);
+/// Daily costs for the last N days from a summary, zero-filled and sorted by date
+pub fn daily_cost_history(summary: &CostSummary, days: u32) -> Vec<(String, f64)> {
     let today = Utc::now().date_naive();
     let mut daily_costs: HashMap<String, f64> = HashMap::new();
 
@@ -366,47 +805,8 @@ pub fn get_daily_cost_history(provider: &str, days: u32) -> Vec<(String, f64)> {
         daily_costs.insert(date_str, 0.0);
     }
 
-    match provider {
-        "codex" => {
-            // Scan Codex logs by day
-            let sessions_dir = scanner.get_codex_sessions_dir();
-            if sessions_dir.exists() {
-                for days_ago in 0..days {
-                    let date = today - Duration::days(days_ago as i64);
-                    let date_str = date.format("%Y-%m-%d").to_string();
-                    let year = date.format("%Y").to_string();
-                    let month = date.format("%m").to_string();
-                    let day = date.format("%d").to_string();
-
-                    let day_dir = sessions_dir.join(&year).join(&month).join(&day);
-                    if day_dir.exists() {
-                        let mut day_cost = 0.0;
-                        if let Ok(entries) = fs::read_dir(&day_dir) {
-                            for entry in entries.flatten() {
-                                let path = entry.path();
-                                if path.extension().map_or(false, |e| e == "jsonl") {
-                                    day_cost += scan_codex_file_cost(&path);
-                                }
-                            }
-                        }
-                        daily_costs.insert(date_str, day_cost);
-                    }
-                }
-            }
-        }
-        "claude" => {
-            // For Claude, we need to check file modification times
-            // This is more complex, so we'll approximate using the summary for now
-            let summary = scanner.scan_claude();
-            if summary.total_cost_usd > 0.0 && days > 0 {
-                // Distribute evenly for now (TODO: actual daily breakdown)
-                let daily = summary.total_cost_usd / days as f64;
-                for (_, cost) in daily_costs.iter_mut() {
-                    *cost = daily;
-                }
-            }
-        }
-        _ => {}
+    for group in summary.group_by(CostGrouping::Day) {
+        daily_costs.insert(group.key, group.cost_usd);
     }
 
     // Convert to sorted vector
@@ -415,53 +815,196 @@ pub fn get_daily_cost_history(provider: &str, days: u32) -> Vec<(String, f64)> {
     result
 }
 
-/// Scan a single Codex file and return its cost
-fn scan_codex_file_cost(path: &PathBuf) -> f64 {
-    let file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return 0.0,
-    };
-
-    let reader = BufReader::new(file);
-    let mut current_model = String::from("gpt-4o");
-    let mut total_cost = 0.0;
-
-    for line in reader.lines().flatten() {
-        if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) {
-            if let Some(model) = event.get("model").and_then(|m| m.as_str()) {
-                current_model = model.to_string();
-            }
-
-            if let Some(event_msg) = event.get("event_msg") {
-                if event_msg.get("type").and_then(|t| t.as_str()) == Some("token_count") {
-                    let input = event_msg.get("input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                    let cached = event_msg.get("cached_input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-                    let output = event_msg.get("output_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
-
-                    total_cost += CodexPricing::cost_usd(&current_model, input, cached, output);
-                }
+/// Daily cache hit ratio and savings for the last N days as
+/// (date, hit ratio, savings_usd), sorted by date
+pub fn daily_cache_history(summary: &CostSummary, days: u32) -> Vec<(String, Option<f64>, f64)> {
+    let today = Utc::now().date_naive();
+    let groups: HashMap<String, CostGroup> = summary
+        .group_by(CostGrouping::Day)
+        .into_iter()
+        .map(|g| (g.key.clone(), g))
+        .collect();
+
+    (0..days)
+        .rev()
+        .map(|days_ago| {
+            let date = (today - Duration::days(days_ago as i64)).format("%Y-%m-%d").to_string();
+            match groups.get(&date) {
+                Some(group) => (date, group.cache_hit_ratio(), group.cache.savings_usd),
+                None => (date, None, 0.0),
             }
-        }
-    }
+        })
+        .collect()
+}
 
-    total_cost
+/// Highest-cost projects in a summary as (name, cost_usd)
+pub fn top_project_costs(summary: &CostSummary, limit: usize) -> Vec<(String, f64)> {
+    summary
+        .group_by(CostGrouping::Project)
+        .into_iter()
+        .filter(|g| g.cost_usd > 0.0)
+        .take(limit)
+        .map(|g| (g.key, g.cost_usd))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::CostUsageFileUsage;
 
     #[test]
     fn test_codex_pricing() {
-        // Test GPT-4o pricing: $2.50/1M input, $10/1M output
-        let cost = CodexPricing::cost_usd("gpt-4o", 1_000_000, 0, 1_000_000);
-        assert!((cost - 12.50).abs() < 0.01);
+        // GPT-5: $1.25/1M input, $0.125/1M cached, $10/1M output
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let usage = codex_tokens_and_cost("gpt-5", day, &[1_000_000, 0, 1_000_000]);
+        assert!((usage.cost_usd - 11.25).abs() < 0.01);
+
+        // Cached tokens are split out of input
+        let usage = codex_tokens_and_cost("gpt-5", day, &[1_000_000, 400_000, 0]);
+        assert_eq!(usage.input, 600_000);
+        assert_eq!(usage.cached, 400_000);
     }
 
     #[test]
     fn test_claude_pricing() {
-        // Test Sonnet pricing: $3/1M input, $15/1M output
-        let cost = ClaudePricing::cost_usd("claude-3-5-sonnet", 1_000_000, 0, 0, 1_000_000);
-        assert!((cost - 18.0).abs() < 0.01);
+        // Cost is computed per event and stored as nanodollars
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let usage = claude_tokens_and_cost("claude-sonnet-4-5", day, &[100, 20, 30, 40, 18_000_000_000]);
+        assert!((usage.cost_usd - 18.0).abs() < 0.01);
+        assert_eq!(usage.cached, 50);
+        assert_eq!(usage.cache.read_tokens, 20);
+        assert_eq!(usage.cache.write_tokens, 30);
+    }
+
+    #[test]
+    fn test_gemini_pricing() {
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let usage = gemini_tokens_and_cost("gemini-2.5-flash", day, &[1_000_000, 400_000, 100, 2_500_000_000]);
+        assert_eq!(usage.input, 600_000);
+        assert_eq!(usage.cached, 400_000);
+        assert!((usage.cost_usd - 2.5).abs() < 1e-9);
+        // 400k cached at $0.03 instead of $0.30 per 1M
+        assert!((usage.cache.savings_usd - 0.108).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cache_hit_ratio() {
+        // 600k fresh input, 400k cache reads at $1.25 vs $0.125 per 1M
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let usage = codex_tokens_and_cost("gpt-5", day, &[1_000_000, 400_000, 0]);
+        let ratio = usage.cache.hit_ratio(usage.input, usage.cached).unwrap();
+        assert!((ratio - 0.4).abs() < 1e-9);
+        assert!((usage.cache.savings_usd - 0.45).abs() < 1e-9);
+    }
+
+    fn entry(date: &str, model: &str, cost_usd: f64) -> CostEntry {
+        CostEntry {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            model: model.to_string(),
+            project: None,
+            session_id: "s1".to_string(),
+            environment: LOCAL_ROOT_LABEL.to_string(),
+            input_tokens: 100,
+            cached_tokens: 0,
+            output_tokens: 10,
+            cost_usd,
+            cache: CacheUsage::default(),
+            priced: true,
+        }
+    }
+
+    #[test]
+    fn test_unpriced_model_is_reported() {
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let usage = codex_tokens_and_cost("gpt-9-turbo", day, &[1_000_000, 0, 0]);
+        assert!(!usage.priced);
+        // Estimated at the gpt-5 fallback rather than $0
+        assert!((usage.cost_usd - 1.25).abs() < 1e-9);
+        assert!(codex_tokens_and_cost("gpt-4o", day, &[1_000, 0, 0]).priced);
+
+        let mut summary = CostSummary::default();
+        summary.record(entry("2026-09-27", "gpt-5", 1.0));
+        summary.record(CostEntry {
+            priced: false,
+            ..entry("2026-09-27", "gpt-9-turbo", 1.0)
+        });
+        assert_eq!(summary.unpriced_tokens, 110);
+        assert_eq!(summary.unpriced_models, vec!["gpt-9-turbo".to_string()]);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let mut summary = CostSummary::default();
+        summary.record(entry("2026-09-27", "gpt-5", 1.0));
+        summary.record(entry("2026-09-27", "gpt-5", 0.5));
+        summary.record(entry("2026-10-01", "gpt-5-codex", 3.0));
+
+        assert_eq!(summary.entries.len(), 2);
+        assert!((summary.total_cost_usd - 4.5).abs() < 1e-9);
+
+        let weeks = summary.group_by(CostGrouping::Week);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].key, "2026-W39");
+        assert_eq!(weeks[1].key, "2026-W40");
+
+        let models = summary.group_by(CostGrouping::Model);
+        assert_eq!(models[0].key, "gpt-5-codex");
+        assert_eq!(models[1].total_tokens(), 220);
+
+        summary.record(entry("2026-10-01", "openrouter/qwen3-coder", 2.0));
+        summary.record(entry("2026-10-02", "anthropic/claude-sonnet-4-5", 1.0));
+        let vendors = summary.group_by(CostGrouping::Provider);
+        let keys: Vec<&str> = vendors.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, vec!["openai", "openrouter", "anthropic"]);
+    }
+
+    #[test]
+    fn test_session_records() {
+        let mut days = HashMap::new();
+        let mut models = HashMap::new();
+        models.insert("claude-sonnet-4-5".to_string(), vec![100, 0, 0, 10, 2_000_000_000]);
+        models.insert("claude-haiku-4-5".to_string(), vec![50, 0, 0, 5, 500_000_000]);
+        let models_at_nine = models.clone();
+        days.insert("2026-10-02".to_string(), models);
+
+        let mut cache = CostUsageCache::default();
+        cache.files.insert(
+            "/logs/-work-app/abc.jsonl".to_string(),
+            CostUsageFileUsage {
+                mtime_unix_ms: 0,
+                size: 0,
+                days,
+                parsed_bytes: None,
+                last_model: None,
+                last_totals: None,
+                project: Some("/work/app".to_string()),
+                message_hashes: Vec::new(),
+                first_activity: Some("2026-10-02T09:00:00Z".to_string()),
+                last_activity: Some("2026-10-02T11:30:00Z".to_string()),
+                hours: HashMap::from([("2026-10-02T09".to_string(), models_at_nine)]),
+            },
+        );
+
+        let since = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let until = NaiveDate::from_ymd_opt(2026, 10, 3).unwrap();
+        let scanner = CostScanner::with_range(since, until);
+        let range = CostUsageDayRange::new(since, until);
+        let keys: Vec<String> = cache.files.keys().cloned().collect();
+        let summary = scanner.summarize(&cache, &keys, &range, claude_tokens_and_cost, |_| None);
+
+        assert_eq!(summary.sessions.len(), 1);
+        let session = &summary.sessions[0];
+        assert_eq!(session.session_id, "abc");
+        assert!((session.cost_usd - 2.5).abs() < 1e-9);
+        assert_eq!(session.main_model(), Some("claude-sonnet-4-5"));
+        assert_eq!(session.duration().map(|d| d.num_minutes()), Some(150));
+        assert_eq!(summary.filter_project("nothing").sessions.len(), 0);
+
+        let nine_utc = Utc.with_ymd_and_hms(2026, 10, 2, 9, 0, 0).unwrap().with_timezone(&Local);
+        let (weekday, hour) = summary.heatmap.peak().unwrap();
+        assert_eq!(weekday, nine_utc.weekday().num_days_from_monday() as usize);
+        assert_eq!(hour, nine_utc.hour() as usize);
+        assert_eq!(summary.heatmap.cell(weekday, hour).tokens, 165);
     }
 }
@@ -0,0 +1,262 @@
+//! Live cost updates for today
+//!
+//! Polls the Codex and Claude log directories for session files touched
+//! since yesterday (Codex: started within the cost history window) and
+//! parses only the bytes appended since the previous poll (via `CostUsageFileUsage::parsed_bytes`), so today's cost can tick
+//! up while an agent is running without rescanning the whole history.
+//! Gemini recordings are rewritten in place, so changed ones are re-read.
+
+use chrono::{Duration, NaiveDate, Utc};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::core::{CostUsageCache, CostUsageDayRange, JsonlScanner, LogRoot, LogRoots, ProviderId};
+use crate::cost_scanner::{claude_tokens_and_cost, codex_tokens_and_cost, gemini_tokens_and_cost};
+
+/// Days of Codex session folders watched. Sessions are filed under the day
+/// they started, so long-running ones are found as far back as the 30-day
+/// cost history scans.
+const CODEX_SESSION_DAYS: i64 = 30;
+
+/// Today's totals for one provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveCost {
+    /// UTC day key (YYYY-MM-DD), matching the daily cost history
+    pub date: String,
+    pub tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Incrementally tracks today's cost for one provider
+pub struct CostWatcher {
+    provider: ProviderId,
+    roots: Vec<PathBuf>,
+    /// In-memory cache holding only recently touched files
+    cache: CostUsageCache,
+    last: Option<LiveCost>,
+}
+
+impl CostWatcher {
+    /// Watcher for a provider with local logs, or None if it has none
+    pub fn new(provider: ProviderId, log_roots: &LogRoots) -> Option<Self> {
+        let roots: Vec<PathBuf> = match provider {
+            ProviderId::Codex | ProviderId::Claude => {
+                log_roots.for_provider(provider).iter().map(|r| r.path.clone()).collect()
+            }
+            ProviderId::Gemini => JsonlScanner::default_gemini_tmp_root()
+                .filter(|root| root.exists())
+                .into_iter()
+                .collect(),
+            _ => return None,
+        };
+        if roots.is_empty() {
+            return None;
+        }
+
+        let cache = Self::seed_cache(provider, JsonlScanner::load_cache(provider, None));
+        Some(Self::with_cache(provider, roots, cache))
+    }
+
+    fn with_cache(provider: ProviderId, roots: Vec<PathBuf>, cache: CostUsageCache) -> Self {
+        Self {
+            provider,
+            roots,
+            cache,
+            last: None,
+        }
+    }
+
+    /// Keep usage for recent files only. Older Codex files stay as stubs with
+    /// their offsets so they are only read again if they grow, and older
+    /// Claude files keep their hashes so resumed sessions don't count
+    /// replayed messages twice.
+    fn seed_cache(provider: ProviderId, mut cache: CostUsageCache) -> CostUsageCache {
+        let cutoff = (Utc::now() - Duration::days(1)).timestamp_millis();
+        cache.files.retain(|_, usage| {
+            if usage.mtime_unix_ms >= cutoff {
+                return true;
+            }
+            usage.days.clear();
+            usage.hours.clear();
+            match provider {
+                ProviderId::Codex => true,
+                ProviderId::Claude => !usage.message_hashes.is_empty(),
+                _ => false,
+            }
+        });
+        cache
+    }
+
+    pub fn provider(&self) -> ProviderId {
+        self.provider
+    }
+
+    /// Parse newly appended log lines. Returns today's totals when they changed.
+    pub fn poll(&mut self) -> Option<LiveCost> {
+        self.poll_on(Utc::now().date_naive())
+    }
+
+    fn poll_on(&mut self, today: NaiveDate) -> Option<LiveCost> {
+        let range = CostUsageDayRange::new(today - Duration::days(1), today);
+        let tokens_and_cost = match self.provider {
+            ProviderId::Codex => {
+                // Codex files sit in the folder of the day the session started
+                let range = CostUsageDayRange::new(today - Duration::days(CODEX_SESSION_DAYS), today);
+                JsonlScanner::refresh_codex_cache(&self.roots, &range, &mut self.cache);
+                codex_tokens_and_cost
+            }
+            ProviderId::Gemini => {
+                JsonlScanner::refresh_gemini_cache(&self.roots[0], &range, &mut self.cache);
+                gemini_tokens_and_cost
+            }
+            _ => {
+                JsonlScanner::refresh_claude_cache(&self.roots, &range, &mut self.cache);
+                claude_tokens_and_cost
+            }
+        };
+
+        let date = CostUsageDayRange::day_key(today);
+        let mut current = LiveCost {
+            date: date.clone(),
+            tokens: 0,
+            cost_usd: 0.0,
+        };
+        for (model, packed) in self.cache.days.get(&date).into_iter().flatten() {
+            let usage = tokens_and_cost(model, today, packed);
+            current.tokens += usage.input + usage.cached + usage.output;
+            current.cost_usd += usage.cost_usd;
+        }
+
+        if self.last.as_ref() == Some(&current) {
+            return None;
+        }
+        self.last = Some(current.clone());
+        Some(current)
+    }
+}
+
+/// Stops the watcher thread when dropped
+pub struct CostWatcherHandle {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for CostWatcherHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Poll `providers` every `interval` on a background thread, calling
+/// `on_change` whenever a provider's cost for today changes. Codex and
+/// Claude are watched in every root `LogRoots::discover` finds.
+pub fn spawn<F>(
+    providers: &[ProviderId],
+    log_roots: Vec<LogRoot>,
+    discover_wsl: bool,
+    interval: std::time::Duration,
+    on_change: F,
+) -> Option<CostWatcherHandle>
+where
+    F: Fn(ProviderId, &LiveCost) + Send + 'static,
+{
+    let providers = providers.to_vec();
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+
+    std::thread::Builder::new()
+        .name("cost-watcher".to_string())
+        .spawn(move || {
+            // Loading caches can take a moment, so do it off the UI thread
+            let roots = LogRoots::discover(&log_roots, discover_wsl);
+            let mut watchers: Vec<CostWatcher> = providers
+                .into_iter()
+                .filter_map(|provider| CostWatcher::new(provider, &roots))
+                .collect();
+            while !thread_stop.load(Ordering::Relaxed) {
+                for watcher in &mut watchers {
+                    if let Some(live) = watcher.poll() {
+                        on_change(watcher.provider(), &live);
+                    }
+                }
+                std::thread::sleep(interval);
+            }
+        })
+        .map_err(|e| tracing::warn!("Failed to start cost watcher: {}", e))
+        .ok()?;
+
+    Some(CostWatcherHandle { stop })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn claude_line(id: &str, timestamp: &str, output: i64) -> String {
+        format!(
+            r#"{{"type":"assistant","timestamp":"{}","requestId":"req-{}","message":{{"id":"{}","model":"claude-sonnet-4-5","usage":{{"input_tokens":100,"output_tokens":{},"cache_read_input_tokens":0,"cache_creation_input_tokens":0}}}}}}"#,
+            timestamp, id, id, output
+        )
+    }
+
+    #[test]
+    fn test_poll_tails_appended_lines() {
+        let root = tempfile::tempdir().unwrap();
+        let project = root.path().join("-tmp-app");
+        std::fs::create_dir_all(&project).unwrap();
+        let file = project.join("session.jsonl");
+
+        let today = Utc::now().date_naive();
+        let ts = format!("{}T10:00:00Z", today);
+        std::fs::write(&file, format!("{}\n", claude_line("m1", &ts, 50))).unwrap();
+
+        let mut watcher =
+            CostWatcher::with_cache(ProviderId::Claude, vec![root.path().to_path_buf()], CostUsageCache::default());
+        let first = watcher.poll_on(today).expect("initial totals");
+        assert_eq!(first.tokens, 150);
+        assert!(first.cost_usd > 0.0);
+        assert_eq!(watcher.poll_on(today), None);
+
+        let mut handle = std::fs::OpenOptions::new().append(true).open(&file).unwrap();
+        writeln!(handle, "{}", claude_line("m2", &ts, 250)).unwrap();
+        // Streaming repeats of an already counted message are ignored
+        writeln!(handle, "{}", claude_line("m1", &ts, 50)).unwrap();
+        drop(handle);
+
+        let second = watcher.poll_on(today).expect("updated totals");
+        assert_eq!(second.tokens, 500);
+        assert!(second.cost_usd > first.cost_usd);
+        assert!(watcher.cache.files.values().all(|f| f.parsed_bytes.is_some()));
+    }
+
+    #[test]
+    fn test_poll_follows_codex_session_started_days_ago() {
+        let root = tempfile::tempdir().unwrap();
+        let today = Utc::now().date_naive();
+        let started = today - Duration::days(3);
+        let folder = root.path().join(started.format("%Y/%m/%d").to_string());
+        std::fs::create_dir_all(&folder).unwrap();
+        let file = folder.join("rollout.jsonl");
+
+        let event = |date: NaiveDate, input: i64, output: i64| {
+            format!(
+                r#"{{"timestamp":"{}T10:00:00Z","type":"event_msg","payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":{},"cached_input_tokens":0,"output_tokens":{}}}}}}}}}"#,
+                date, input, output
+            )
+        };
+        std::fs::write(&file, format!("{}\n", event(started, 100, 10))).unwrap();
+
+        let roots = vec![root.path().to_path_buf()];
+        let mut watcher = CostWatcher::with_cache(ProviderId::Codex, roots, CostUsageCache::default());
+        assert_eq!(watcher.poll_on(today).expect("initial totals").tokens, 0);
+
+        let mut handle = std::fs::OpenOptions::new().append(true).open(&file).unwrap();
+        writeln!(handle, "{}", event(today, 300, 20)).unwrap();
+        drop(handle);
+
+        let live = watcher.poll_on(today).expect("updated totals");
+        assert_eq!(live.tokens, 210);
+        assert!(live.cost_usd > 0.0);
+    }
+}
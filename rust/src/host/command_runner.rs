@@ -125,6 +125,41 @@ impl CommandRunner {
         which::which(binary).ok()
     }
 
+    /// Resolve a binary name or path
+    fn resolve(binary: &str) -> Result<PathBuf, CommandError> {
+        if std::path::Path::new(binary).exists() {
+            Ok(PathBuf::from(binary))
+        } else {
+            Self::which(binary).ok_or_else(|| CommandError::BinaryNotFound(binary.to_string()))
+        }
+    }
+
+    /// Start a command attached to this terminal, for interactive tools the
+    /// caller supervises until they exit. On Windows it gets its own process
+    /// group so `interrupt` reaches it and not the caller's console.
+    pub fn spawn_attached(
+        &self,
+        binary: &str,
+        args: &[String],
+        options: &CommandOptions,
+    ) -> Result<Child, CommandError> {
+        let mut cmd = Command::new(Self::resolve(binary)?);
+        cmd.args(args).args(&options.extra_args).envs(&self.env_additions);
+        if let Some(dir) = &options.working_directory {
+            cmd.current_dir(dir);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x00000200); // CREATE_NEW_PROCESS_GROUP
+        }
+        cmd.stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| CommandError::LaunchFailed(e.to_string()))
+    }
+
     /// Run a command and capture output
     pub fn run(
         &self,
@@ -133,11 +168,7 @@ impl CommandRunner {
         options: &CommandOptions,
     ) -> Result<CommandResult, CommandError> {
         // Find the binary
-        let binary_path = if std::path::Path::new(binary).exists() {
-            PathBuf::from(binary)
-        } else {
-            Self::which(binary).ok_or_else(|| CommandError::BinaryNotFound(binary.to_string()))?
-        };
+        let binary_path = Self::resolve(binary)?;
 
         // Build the command
         let mut cmd = Command::new(&binary_path);
@@ -303,6 +334,29 @@ impl CommandRunner {
     }
 }
 
+/// Ask a child started with `spawn_attached` to stop. Windows can't send
+/// Ctrl+C to a single process group, so its group gets Ctrl+Break.
+#[cfg(windows)]
+pub fn interrupt(pid: u32) -> Result<(), CommandError> {
+    use windows::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+    unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) }.map_err(|e| CommandError::IoError(e.to_string()))
+}
+
+/// Ask a child started with `spawn_attached` to stop, as Ctrl+C would
+#[cfg(not(windows))]
+pub fn interrupt(pid: u32) -> Result<(), CommandError> {
+    let status = Command::new("kill")
+        .args(["-INT", &pid.to_string()])
+        .status()
+        .map_err(|e| CommandError::IoError(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(CommandError::IoError(format!("kill -INT exited with {}", status)))
+    }
+}
+
 impl Default for CommandRunner {
     fn default() -> Self {
         Self::new()
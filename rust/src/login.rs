@@ -1,12 +1,18 @@
 //! Login flow runners for various providers
 //!
-//! Runs CLI login commands and captures output/URLs
+//! Runs CLI login commands, either attached to the terminal and watching
+//! for fresh credentials, or with output piped to capture sign-in URLs
 
 #![allow(dead_code)]
 
-use std::process::{Command, Stdio};
-use std::io::{BufRead, BufReader};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, SystemTime};
+
 use regex_lite::Regex;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
 
 /// Result of a login attempt
 #[derive(Debug, Clone)]
@@ -22,6 +28,8 @@ pub enum LoginOutcome {
     Success,
     TimedOut,
     Failed { status: i32 },
+    /// The CLI exited cleanly without saving new credentials
+    NoCredentials,
     MissingBinary,
     LaunchFailed(String),
 }
@@ -35,39 +43,39 @@ pub enum LoginPhase {
     Complete,
 }
 
-/// Run Claude CLI login
+/// Run Claude CLI login. `/login` opens the interactive CLI, so it runs
+/// attached to the terminal until the user exits it.
 pub async fn run_claude_login<F>(timeout_secs: u64, on_phase: F) -> LoginResult
 where
     F: Fn(LoginPhase) + Send + 'static,
 {
-    run_cli_login("claude", &["/login"], timeout_secs, on_phase, &[
-        "Successfully logged in",
-        "Login successful",
-        "Logged in successfully",
-    ]).await
+    let creds_path = home_dir().join(".claude").join(".credentials.json");
+    run_attached_login("claude", &["/login"], &creds_path, timeout_secs, on_phase).await
 }
 
-/// Run Codex CLI login
+/// Run Codex CLI login (`codex login`), which exits once the browser
+/// sign-in completes
 pub async fn run_codex_login<F>(timeout_secs: u64, on_phase: F) -> LoginResult
 where
     F: Fn(LoginPhase) + Send + 'static,
 {
-    run_cli_login("codex", &["auth", "login"], timeout_secs, on_phase, &[
-        "Successfully logged in",
-        "Login successful",
-        "Logged in successfully",
-    ]).await
+    let codex_home = std::env::var("CODEX_HOME")
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+        .map(|h| PathBuf::from(h.trim()))
+        .unwrap_or_else(|| home_dir().join(".codex"));
+    run_attached_login("codex", &["login"], &codex_home.join("auth.json"), timeout_secs, on_phase).await
 }
 
-/// Run Gemini/gcloud login
+/// Run Gemini CLI login. The CLI has no login subcommand and asks for
+/// sign-in on start, so it runs attached to the terminal until the user
+/// exits it.
 pub async fn run_gemini_login<F>(timeout_secs: u64, on_phase: F) -> LoginResult
 where
     F: Fn(LoginPhase) + Send + 'static,
 {
-    run_cli_login("gcloud", &["auth", "login"], timeout_secs, on_phase, &[
-        "You are now logged in",
-        "Credentials saved",
-    ]).await
+    let creds_path = home_dir().join(".gemini").join("oauth_creds.json");
+    run_attached_login("gemini", &[], &creds_path, timeout_secs, on_phase).await
 }
 
 /// Run Copilot/GitHub device flow login
@@ -75,42 +83,117 @@ pub async fn run_copilot_login<F>(timeout_secs: u64, on_phase: F) -> LoginResult
 where
     F: Fn(LoginPhase) + Send + 'static,
 {
-    run_cli_login("gh", &["auth", "login", "-w"], timeout_secs, on_phase, &[
-        "Logged in as",
-        "Authentication complete",
-    ]).await
+    run_cli_login("gh", &["auth", "login", "-w"], timeout_secs, on_phase).await
+}
+
+fn home_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
 }
 
-/// Generic CLI login runner
-async fn run_cli_login<F>(
+/// Run a CLI attached to the terminal and wait for it to exit. The login
+/// succeeded only if it wrote `creds_path` while running.
+async fn run_attached_login<F>(
     binary: &str,
     args: &[&str],
+    creds_path: &Path,
     timeout_secs: u64,
     on_phase: F,
-    success_markers: &[&str],
 ) -> LoginResult
 where
     F: Fn(LoginPhase) + Send + 'static,
 {
-    // Check if binary exists
-    let binary_path = match which::which(binary) {
-        Ok(p) => p,
+    let result = |outcome| LoginResult {
+        outcome,
+        output: String::new(),
+        auth_link: None,
+    };
+    let Ok(binary_path) = which::which(binary) else {
+        return LoginResult {
+            outcome: LoginOutcome::MissingBinary,
+            output: format!("{} not found in PATH", binary),
+            auth_link: None,
+        };
+    };
+
+    on_phase(LoginPhase::Requesting);
+    let started = SystemTime::now();
+    let mut child = match Command::new(&binary_path)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(e) => return result(LoginOutcome::LaunchFailed(e.to_string())),
+    };
+    on_phase(LoginPhase::WaitingBrowser);
+
+    match tokio::time::timeout(Duration::from_secs(timeout_secs), child.wait()).await {
+        Ok(Ok(status)) => {
+            if credentials_written_since(creds_path, started) {
+                on_phase(LoginPhase::Complete);
+                result(LoginOutcome::Success)
+            } else if status.success() {
+                result(LoginOutcome::NoCredentials)
+            } else {
+                result(LoginOutcome::Failed {
+                    status: status.code().unwrap_or(-1),
+                })
+            }
+        }
+        Ok(Err(e)) => result(LoginOutcome::LaunchFailed(e.to_string())),
         Err(_) => {
-            return LoginResult {
-                outcome: LoginOutcome::MissingBinary,
-                output: format!("{} not found in PATH", binary),
-                auth_link: None,
-            };
+            let _ = child.kill().await;
+            restore_terminal();
+            result(LoginOutcome::TimedOut)
         }
+    }
+}
+
+/// Undo what a killed full-screen CLI may have left behind: the alternate
+/// screen, a hidden cursor and raw input mode
+fn restore_terminal() {
+    print!("\x1b[?1049l\x1b[?25h");
+    let _ = std::io::stdout().flush();
+
+    #[cfg(not(windows))]
+    {
+        let _ = std::process::Command::new("stty")
+            .arg("sane")
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+/// Whether the file was modified at or after `since`
+fn credentials_written_since(path: &Path, since: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified >= since)
+}
+
+/// Run a CLI with its output piped, opening the first URL it prints.
+/// Succeeds when it exits cleanly within the timeout.
+async fn run_cli_login<F>(binary: &str, args: &[&str], timeout_secs: u64, on_phase: F) -> LoginResult
+where
+    F: Fn(LoginPhase) + Send + 'static,
+{
+    let Ok(binary_path) = which::which(binary) else {
+        return LoginResult {
+            outcome: LoginOutcome::MissingBinary,
+            output: format!("{} not found in PATH", binary),
+            auth_link: None,
+        };
     };
 
     on_phase(LoginPhase::Requesting);
 
-    // Spawn process
     let mut child = match Command::new(&binary_path)
         .args(args)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
+        .kill_on_drop(true)
         .spawn()
     {
         Ok(c) => c,
@@ -123,124 +206,70 @@ where
         }
     };
 
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
+    // Both pipes feed one channel so neither can stall the other
+    let (tx, mut lines) = tokio::sync::mpsc::unbounded_channel();
+    forward_lines(child.stdout.take(), tx.clone());
+    forward_lines(child.stderr.take(), tx);
 
     let mut output = String::new();
     let mut auth_link = None;
     let url_regex = Regex::new(r"https?://[A-Za-z0-9._~:/?#\[\]@!$&'()*+,;=%-]+").unwrap();
 
-    // Read output with timeout
-    let start = std::time::Instant::now();
-    let timeout = std::time::Duration::from_secs(timeout_secs);
-
-    // Read stdout
-    if let Some(stdout) = stdout {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines().map_while(Result::ok) {
+    let session = async {
+        while let Some(line) = lines.recv().await {
             output.push_str(&line);
             output.push('\n');
 
-            // Check for URL (indicates browser login)
+            // A URL means the sign-in continues in the browser
             if auth_link.is_none() {
                 if let Some(m) = url_regex.find(&line) {
                     auth_link = Some(m.as_str().to_string());
                     on_phase(LoginPhase::WaitingBrowser);
-
-                    // Open the URL in browser
                     let _ = open::that(m.as_str());
                 }
             }
-
-            // Check for success markers
-            for marker in success_markers {
-                if line.contains(marker) {
-                    on_phase(LoginPhase::Complete);
-                    let _ = child.kill();
-                    return LoginResult {
-                        outcome: LoginOutcome::Success,
-                        output,
-                        auth_link,
-                    };
-                }
-            }
-
-            // Check timeout
-            if start.elapsed() > timeout {
-                let _ = child.kill();
-                return LoginResult {
-                    outcome: LoginOutcome::TimedOut,
-                    output,
-                    auth_link,
-                };
-            }
         }
-    }
-
-    // Read stderr too
-    if let Some(stderr) = stderr {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines().map_while(Result::ok) {
-            output.push_str(&line);
-            output.push('\n');
-
-            if auth_link.is_none() {
-                if let Some(m) = url_regex.find(&line) {
-                    auth_link = Some(m.as_str().to_string());
-                    on_phase(LoginPhase::WaitingBrowser);
-                    let _ = open::that(m.as_str());
-                }
-            }
-
-            for marker in success_markers {
-                if line.contains(marker) {
-                    on_phase(LoginPhase::Complete);
-                    let _ = child.kill();
-                    return LoginResult {
-                        outcome: LoginOutcome::Success,
-                        output,
-                        auth_link,
-                    };
-                }
-            }
+        child.wait().await
+    };
+    let finished = tokio::time::timeout(Duration::from_secs(timeout_secs), session).await;
 
-            if start.elapsed() > timeout {
-                let _ = child.kill();
-                return LoginResult {
-                    outcome: LoginOutcome::TimedOut,
-                    output,
-                    auth_link,
-                };
-            }
+    let outcome = match finished {
+        Ok(Ok(status)) if status.success() => {
+            on_phase(LoginPhase::Complete);
+            LoginOutcome::Success
+        }
+        Ok(Ok(status)) => LoginOutcome::Failed {
+            status: status.code().unwrap_or(-1),
+        },
+        Ok(Err(e)) => LoginOutcome::LaunchFailed(e.to_string()),
+        Err(_) => {
+            let _ = child.kill().await;
+            LoginOutcome::TimedOut
         }
+    };
+    LoginResult {
+        outcome,
+        output,
+        auth_link,
     }
+}
 
-    // Wait for process to complete
-    match child.wait() {
-        Ok(status) => {
-            if status.success() {
-                on_phase(LoginPhase::Complete);
-                LoginResult {
-                    outcome: LoginOutcome::Success,
-                    output,
-                    auth_link,
-                }
-            } else {
-                LoginResult {
-                    outcome: LoginOutcome::Failed {
-                        status: status.code().unwrap_or(-1),
-                    },
-                    output,
-                    auth_link,
-                }
+/// Send each line read from a child pipe to `tx`
+fn forward_lines<R>(reader: Option<R>, tx: tokio::sync::mpsc::UnboundedSender<String>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let Some(reader) = reader else {
+        return;
+    };
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx.send(line).is_err() {
+                break;
             }
         }
-        Err(e) => LoginResult {
-            outcome: LoginOutcome::LaunchFailed(e.to_string()),
-            output,
-            auth_link,
-        },
-    }
+    });
 }
 
 /// Open a URL in the default browser
@@ -248,3 +277,55 @@ pub fn open_auth_url(url: &str) -> anyhow::Result<()> {
     open::that(url)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_cli_login_times_out_without_output() {
+        let result = run_cli_login("sleep", &["30"], 1, |_| {}).await;
+        assert!(matches!(result.outcome, LoginOutcome::TimedOut));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_cli_login_collects_both_pipes() {
+        let result = run_cli_login("sh", &["-c", "echo out; echo err >&2"], 5, |_| {}).await;
+        assert!(matches!(result.outcome, LoginOutcome::Success));
+        assert!(result.output.contains("out\n") && result.output.contains("err\n"));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_attached_login_needs_fresh_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let creds = dir.path().join("creds.json");
+        std::fs::write(&creds, "{}").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let result = run_attached_login("true", &[], &creds, 5, |_| {}).await;
+        assert!(matches!(result.outcome, LoginOutcome::NoCredentials));
+
+        // File times come from a coarse clock, so don't write in the same tick
+        let script = format!("sleep 0.1; echo {{}} > '{}'", creds.display());
+        let result = run_attached_login("sh", &["-c", &script], &creds, 5, |_| {}).await;
+        assert!(matches!(result.outcome, LoginOutcome::Success));
+    }
+
+    #[test]
+    fn test_credentials_written_since() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("oauth_creds.json");
+        let before = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+
+        assert!(!credentials_written_since(&path, before));
+        std::fs::write(&path, "{}").unwrap();
+        assert!(credentials_written_since(&path, before));
+        assert!(!credentials_written_since(
+            &path,
+            std::time::SystemTime::now() + std::time::Duration::from_secs(60)
+        ));
+    }
+}
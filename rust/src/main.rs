@@ -9,6 +9,7 @@ mod browser;
 mod cli;
 mod core;
 mod cost_scanner;
+mod cost_watcher;
 mod host;
 mod logging;
 mod login;
@@ -72,6 +73,17 @@ fn run() -> i32 {
                 }
             })
         }
+        Some(Commands::Blocks(args)) => {
+            rt.block_on(async {
+                match cli::blocks::run(args).await {
+                    Ok(()) => exit_codes::SUCCESS,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit_codes::UNEXPECTED_FAILURE
+                    }
+                }
+            })
+        }
         Some(Commands::Menubar) => {
             // Hide the console window for GUI mode
             #[cfg(windows)]
@@ -124,6 +136,76 @@ fn run() -> i32 {
                 }
             })
         }
+        Some(Commands::Doctor(args)) => {
+            // The JSON report already says which providers aren't ready
+            let json = args.json || args.format == cli::usage::OutputFormat::Json;
+            rt.block_on(async {
+                match cli::doctor::run(args).await {
+                    Ok(()) => exit_codes::SUCCESS,
+                    Err(e) => {
+                        if !(json && e.is::<cli::doctor::ProvidersNotReady>()) {
+                            eprintln!("Error: {}", e);
+                        }
+                        categorize_error(&e)
+                    }
+                }
+            })
+        }
+        Some(Commands::Login(args)) => {
+            rt.block_on(async {
+                match cli::login::run(args).await {
+                    Ok(()) => exit_codes::SUCCESS,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        categorize_error(&e)
+                    }
+                }
+            })
+        }
+        Some(Commands::Pricing(args)) => {
+            rt.block_on(async {
+                match cli::pricing::run(args).await {
+                    Ok(()) => exit_codes::SUCCESS,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        categorize_error(&e)
+                    }
+                }
+            })
+        }
+        Some(Commands::Wait(args)) => {
+            rt.block_on(async {
+                match cli::wait::run(args).await {
+                    Ok(()) => exit_codes::SUCCESS,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        categorize_error(&e)
+                    }
+                }
+            })
+        }
+        Some(Commands::Check(args)) => {
+            rt.block_on(async {
+                match cli::check::run(args).await {
+                    Ok(()) => exit_codes::SUCCESS,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        categorize_error(&e)
+                    }
+                }
+            })
+        }
+        Some(Commands::Run(args)) => {
+            rt.block_on(async {
+                match cli::run::run(args).await {
+                    Ok(code) => code,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        categorize_error(&e)
+                    }
+                }
+            })
+        }
         None => {
             // Default: run usage command with args from top-level CLI
             let args = cli.to_usage_args();
@@ -142,6 +224,22 @@ fn run() -> i32 {
 
 /// Categorize an error into the appropriate exit code
 fn categorize_error(e: &anyhow::Error) -> i32 {
+    if e.is::<cli::cost::BudgetExceeded>() {
+        return exit_codes::BUDGET_EXCEEDED;
+    }
+    if e.is::<cli::wait::WaitTimedOut>() {
+        return exit_codes::WAIT_TIMEOUT;
+    }
+    if e.is::<cli::run::RunLimitReached>() {
+        return exit_codes::BUDGET_EXCEEDED;
+    }
+    if e.is::<cli::doctor::ProvidersNotReady>() {
+        return exit_codes::AUTH_MISSING;
+    }
+    if e.is::<cli::check::CheckFailed>() {
+        return exit_codes::UNEXPECTED_FAILURE;
+    }
+
     let msg = e.to_string().to_lowercase();
 
     if msg.contains("not installed") || msg.contains("not found") || msg.contains("binary") {
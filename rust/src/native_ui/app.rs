@@ -6,19 +6,24 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use super::charts::{
-    ChartPoint, CostHistoryChart, CreditsHistoryChart, ServiceUsage, UsageBreakdownChart,
-    UsageBreakdownPoint,
+    CacheChartPoint, CacheHitChart, ChartPoint, CostHistoryChart, CreditsHistoryChart,
+    ServiceUsage, UsageBreakdownChart, UsageBreakdownPoint, UsageHeatmapChart,
 };
 use super::preferences::PreferencesWindow;
 use super::provider_icons::ProviderIconCache;
 use super::theme::{provider_color, status_color, FontSize, Radius, Spacing, Theme};
 use crate::core::{
-    FetchContext, OpenAIDashboardCacheStore, PersonalInfoRedactor, Provider, ProviderId,
-    ProviderFetchResult, RateWindow,
+    detect_cost_anomalies, forecast_month_end, AnomalyOptions, BudgetSpend, BudgetStatus, CostAnomaly, CostSnapshot, FetchContext, MonthForecast, OpenAIDashboardCacheStore, PersonalInfoRedactor, Provider, ProviderId,
+    LogRoots, ProviderFetchResult, RateWindow, WidgetProviderEntry, WidgetSnapshot, WidgetSnapshotStore,
 };
 use crate::core::{TokenAccountStore, TokenAccountSupport};
-use crate::cost_scanner::get_daily_cost_history;
+use crate::cost_scanner::{
+    daily_cache_history, daily_cost_history, scan_provider_costs, top_project_costs, SessionCost, UsageHeatmap,
+};
+use crate::cost_watcher::{self, CostWatcherHandle, LiveCost};
 use crate::login::LoginPhase;
+use crate::notifications::rules::ProviderObservation;
+use crate::notifications::{NotificationManager, NotificationType};
 use crate::providers::*;
 use crate::settings::{ApiKeys, ManualCookies, Settings};
 use crate::browser::cookies::get_cookie_header;
@@ -44,11 +49,23 @@ pub struct ProviderData {
     pub pace_percent: Option<f64>,
     pub pace_lasts_to_reset: bool,
     pub cost_used: Option<String>,
+    /// Cost reported by the provider, used for budgets
+    pub cost_snapshot: Option<CostSnapshot>,
     pub credits_remaining: Option<f64>,
     pub credits_percent: Option<f64>,
     pub status_level: StatusLevel,
     pub status_description: Option<String>,
     pub cost_history: Vec<(String, f64)>,
+    /// Month-end projection from `cost_history`
+    pub cost_forecast: Option<MonthForecast>,
+    /// Unusual days in `cost_history`
+    pub cost_anomalies: Vec<CostAnomaly>,
+    pub cost_by_project: Vec<(String, f64)>,
+    pub top_sessions: Vec<SessionCost>,
+    pub usage_heatmap: Option<UsageHeatmap>,
+    pub cache_history: Vec<CacheChartPoint>,
+    /// Cache hit ratio and savings over the cost period
+    pub cache_summary: Option<(f64, f64)>,
     pub credits_history: Vec<(String, f64)>,
     pub usage_breakdown: Vec<UsageBreakdownPoint>,
 }
@@ -71,11 +88,19 @@ impl ProviderData {
             pace_percent: None,
             pace_lasts_to_reset: false,
             cost_used: None,
+            cost_snapshot: None,
             credits_remaining: None,
             credits_percent: None,
             status_level: StatusLevel::Unknown,
             status_description: None,
             cost_history: Vec::new(),
+            cost_forecast: None,
+            cost_anomalies: Vec::new(),
+            cost_by_project: Vec::new(),
+            top_sessions: Vec::new(),
+            usage_heatmap: None,
+            cache_history: Vec::new(),
+            cache_summary: None,
             credits_history: Vec::new(),
             usage_breakdown: Vec::new(),
         }
@@ -123,11 +148,19 @@ impl ProviderData {
             pace_percent,
             pace_lasts_to_reset: pace_lasts,
             cost_used,
+            cost_snapshot: result.cost.clone(),
             credits_remaining,
             credits_percent,
             status_level: StatusLevel::Unknown,
             status_description: None,
             cost_history: Vec::new(),
+            cost_forecast: None,
+            cost_anomalies: Vec::new(),
+            cost_by_project: Vec::new(),
+            top_sessions: Vec::new(),
+            usage_heatmap: None,
+            cache_history: Vec::new(),
+            cache_summary: None,
             credits_history: Vec::new(),
             usage_breakdown: Vec::new(),
         }
@@ -150,16 +183,30 @@ impl ProviderData {
             pace_percent: None,
             pace_lasts_to_reset: false,
             cost_used: None,
+            cost_snapshot: None,
             credits_remaining: None,
             credits_percent: None,
             status_level: StatusLevel::Unknown,
             status_description: None,
             cost_history: Vec::new(),
+            cost_forecast: None,
+            cost_anomalies: Vec::new(),
+            cost_by_project: Vec::new(),
+            top_sessions: Vec::new(),
+            usage_heatmap: None,
+            cache_history: Vec::new(),
+            cache_summary: None,
             credits_history: Vec::new(),
             usage_breakdown: Vec::new(),
         }
     }
 
+    /// Recompute the forecast and unusual days after `cost_history` changes
+    fn update_cost_insights(&mut self) {
+        self.cost_forecast = forecast_month_end(&self.cost_history, chrono::Utc::now().date_naive());
+        self.cost_anomalies = detect_cost_anomalies(&self.cost_history, &AnomalyOptions::default());
+    }
+
     /// Get the preferred metric percent based on the MetricPreference setting
     pub fn get_preferred_metric(&self, pref: crate::settings::MetricPreference) -> f64 {
         match pref {
@@ -319,6 +366,20 @@ fn load_usage_breakdown_points(provider_id: ProviderId, account_email: Option<&s
         .collect()
 }
 
+/// Put today's live cost into a daily history, rolling over to a new day
+/// when needed. Histories that haven't been scanned yet are left alone.
+fn apply_live_cost(history: &mut Vec<(String, f64)>, live: &LiveCost) {
+    match history.last_mut() {
+        // The full scan may have seen files the watcher skips, so only raise
+        Some((date, cost)) if *date == live.date => *cost = cost.max(live.cost_usd),
+        Some((date, _)) if *date < live.date => {
+            history.remove(0);
+            history.push((live.date.clone(), live.cost_usd));
+        }
+        _ => {}
+    }
+}
+
 fn random_surprise_delay() -> Duration {
     use rand::Rng;
     let mut rng = rand::rng();
@@ -343,6 +404,12 @@ struct SharedState {
     login_provider: Option<String>,
     login_phase: LoginPhase,
     login_message: Option<String>,
+    /// Usage and status alerts, shown as toasts and posted to webhooks
+    notifications: NotificationManager,
+    /// Budgets evaluated against the latest costs
+    budget_statuses: Vec<BudgetStatus>,
+    /// Costs or budgets changed since `budget_statuses` was evaluated
+    budgets_stale: bool,
 }
 
 pub struct CodexBarApp {
@@ -355,6 +422,8 @@ pub struct CodexBarApp {
     was_refreshing: bool, // Track previous frame's refresh state
     pending_main_window_layout: bool,
     anchor_main_window_to_pointer: bool,
+    _cost_watcher: Option<CostWatcherHandle>,
+    budget_window_open: bool,
 }
 
 impl CodexBarApp {
@@ -398,6 +467,9 @@ impl CodexBarApp {
             login_provider: None,
             login_phase: LoginPhase::Idle,
             login_message: None,
+            notifications: NotificationManager::load(),
+            budget_statuses: Vec::new(),
+            budgets_stale: true,
         }));
 
         // Initialize system tray based on settings
@@ -498,6 +570,27 @@ impl CodexBarApp {
             }
         };
 
+        // Tick today's cost up as local logs grow, between full refreshes
+        let watched: Vec<ProviderId> = enabled_ids
+            .iter()
+            .copied()
+            .filter(|id| matches!(id, ProviderId::Codex | ProviderId::Claude | ProviderId::Gemini))
+            .collect();
+        let cost_watcher = {
+            let state = Arc::clone(&state);
+            let log_roots = settings.log_roots.clone();
+            let discover_wsl = settings.discover_wsl_logs;
+            cost_watcher::spawn(&watched, log_roots, discover_wsl, Duration::from_secs(5), move |id, live| {
+                if let Ok(mut s) = state.lock() {
+                    if let Some(provider) = s.providers.iter_mut().find(|p| p.name == id.cli_name()) {
+                        apply_live_cost(&mut provider.cost_history, live);
+                        provider.update_cost_insights();
+                        s.budgets_stale = true;
+                    }
+                }
+            })
+        };
+
         Self {
             state,
             settings,
@@ -508,6 +601,137 @@ impl CodexBarApp {
             was_refreshing: false,
             pending_main_window_layout: true,
             anchor_main_window_to_pointer: false,
+            _cost_watcher: cost_watcher,
+            budget_window_open: false,
+        }
+    }
+
+    /// Re-evaluate budgets once costs or budgets have changed, and send
+    /// any new alerts
+    fn refresh_budget_statuses(&self) {
+        let Ok(mut s) = self.state.lock() else {
+            return;
+        };
+        if !s.budgets_stale {
+            return;
+        }
+        s.budgets_stale = false;
+        s.budget_statuses = self.budget_statuses(&s.providers);
+        self.notify_budget_alerts(&mut s);
+    }
+
+    /// Budgets from settings evaluated against the providers' cost data
+    fn budget_statuses(&self, providers: &[ProviderData]) -> Vec<BudgetStatus> {
+        if self.settings.budgets.is_empty() {
+            return Vec::new();
+        }
+
+        let mut spend = BudgetSpend::default();
+        for provider in providers {
+            if !provider.cost_history.is_empty() {
+                spend.add_daily(&provider.name, provider.cost_history.clone());
+            }
+            if let Some(ref snapshot) = provider.cost_snapshot {
+                spend.add_snapshot(&provider.name, snapshot.clone());
+            }
+        }
+        spend.evaluate(&self.settings.budgets, chrono::Utc::now().date_naive())
+    }
+
+    /// Notify once per budget, period and alert percentage
+    fn notify_budget_alerts(&self, s: &mut SharedState) {
+        let SharedState {
+            budget_statuses,
+            notifications,
+            ..
+        } = s;
+
+        for status in budget_statuses.iter() {
+            let Some(threshold) = status.crossed_threshold(&self.settings.budget_alert_percents) else {
+                continue;
+            };
+            let key = format!("budget|{}|{}|{}", status.budget.label(), status.period_start, threshold);
+            let provider = status.budget.provider.as_deref().and_then(ProviderId::from_cli_name);
+            let notif_type = if status.is_over() {
+                NotificationType::BudgetExceeded
+            } else {
+                NotificationType::BudgetAlert
+            };
+            let body = format!(
+                "{} budget at {:.0}%: ${:.2} of ${:.2}",
+                status.budget.label(),
+                status.percent,
+                status.spent_usd,
+                status.budget.limit_usd
+            );
+            notifications.notify_once(&key, provider, notif_type, &body, &self.settings);
+        }
+    }
+
+    /// Budget progress window
+    fn show_budget_window(&mut self, ctx: &egui::Context, statuses: &[BudgetStatus]) {
+        if !self.budget_window_open {
+            return;
+        }
+
+        let mut open = true;
+        let mut edit_requested = false;
+        egui::Window::new("Budgets")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                if statuses.is_empty() {
+                    ui.label(
+                        RichText::new("No budgets set.")
+                            .size(FontSize::SM)
+                            .color(Theme::TEXT_MUTED),
+                    );
+                }
+
+                for status in statuses {
+                    let color = if status.is_over() {
+                        Theme::RED
+                    } else if status.percent >= 80.0 {
+                        Theme::ORANGE
+                    } else {
+                        Theme::GREEN
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new(status.budget.label())
+                                .size(FontSize::SM)
+                                .color(Theme::TEXT_PRIMARY),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(
+                                RichText::new(format!(
+                                    "${:.2} / ${:.2}",
+                                    status.spent_usd, status.budget.limit_usd
+                                ))
+                                .size(FontSize::XS)
+                                .color(color),
+                            );
+                        });
+                    });
+                    ui.add(
+                        egui::ProgressBar::new((status.percent / 100.0).clamp(0.0, 1.0) as f32)
+                            .fill(color)
+                            .desired_height(6.0),
+                    );
+                    ui.add_space(Spacing::SM);
+                }
+
+                if ui.button("Edit budgets...").clicked() {
+                    edit_requested = true;
+                }
+            });
+
+        self.budget_window_open = open && !edit_requested;
+        if edit_requested {
+            self.preferences_window.active_tab = super::preferences::PreferencesTab::Budgets;
+            self.preferences_window.open();
         }
     }
 
@@ -577,12 +801,33 @@ impl CodexBarApp {
         self.anchor_main_window_to_pointer = false;
     }
 
+    /// When a provider's alert snooze ends, if it is snoozed
+    fn alerts_snoozed_until(&self, provider_name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        let id = ProviderId::from_cli_name(provider_name)?;
+        self.state.lock().ok()?.notifications.snoozed_until(id)
+    }
+
+    fn apply_snooze(&self, provider_name: &str, request: SnoozeRequest) {
+        let (Some(id), Ok(mut state)) = (ProviderId::from_cli_name(provider_name), self.state.lock()) else {
+            return;
+        };
+        match request {
+            SnoozeRequest::Until(until) => state.notifications.snooze(id, until),
+            SnoozeRequest::Clear => state.notifications.unsnooze(id),
+        }
+    }
+
     fn refresh_providers(&self) {
         let state = Arc::clone(&self.state);
         let enabled_ids = self.settings.get_enabled_provider_ids();
         let manual_cookies = ManualCookies::load();
         let api_keys = ApiKeys::load();
         let reset_time_relative = self.settings.reset_time_relative;
+        let project_aliases = self.settings.project_aliases.clone();
+        let log_roots = self.settings.log_roots.clone();
+        let discover_wsl_logs = self.settings.discover_wsl_logs;
+        let cost_anomaly_notifications = self.settings.cost_anomaly_notifications;
+        let notification_settings = Arc::new(self.settings.clone());
         // Load token accounts for account switching support
         let token_accounts = TokenAccountStore::new().load().unwrap_or_default();
 
@@ -600,6 +845,9 @@ impl CodexBarApp {
                 }
             }
 
+            // Looking for WSL homes touches the network redirector, so do it here
+            let roots = LogRoots::discover(&log_roots, discover_wsl_logs);
+
             let rt = match tokio::runtime::Runtime::new() {
                 Ok(rt) => rt,
                 Err(e) => {
@@ -622,6 +870,9 @@ impl CodexBarApp {
                     std::env::remove_var(key);
                 }
 
+                // Saved after the refresh so `codexbar check --cached` can read it
+                let snapshot_entries: Arc<Mutex<Vec<WidgetProviderEntry>>> = Arc::new(Mutex::new(Vec::new()));
+
                 let handles: Vec<_> = enabled_ids
                     .iter()
                     .enumerate()
@@ -675,6 +926,10 @@ impl CodexBarApp {
                             ..FetchContext::default()
                         };
                         let state = Arc::clone(&state);
+                        let snapshot_entries = Arc::clone(&snapshot_entries);
+                        let notification_settings = Arc::clone(&notification_settings);
+                        let project_aliases = project_aliases.clone();
+                        let roots = roots.clone();
                         tokio::spawn(async move {
                             let provider = create_provider(id);
                             let metadata = provider.metadata().clone();
@@ -695,29 +950,97 @@ impl CodexBarApp {
                                 }
                             );
 
-                            let mut result = match usage_result {
-                                Ok(Ok(result)) => ProviderData::from_result(id, &result, &metadata, reset_time_relative),
-                                Ok(Err(e)) => ProviderData::from_error(id, e.to_string()),
-                                Err(_) => ProviderData::from_error(id, "Timeout".to_string()),
+                            // Stamped with the refresh, not the data's own time, for `check --max-age`
+                            let refreshed_at = chrono::Utc::now();
+                            let mut entry = None;
+                            let (mut result, mut observation) = match usage_result {
+                                Ok(Ok(result)) => {
+                                    entry = Some(widget_entry(id, &result, refreshed_at));
+                                    (
+                                        ProviderData::from_result(id, &result, &metadata, reset_time_relative),
+                                        ProviderObservation::from_usage(id, &result.usage),
+                                    )
+                                }
+                                Ok(Err(e)) => (
+                                    ProviderData::from_error(id, e.to_string()),
+                                    ProviderObservation::from_error(id, e.to_string()),
+                                ),
+                                Err(_) => (
+                                    ProviderData::from_error(id, "Timeout".to_string()),
+                                    ProviderObservation::from_error(id, "Timeout"),
+                                ),
                             };
 
                             if let Ok(Some(status)) = status_result {
+                                observation.status = Some(status.level);
+                                // The status is worth caching even when usage couldn't be read
+                                entry = Some(
+                                    entry
+                                        .unwrap_or_else(|| WidgetProviderEntry::new(id, refreshed_at))
+                                        .with_status(status.level.indicator()),
+                                );
                                 result.status_level = status.level;
                                 result.status_description = Some(status.description);
                             }
 
+                            if let (Some(entry), Ok(mut entries)) = (entry, snapshot_entries.lock()) {
+                                entries.push(entry);
+                            }
+
                             if result.error.is_none() {
                                 result.usage_breakdown = load_usage_breakdown_points(id, result.account.as_deref());
                             }
 
                             let provider_name_lower = provider_name.to_lowercase();
-                            if provider_name_lower == "codex" || provider_name_lower == "claude" {
-                                result.cost_history = get_daily_cost_history(&provider_name_lower, 30);
+                            if matches!(provider_name_lower.as_str(), "codex" | "claude" | "gemini" | "opencode") {
+                                let summary = scan_provider_costs(&provider_name_lower, 30, project_aliases.clone(), roots.clone());
+                                result.cost_history = daily_cost_history(&summary, 30);
+                                result.cost_by_project = top_project_costs(&summary, 5);
+                                if summary.cached_tokens > 0 {
+                                    result.cache_summary = summary
+                                        .cache_hit_ratio()
+                                        .map(|ratio| (ratio, summary.cache.savings_usd));
+                                    result.cache_history = daily_cache_history(&summary, 30)
+                                        .into_iter()
+                                        .map(|(date, hit_ratio, savings_usd)| CacheChartPoint { date, hit_ratio, savings_usd })
+                                        .collect();
+                                }
+                                if !summary.heatmap.is_empty() {
+                                    result.usage_heatmap = Some(summary.heatmap.clone());
+                                }
+                                let mut sessions = summary.sessions;
+                                sessions.sort_by(|a, b| b.cost_usd.partial_cmp(&a.cost_usd).unwrap_or(std::cmp::Ordering::Equal));
+                                sessions.truncate(5);
+                                result.top_sessions = sessions;
                             }
 
+                            // Today's cost far above the trailing baseline
+                            result.update_cost_insights();
+                            let anomaly = result
+                                .cost_anomalies
+                                .last()
+                                .filter(|a| result.cost_history.last().is_some_and(|(date, _)| *date == a.date))
+                                .cloned();
+
                             if let Ok(mut s) = state.lock() {
+                                if let Some(anomaly) = anomaly.filter(|_| cost_anomaly_notifications) {
+                                    let key = format!("anomaly|{}|{}", provider_name_lower, anomaly.date);
+                                    let body = format!(
+                                        "{} cost today is ${:.2}, usually about ${:.2} a day",
+                                        metadata.display_name, anomaly.cost_usd, anomaly.baseline_usd
+                                    );
+                                    s.notifications.notify_once(
+                                        &key,
+                                        Some(id),
+                                        NotificationType::CostAnomaly,
+                                        &body,
+                                        &notification_settings,
+                                    );
+                                }
+                                s.notifications.observe(&observation, &notification_settings);
                                 if idx < s.providers.len() {
                                     s.providers[idx] = result;
+                                    s.budgets_stale = true;
                                 }
                             }
                         })
@@ -727,6 +1050,13 @@ impl CodexBarApp {
                 for handle in handles {
                     let _ = handle.await;
                 }
+
+                let mut entries = snapshot_entries.lock().map(|e| e.clone()).unwrap_or_default();
+                entries.sort_by_key(|e| enabled_ids.iter().position(|id| *id == e.provider));
+                let snapshot = WidgetSnapshot::new(entries, chrono::Utc::now()).with_enabled_providers(enabled_ids.clone());
+                if let Err(e) = WidgetSnapshotStore::save(&snapshot) {
+                    tracing::warn!("Failed to save widget snapshot: {}", e);
+                }
             });
 
             if let Ok(mut s) = state.lock() {
@@ -789,6 +1119,17 @@ fn work_area_rect(ctx: &egui::Context) -> Option<Rect> {
     })
 }
 
+/// Snapshot entry for a successful fetch
+fn widget_entry(id: ProviderId, result: &ProviderFetchResult, refreshed_at: chrono::DateTime<chrono::Utc>) -> WidgetProviderEntry {
+    let usage = &result.usage;
+    let mut entry = WidgetProviderEntry::new(id, refreshed_at).with_primary(usage.primary.clone());
+    entry.secondary = usage.secondary.clone();
+    entry.tertiary = usage.model_specific.clone();
+    entry.account_email = usage.account_email.clone();
+    entry.login_method = usage.login_method.clone();
+    entry
+}
+
 fn create_provider(id: ProviderId) -> Box<dyn Provider> {
     match id {
         ProviderId::Claude => Box::new(ClaudeProvider::new()),
@@ -847,6 +1188,11 @@ impl eframe::App for CodexBarApp {
             self.refresh_providers();
         }
 
+        // Reset reminders and notices follow the clock, not the refresh
+        if let Ok(mut state) = self.state.lock() {
+            state.notifications.tick(&self.settings);
+        }
+
         // Get state
         let (providers, selected_idx, is_refreshing, loading_pattern, loading_phase, surprise_state, update_info, update_download_state, login_state) = {
             if let Ok(mut state) = self.state.lock() {
@@ -990,6 +1336,12 @@ impl eframe::App for CodexBarApp {
                         self.layout_main_window(ctx, true);
                         self.preferences_window.open();
                     }
+                    TrayMenuAction::Budgets => {
+                        self.pending_main_window_layout = true;
+                        self.anchor_main_window_to_pointer = true;
+                        self.layout_main_window(ctx, true);
+                        self.budget_window_open = true;
+                    }
                     TrayMenuAction::CheckForUpdates => {
                         // Trigger update check in background
                         let state = Arc::clone(&self.state);
@@ -1357,32 +1709,39 @@ impl eframe::App for CodexBarApp {
                             // ════════════════════════════════════════════════════════════
                             let mut manual_refresh_requested = false;
                             let mut account_switch_provider: Option<String> = None;
+                            let mut snooze_request: Option<(String, SnoozeRequest)> = None;
                             let show_credits = self.settings.show_credits_extra_usage;
                             let show_as_used = self.settings.show_as_used;
                             let hide_personal_info = self.settings.hide_personal_info;
                             if let Some((_, selected_provider)) = visible_providers.iter().find(|(idx, _)| *idx == selected_idx) {
-                                let (refresh, switch) = draw_provider_detail_card(
+                                let snoozed_until = self.alerts_snoozed_until(&selected_provider.name);
+                                let (refresh, switch, snooze) = draw_provider_detail_card(
                                     ui,
                                     selected_provider,
                                     &mut self.icon_cache,
                                     show_credits,
                                     show_as_used,
                                     hide_personal_info,
+                                    snoozed_until,
                                 );
                                 manual_refresh_requested = refresh;
                                 account_switch_provider = switch;
+                                snooze_request = snooze.map(|s| (selected_provider.name.clone(), s));
                             } else if let Some((_, first_provider)) = visible_providers.first() {
                                 // Fallback to first if selected isn't visible
-                                let (refresh, switch) = draw_provider_detail_card(
+                                let snoozed_until = self.alerts_snoozed_until(&first_provider.name);
+                                let (refresh, switch, snooze) = draw_provider_detail_card(
                                     ui,
                                     first_provider,
                                     &mut self.icon_cache,
                                     show_credits,
                                     show_as_used,
                                     hide_personal_info,
+                                    snoozed_until,
                                 );
                                 manual_refresh_requested = refresh;
                                 account_switch_provider = switch;
+                                snooze_request = snooze.map(|s| (first_provider.name.clone(), s));
                             }
 
                             // Trigger manual refresh if requested
@@ -1390,6 +1749,10 @@ impl eframe::App for CodexBarApp {
                                 self.refresh_providers();
                             }
 
+                            if let Some((provider_name, request)) = snooze_request {
+                                self.apply_snooze(&provider_name, request);
+                            }
+
                             // Handle account switch request - open preferences to Providers tab with provider selected
                             if let Some(provider_name) = account_switch_provider {
                                 if let Some(provider_id) = ProviderId::from_cli_name(&provider_name) {
@@ -1472,6 +1835,9 @@ impl eframe::App for CodexBarApp {
                     draw_horizontal_separator(ui, 0.0);
                     ui.add_space(4.0);
 
+                    if draw_text_menu_item(ui, "Budgets...") {
+                        self.budget_window_open = true;
+                    }
                     if draw_text_menu_item(ui, "Settings...") {
                         self.preferences_window.open();
                     }
@@ -1485,6 +1851,12 @@ impl eframe::App for CodexBarApp {
                 }); // end ScrollArea
             });
 
+        self.refresh_budget_statuses();
+        if self.budget_window_open {
+            let budget_statuses = self.state.lock().map(|s| s.budget_statuses.clone()).unwrap_or_default();
+            self.show_budget_window(ctx, &budget_statuses);
+        }
+
         // Show preferences window
         self.preferences_window.show(ctx);
 
@@ -1500,6 +1872,9 @@ impl eframe::App for CodexBarApp {
             if previous_enabled_provider_ids != self.settings.get_enabled_provider_ids() {
                 refresh_requested = true;
             }
+            if let Ok(mut s) = self.state.lock() {
+                s.budgets_stale = true;
+            }
             self.preferences_window.settings_changed = false;
         }
 
@@ -1520,9 +1895,16 @@ impl eframe::App for CodexBarApp {
     }
 }
 
+/// Snooze change picked in a provider's detail card
+enum SnoozeRequest {
+    Until(chrono::DateTime<chrono::Utc>),
+    Clear,
+}
+
 /// Draw a provider detail card - macOS UsageMenuCardView style
 /// Structure: Header -> Divider -> Metrics (Session, Weekly, Model) -> Credits -> Cost
-/// Returns (refresh_requested, account_switch_provider_name)
+/// Returns (refresh_requested, account_switch_provider_name, snooze_request)
+#[allow(clippy::too_many_arguments)]
 fn draw_provider_detail_card(
     ui: &mut egui::Ui,
     provider: &ProviderData,
@@ -1530,9 +1912,11 @@ fn draw_provider_detail_card(
     show_credits_extra: bool,
     show_as_used: bool,
     hide_personal_info: bool,
-) -> (bool, Option<String>) {
+    snoozed_until: Option<chrono::DateTime<chrono::Utc>>,
+) -> (bool, Option<String>, Option<SnoozeRequest>) {
     let mut refresh_requested = false;
     let mut account_switch_requested: Option<String> = None;
+    let mut snooze_request: Option<SnoozeRequest> = None;
     let brand_color = provider_color(&provider.name);
     let content_width = ui.available_width() - 32.0; // 16px padding each side
 
@@ -1836,6 +2220,16 @@ fn draw_provider_detail_card(
                         .size(FontSize::XS)
                         .color(Theme::TEXT_PRIMARY)
                 );
+                if let Some(ref forecast) = provider.cost_forecast {
+                    ui.label(
+                        RichText::new(format!(
+                            "This month: ${:.2} · on track for ${:.2}",
+                            forecast.month_to_date_usd, forecast.projected_usd
+                        ))
+                        .size(FontSize::XS)
+                        .color(Theme::TEXT_SECONDARY)
+                    );
+                }
             } else if let Some(cost_used) = &provider.cost_used {
                 ui.label(
                     RichText::new(cost_used)
@@ -1851,9 +2245,98 @@ fn draw_provider_detail_card(
                     .iter()
                     .map(|(date, cost)| ChartPoint::new(date.clone(), *cost))
                     .collect();
-                let mut chart = CostHistoryChart::new(chart_points, brand_color);
+                let anomalies: Vec<String> = provider.cost_anomalies.iter().map(|a| a.date.clone()).collect();
+                let mut chart = CostHistoryChart::new(chart_points, brand_color).with_anomalies(anomalies);
                 chart.show(ui);
             }
+
+            // Prompt cache efficiency
+            if let Some((ratio, savings)) = provider.cache_summary {
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new(format!(
+                        "Cache: {:.0}% hits · {} ${:.2} vs uncached",
+                        ratio * 100.0,
+                        if savings >= 0.0 { "saved" } else { "lost" },
+                        savings.abs()
+                    ))
+                    .size(FontSize::XS)
+                    .color(Theme::TEXT_SECONDARY)
+                );
+                if !provider.cache_history.is_empty() {
+                    ui.add_space(4.0);
+                    let mut chart = CacheHitChart::new(provider.cache_history.clone(), brand_color);
+                    chart.show(ui);
+                }
+            }
+
+            // When usage happens: day of week × hour of day
+            if let Some(heatmap) = &provider.usage_heatmap {
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new("Usage by hour (30 days)")
+                        .size(FontSize::XS)
+                        .color(Theme::TEXT_SECONDARY)
+                );
+                ui.add_space(4.0);
+                UsageHeatmapChart::new(heatmap.clone(), brand_color).show(ui);
+            }
+
+            // Top projects by cost (git root or alias)
+            if !provider.cost_by_project.is_empty() {
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new("Top projects (30 days)")
+                        .size(FontSize::XS)
+                        .color(Theme::TEXT_SECONDARY)
+                );
+                for (project, cost) in &provider.cost_by_project {
+                    let name = project.rsplit(['/', '\\']).next().filter(|n| !n.is_empty()).unwrap_or(project);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new(name)
+                                .size(FontSize::XS)
+                                .color(Theme::TEXT_PRIMARY)
+                        )
+                        .on_hover_text(project);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(
+                                RichText::new(format!("${:.2}", cost))
+                                    .size(FontSize::XS)
+                                    .color(Theme::TEXT_PRIMARY)
+                            );
+                        });
+                    });
+                }
+            }
+
+            // Most expensive sessions - click to show the log file
+            if !provider.top_sessions.is_empty() {
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new("Top sessions (30 days)")
+                        .size(FontSize::XS)
+                        .color(Theme::TEXT_SECONDARY)
+                );
+                for session in &provider.top_sessions {
+                    let started = session
+                        .started_at
+                        .map(|t| t.with_timezone(&chrono::Local).format("%b %d %H:%M").to_string())
+                        .unwrap_or_default();
+                    let project = session
+                        .project
+                        .as_deref()
+                        .and_then(|p| p.rsplit(['/', '\\']).next())
+                        .unwrap_or("unknown project");
+                    let label = format!("{}  {}", started, project);
+
+                    let response = draw_session_row(ui, &label, &format!("${:.2}", session.cost_usd))
+                        .on_hover_text(session.path.display().to_string());
+                    if response.clicked() {
+                        reveal_in_file_manager(&session.path);
+                    }
+                }
+            }
         }
 
         // ═══════════════════════════════════════════════════════════════════
@@ -1912,7 +2395,28 @@ fn draw_provider_detail_card(
             ui.add_space(4.0);
         }
 
-        (refresh_requested, account_switch_requested)
+        // Snooze alerts for this provider
+        if let Some(until) = snoozed_until {
+            let label = format!("Alerts Snoozed Until {} (Resume)", until.with_timezone(&chrono::Local).format("%a %H:%M"));
+            if draw_menu_item(ui, "🔕", &label) {
+                snooze_request = Some(SnoozeRequest::Clear);
+            }
+        } else {
+            if draw_menu_item(ui, "🔕", "Snooze Alerts for 1 Hour") {
+                snooze_request = Some(SnoozeRequest::Until(chrono::Utc::now() + chrono::Duration::hours(1)));
+            }
+            if draw_menu_item(ui, "🔕", "Snooze Alerts Until Tomorrow") {
+                let tomorrow = chrono::Local::now().date_naive() + chrono::Days::new(1);
+                let until = tomorrow
+                    .and_hms_opt(0, 0, 0)
+                    .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::hours(24));
+                snooze_request = Some(SnoozeRequest::Until(until));
+            }
+        }
+
+        (refresh_requested, account_switch_requested, snooze_request)
     }).inner
 }
 
@@ -2106,6 +2610,53 @@ fn draw_menu_item(ui: &mut egui::Ui, icon: &str, label: &str) -> bool {
     response.clicked()
 }
 
+/// Draw a clickable session row with the cost right-aligned
+fn draw_session_row(ui: &mut egui::Ui, label: &str, cost: &str) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width(), 20.0),
+        egui::Sense::click(),
+    );
+
+    if response.hovered() {
+        ui.painter().rect_filled(rect, Rounding::same(Radius::SM), Theme::menu_hover());
+    }
+
+    let font = egui::FontId::proportional(FontSize::XS);
+    ui.painter().text(
+        egui::pos2(rect.min.x + 4.0, rect.center().y),
+        egui::Align2::LEFT_CENTER,
+        label,
+        font.clone(),
+        Theme::TEXT_PRIMARY,
+    );
+    ui.painter().text(
+        egui::pos2(rect.max.x - 4.0, rect.center().y),
+        egui::Align2::RIGHT_CENTER,
+        cost,
+        font,
+        Theme::TEXT_PRIMARY,
+    );
+
+    response.on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
+/// Show a file in Explorer (or open its folder elsewhere)
+fn reveal_in_file_manager(path: &std::path::Path) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = std::process::Command::new("explorer")
+            .arg(format!("/select,{}", path.display()))
+            .spawn();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(parent) = path.parent() {
+            let _ = open::that(parent);
+        }
+    }
+}
+
 /// Run the application
 pub fn run() -> anyhow::Result<()> {
     // Delete any corrupted window state
@@ -1,11 +1,14 @@
 //! Charts module for cost and credits history visualization
 //!
-//! Provides bar charts similar to the macOS SwiftUI Charts
+//! Provides bar charts similar to the macOS SwiftUI Charts, plus an
+//! hour-of-day × day-of-week usage heatmap and a daily cache hit chart
 
 #![allow(dead_code)]
 
 use eframe::egui::{self, Color32, RichText, Rounding, Vec2};
 
+use crate::cost_scanner::{UsageHeatmap, WEEKDAYS};
+
 /// Model cost breakdown for a single day
 #[derive(Clone, Debug)]
 pub struct ModelBreakdown {
@@ -54,6 +57,8 @@ pub struct CostHistoryChart {
     total_cost: Option<f64>,
     animation_start: Option<std::time::Instant>,
     is_animated: bool,
+    /// Dates with unusually high spend, drawn in a warning color
+    anomalies: Vec<String>,
 }
 
 impl CostHistoryChart {
@@ -71,9 +76,16 @@ impl CostHistoryChart {
             total_cost,
             animation_start: None,
             is_animated: false,
+            anomalies: Vec::new(),
         }
     }
 
+    /// Highlight days with unusually high spend
+    pub fn with_anomalies(mut self, dates: Vec<String>) -> Self {
+        self.anomalies = dates;
+        self
+    }
+
     /// Start the entrance animation
     pub fn animate_entrance(&mut self) {
         self.animation_start = Some(std::time::Instant::now());
@@ -186,6 +198,12 @@ impl CostHistoryChart {
                 self.selected_index = Some(i);
             }
 
+            let base_color = if self.anomalies.contains(&point.date) {
+                Color32::from_rgb(230, 90, 70)
+            } else {
+                self.bar_color
+            };
+
             // Bar color - peak gets yellow cap
             let color = if Some(i) == peak_index && bar_height > 5.0 {
                 // Draw main bar
@@ -193,7 +211,7 @@ impl CostHistoryChart {
                     egui::pos2(x, rect.bottom() - bar_height + 5.0),
                     Vec2::new(bar_width, bar_height - 5.0),
                 );
-                painter.rect_filled(main_rect, Rounding::same(2.0), self.bar_color);
+                painter.rect_filled(main_rect, Rounding::same(2.0), base_color);
 
                 // Draw yellow peak cap
                 let cap_rect = egui::Rect::from_min_size(
@@ -203,9 +221,9 @@ impl CostHistoryChart {
                 painter.rect_filled(cap_rect, Rounding::same(2.0), Color32::from_rgb(255, 200, 50));
                 continue;
             } else if is_hovered {
-                self.bar_color.gamma_multiply(1.2)
+                base_color.gamma_multiply(1.2)
             } else {
-                self.bar_color
+                base_color
             };
 
             painter.rect_filled(bar_rect, Rounding::same(2.0), color);
@@ -239,11 +257,14 @@ impl CostHistoryChart {
                 let date_display = format_date_display(&point.date);
                 let cost_display = format!("${:.2}", point.value);
 
-                let detail = if let Some(tokens) = point.tokens {
+                let mut detail = if let Some(tokens) = point.tokens {
                     format!("{}: {} · {} tokens", date_display, cost_display, format_tokens(tokens))
                 } else {
                     format!("{}: {}", date_display, cost_display)
                 };
+                if self.anomalies.contains(&point.date) {
+                    detail.push_str(" · unusually high");
+                }
 
                 ui.label(
                     RichText::new(detail)
@@ -642,6 +663,208 @@ impl UsageBreakdownChart {
     }
 }
 
+/// Cache efficiency for one day
+#[derive(Clone, Debug)]
+pub struct CacheChartPoint {
+    pub date: String,             // "2025-01-15" format
+    pub hit_ratio: Option<f64>,   // 0.0-1.0, None without input
+    pub savings_usd: f64,         // Savings vs. uncached input
+}
+
+/// Daily cache hit ratio chart widget
+pub struct CacheHitChart {
+    points: Vec<CacheChartPoint>,
+    bar_color: Color32,
+    selected_index: Option<usize>,
+}
+
+impl CacheHitChart {
+    pub fn new(points: Vec<CacheChartPoint>, bar_color: Color32) -> Self {
+        Self {
+            points,
+            bar_color,
+            selected_index: None,
+        }
+    }
+
+    /// Render the chart
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if self.points.iter().all(|p| p.hit_ratio.is_none()) {
+            ui.label(
+                RichText::new("No cache data yet")
+                    .size(11.0)
+                    .color(Color32::GRAY),
+            );
+            return;
+        }
+
+        let chart_height = 24.0;
+        let available_width = ui.available_width();
+        let slot_width = available_width / self.points.len() as f32;
+        let bar_width = slot_width * 0.8;
+
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(available_width, chart_height),
+            egui::Sense::hover(),
+        );
+        let rect = response.rect;
+
+        self.selected_index = response.hover_pos().and_then(|pos| {
+            let idx = ((pos.x - rect.left()) / slot_width).floor();
+            (idx >= 0.0 && (idx as usize) < self.points.len()).then_some(idx as usize)
+        });
+
+        // Full-height track so 100% is visible as a reference
+        for (i, point) in self.points.iter().enumerate() {
+            let x = rect.left() + i as f32 * slot_width + (slot_width - bar_width) / 2.0;
+            let track = egui::Rect::from_min_size(egui::pos2(x, rect.top()), Vec2::new(bar_width, chart_height));
+            painter.rect_filled(track, Rounding::same(2.0), Color32::from_rgba_unmultiplied(255, 255, 255, 10));
+
+            if let Some(ratio) = point.hit_ratio {
+                let height = (ratio as f32 * chart_height).max(1.0);
+                let bar = egui::Rect::from_min_size(
+                    egui::pos2(x, rect.bottom() - height),
+                    Vec2::new(bar_width, height),
+                );
+                let color = if self.selected_index == Some(i) {
+                    self.bar_color.gamma_multiply(1.2)
+                } else {
+                    self.bar_color
+                };
+                painter.rect_filled(bar, Rounding::same(2.0), color);
+            }
+        }
+
+        if let Some(point) = self.selected_index.and_then(|i| self.points.get(i)) {
+            let detail = match point.hit_ratio {
+                Some(ratio) => format!(
+                    "{}: {:.0}% cache hits · {} ${:.2}",
+                    format_date_display(&point.date),
+                    ratio * 100.0,
+                    if point.savings_usd >= 0.0 { "saved" } else { "lost" },
+                    point.savings_usd.abs()
+                ),
+                None => format!("{}: no usage", format_date_display(&point.date)),
+            };
+            ui.label(RichText::new(detail).size(10.0).color(Color32::GRAY));
+        }
+    }
+}
+
+/// Usage heatmap widget: rows are days of the week (Monday first),
+/// columns are hours of the day in local time
+pub struct UsageHeatmapChart {
+    heatmap: UsageHeatmap,
+    color: Color32,
+    selected: Option<(usize, usize)>,
+}
+
+impl UsageHeatmapChart {
+    pub fn new(heatmap: UsageHeatmap, color: Color32) -> Self {
+        Self {
+            heatmap,
+            color,
+            selected: None,
+        }
+    }
+
+    /// Render the chart
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let Some(peak) = self.heatmap.peak() else {
+            ui.label(
+                RichText::new("No hourly usage yet")
+                    .size(11.0)
+                    .color(Color32::GRAY),
+            );
+            return;
+        };
+        let max_tokens = self.heatmap.cell(peak.0, peak.1).tokens as f32;
+
+        let label_width = 28.0;
+        let cell_height = 9.0;
+        let gap = 1.0;
+        let available_width = ui.available_width();
+        let cell_width = ((available_width - label_width) / 24.0).max(2.0);
+        let grid_height = 7.0 * cell_height;
+        let axis_height = 12.0;
+
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(available_width, grid_height + axis_height),
+            egui::Sense::hover(),
+        );
+        let rect = response.rect;
+        let grid_left = rect.left() + label_width;
+
+        self.selected = response.hover_pos().and_then(|pos| {
+            let col = ((pos.x - grid_left) / cell_width).floor();
+            let row = ((pos.y - rect.top()) / cell_height).floor();
+            if (0.0..24.0).contains(&col) && (0.0..7.0).contains(&row) {
+                Some((row as usize, col as usize))
+            } else {
+                None
+            }
+        });
+
+        for (day, label) in WEEKDAYS.iter().enumerate() {
+            let y = rect.top() + day as f32 * cell_height;
+            painter.text(
+                egui::pos2(rect.left(), y + cell_height / 2.0),
+                egui::Align2::LEFT_CENTER,
+                *label,
+                egui::FontId::proportional(8.0),
+                Color32::GRAY,
+            );
+
+            for hour in 0..24 {
+                let cell = self.heatmap.cell(day, hour);
+                let cell_rect = egui::Rect::from_min_size(
+                    egui::pos2(grid_left + hour as f32 * cell_width, y),
+                    Vec2::new(cell_width - gap, cell_height - gap),
+                );
+
+                let color = if cell.tokens == 0 {
+                    Color32::from_rgba_unmultiplied(255, 255, 255, 12)
+                } else {
+                    // Square root keeps quieter hours visible next to the peak
+                    let intensity = (cell.tokens as f32 / max_tokens).sqrt();
+                    self.color.gamma_multiply(0.15 + 0.85 * intensity)
+                };
+                painter.rect_filled(cell_rect, Rounding::same(1.5), color);
+
+                if self.selected == Some((day, hour)) {
+                    painter.rect_stroke(cell_rect, Rounding::same(1.5), egui::Stroke::new(1.0, Color32::WHITE));
+                }
+            }
+        }
+
+        for hour in [0, 6, 12, 18] {
+            painter.text(
+                egui::pos2(grid_left + hour as f32 * cell_width, rect.top() + grid_height + 2.0),
+                egui::Align2::LEFT_TOP,
+                format!("{:02}", hour),
+                egui::FontId::proportional(8.0),
+                Color32::GRAY,
+            );
+        }
+
+        let (day, hour) = self.selected.unwrap_or(peak);
+        let cell = self.heatmap.cell(day, hour);
+        let prefix = if self.selected.is_some() { "" } else { "Busiest: " };
+        ui.label(
+            RichText::new(format!(
+                "{}{} {:02}:00 · {} tokens · ${:.2}",
+                prefix,
+                WEEKDAYS[day],
+                hour,
+                format_tokens(cell.tokens as i64),
+                cell.cost_usd
+            ))
+            .size(10.0)
+            .color(Color32::GRAY),
+        );
+    }
+}
+
 /// Get color for a service name
 fn color_for_service(service: &str) -> Color32 {
     let lower = service.to_lowercase();
@@ -12,10 +12,13 @@ use eframe::egui::{self, Color32, RichText, Rounding, Stroke, Vec2, Rect};
 use super::provider_icons::ProviderIconCache;
 use super::theme::{provider_color, provider_icon, FontSize, Radius, Spacing, Theme};
 use crate::settings::{ApiKeys, ManualCookies, Settings, TrayIconMode, get_api_key_providers};
-use crate::core::{PersonalInfoRedactor, ProviderId, WidgetSnapshot, WidgetSnapshotStore};
+use crate::core::{Budget, BudgetPeriod, PersonalInfoRedactor, ProviderId, UsageWindow, WidgetSnapshot, WidgetSnapshotStore};
 use crate::core::{TokenAccountStore, TokenAccount, TokenAccountSupport, ProviderAccountData};
 use crate::browser::detection::{BrowserDetector, BrowserType};
 use crate::browser::cookies::get_cookie_header_from_browser;
+use crate::notifications::rules::{NotificationRule, RuleTrigger};
+use crate::notifications::webhooks::{self, WebhookKind, WebhookSink};
+use crate::notifications::NotificationType;
 use crate::shortcuts::format_shortcut;
 use std::collections::HashMap;
 
@@ -31,6 +34,8 @@ pub enum PreferencesTab {
     General,
     Providers,
     Display,
+    Budgets,
+    Alerts,
     ApiKeys,
     Cookies,
     Advanced,
@@ -43,6 +48,8 @@ impl PreferencesTab {
             PreferencesTab::General => "General",
             PreferencesTab::Providers => "Providers",
             PreferencesTab::Display => "Display",
+            PreferencesTab::Budgets => "Budgets",
+            PreferencesTab::Alerts => "Alerts",
             PreferencesTab::ApiKeys => "API Keys",
             PreferencesTab::Cookies => "Cookies",
             PreferencesTab::Advanced => "Advanced",
@@ -55,6 +62,8 @@ impl PreferencesTab {
             PreferencesTab::General => "⚙",
             PreferencesTab::Providers => "☰",
             PreferencesTab::Display => "👁",
+            PreferencesTab::Budgets => "$",
+            PreferencesTab::Alerts => "🔔",
             PreferencesTab::ApiKeys => "🔑",
             PreferencesTab::Cookies => "🍪",
             PreferencesTab::Advanced => "⚡",
@@ -121,6 +130,10 @@ struct PreferencesSharedState {
     // Keyboard shortcut editing
     shortcut_input: String,
     shortcut_status_msg: Option<(String, bool)>,
+    // Budget alert percentages being edited
+    budget_percents_input: String,
+    // Result of the last webhook test: (webhook index, message, is_error)
+    webhook_status_msg: Option<(usize, String, bool)>,
 }
 
 impl Default for PreferencesWindow {
@@ -156,6 +169,8 @@ impl Default for PreferencesWindow {
             token_account_status_msg: None,
             shortcut_input: settings.global_shortcut.clone(),
             shortcut_status_msg: None,
+            budget_percents_input: format_percents(&settings.budget_alert_percents),
+            webhook_status_msg: None,
         }));
 
         Self {
@@ -211,6 +226,7 @@ impl PreferencesWindow {
             state.selected_provider = self.selected_provider;
             state.shortcut_input = self.settings.global_shortcut.clone();
             state.shortcut_status_msg = None;
+            state.budget_percents_input = format_percents(&self.settings.budget_alert_percents);
         }
     }
 
@@ -376,6 +392,14 @@ impl PreferencesWindow {
 
             setting_divider(ui);
 
+            let mut cost_anomalies = self.settings.cost_anomaly_notifications;
+            if setting_toggle(ui, "Unusual spend alerts", "Alert when a day's local cost is far above normal", &mut cost_anomalies) {
+                self.settings.cost_anomaly_notifications = cost_anomalies;
+                self.settings_changed = true;
+            }
+
+            setting_divider(ui);
+
             // Sound effects toggle
             let mut sound_enabled = self.settings.sound_enabled;
             if setting_toggle(ui, "Sound effects", "Play sound when thresholds are reached", &mut sound_enabled) {
@@ -1785,6 +1809,8 @@ fn render_settings_ui(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSha
             PreferencesTab::General,
             PreferencesTab::Providers,
             PreferencesTab::Display,
+            PreferencesTab::Budgets,
+            PreferencesTab::Alerts,
             PreferencesTab::ApiKeys,
             PreferencesTab::Cookies,
             PreferencesTab::Advanced,
@@ -1883,6 +1909,8 @@ fn render_settings_ui(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSha
                         match active_tab {
                             PreferencesTab::General => render_general_tab(ui, shared_state),
                             PreferencesTab::Display => render_display_tab(ui, shared_state),
+                            PreferencesTab::Budgets => render_budgets_tab(ui, shared_state),
+                            PreferencesTab::Alerts => render_alerts_tab(ui, shared_state),
                             PreferencesTab::ApiKeys => render_api_keys_tab(ui, shared_state),
                             PreferencesTab::Cookies => render_cookies_tab(ui, shared_state),
                             PreferencesTab::Advanced => render_advanced_tab(ui, shared_state),
@@ -2893,6 +2921,19 @@ fn render_general_tab(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSha
 
         setting_divider(ui);
 
+        let mut cost_anomalies = if let Ok(state) = shared_state.lock() {
+            state.settings.cost_anomaly_notifications
+        } else { false };
+
+        if setting_toggle(ui, "Unusual spend alerts", "Alert when a day's local cost is far above normal", &mut cost_anomalies) {
+            if let Ok(mut state) = shared_state.lock() {
+                state.settings.cost_anomaly_notifications = cost_anomalies;
+                state.settings_changed = true;
+            }
+        }
+
+        setting_divider(ui);
+
         // Sound effects toggle
         let mut sound_enabled = if let Ok(state) = shared_state.lock() {
             state.settings.sound_enabled
@@ -3213,6 +3254,522 @@ fn render_display_tab(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSha
     });
 }
 
+/// Render Budgets tab for viewport
+fn render_budgets_tab(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSharedState>>) {
+    section_header(ui, "Budgets");
+
+    let mut budgets = if let Ok(state) = shared_state.lock() {
+        state.settings.budgets.clone()
+    } else { Vec::new() };
+    let mut changed = false;
+    let mut remove: Option<usize> = None;
+
+    settings_card(ui, |ui| {
+        if budgets.is_empty() {
+            ui.label(
+                RichText::new("No budgets yet. Codex and Claude spend comes from local logs; other providers use the cost they report.")
+                    .size(FontSize::SM)
+                    .color(Theme::TEXT_MUTED)
+            );
+        }
+
+        for (i, budget) in budgets.iter_mut().enumerate() {
+            if i > 0 {
+                setting_divider(ui);
+            }
+
+            ui.horizontal(|ui| {
+                let scope = budget
+                    .provider
+                    .as_deref()
+                    .and_then(ProviderId::from_cli_name)
+                    .map(|id| id.display_name())
+                    .unwrap_or("All providers");
+                egui::ComboBox::from_id_salt(("budget_scope", i))
+                    .selected_text(scope)
+                    .width(120.0)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(budget.provider.is_none(), "All providers").clicked() {
+                            budget.provider = None;
+                            changed = true;
+                        }
+                        for id in ProviderId::all() {
+                            let selected = budget.provider.as_deref() == Some(id.cli_name());
+                            if ui.selectable_label(selected, id.display_name()).clicked() {
+                                budget.provider = Some(id.cli_name().to_string());
+                                changed = true;
+                            }
+                        }
+                    });
+
+                egui::ComboBox::from_id_salt(("budget_period", i))
+                    .selected_text(period_label(budget.period))
+                    .width(80.0)
+                    .show_ui(ui, |ui| {
+                        for period in [BudgetPeriod::Daily, BudgetPeriod::Weekly, BudgetPeriod::Monthly] {
+                            if ui.selectable_label(budget.period == period, period_label(period)).clicked() {
+                                budget.period = period;
+                                changed = true;
+                            }
+                        }
+                    });
+
+                ui.label(RichText::new("$").size(FontSize::MD).color(Theme::TEXT_SECONDARY));
+                if ui
+                    .add(egui::DragValue::new(&mut budget.limit_usd).range(0.0..=100_000.0).speed(1.0).max_decimals(2))
+                    .changed()
+                {
+                    changed = true;
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if small_button(ui, "Remove", Theme::RED) {
+                        remove = Some(i);
+                    }
+                });
+            });
+        }
+
+        if !budgets.is_empty() {
+            setting_divider(ui);
+        }
+        if small_button(ui, "Add budget", Theme::ACCENT_PRIMARY) {
+            budgets.push(Budget {
+                provider: None,
+                period: BudgetPeriod::Monthly,
+                limit_usd: 100.0,
+            });
+            changed = true;
+        }
+    });
+
+    if let Some(i) = remove {
+        budgets.remove(i);
+        changed = true;
+    }
+    if changed {
+        if let Ok(mut state) = shared_state.lock() {
+            state.settings.budgets = budgets;
+            state.settings_changed = true;
+        }
+    }
+
+    ui.add_space(Spacing::LG);
+
+    section_header(ui, "Alerts");
+
+    settings_card(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label(RichText::new("Notify at").size(FontSize::MD).color(Theme::TEXT_PRIMARY));
+                ui.label(RichText::new("Percentages of a budget, comma separated").size(FontSize::SM).color(Theme::TEXT_MUTED));
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let mut input = if let Ok(state) = shared_state.lock() {
+                    state.budget_percents_input.clone()
+                } else { String::new() };
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut input)
+                        .desired_width(100.0)
+                        .hint_text("80, 100")
+                );
+                if response.changed() {
+                    if let Ok(mut state) = shared_state.lock() {
+                        state.budget_percents_input = input.clone();
+                    }
+                }
+                if response.lost_focus() {
+                    let parsed: Result<Vec<f64>, _> = input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(|p| p.trim_end_matches('%').parse::<f64>())
+                        .collect();
+                    if let Ok(mut state) = shared_state.lock() {
+                        if let Ok(mut percents) = parsed {
+                            percents.retain(|p| *p > 0.0);
+                            percents.sort_by(|a, b| a.total_cmp(b));
+                            percents.dedup();
+                            state.budget_percents_input = format_percents(&percents);
+                            state.settings.budget_alert_percents = percents;
+                            state.settings_changed = true;
+                        } else {
+                            state.budget_percents_input = format_percents(&state.settings.budget_alert_percents);
+                        }
+                    }
+                }
+            });
+        });
+    });
+}
+
+fn period_label(period: BudgetPeriod) -> &'static str {
+    match period {
+        BudgetPeriod::Daily => "Daily",
+        BudgetPeriod::Weekly => "Weekly",
+        BudgetPeriod::Monthly => "Monthly",
+    }
+}
+
+/// "80, 100"
+fn format_percents(percents: &[f64]) -> String {
+    percents
+        .iter()
+        .map(|p| format!("{}", p))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Render Alerts tab for viewport
+fn render_alerts_tab(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSharedState>>) {
+    render_alert_rules(ui, shared_state);
+
+    ui.add_space(Spacing::LG);
+
+    section_header(ui, "Quiet Hours");
+
+    let mut quiet = if let Ok(state) = shared_state.lock() {
+        state.settings.quiet_hours.clone()
+    } else { return };
+    let mut quiet_changed = false;
+
+    settings_card(ui, |ui| {
+        quiet_changed |= setting_toggle(ui, "Hold back alerts", "Alerts still due afterwards are sent when quiet hours end", &mut quiet.enabled);
+        if quiet.enabled {
+            setting_divider(ui);
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("From").size(FontSize::MD).color(Theme::TEXT_SECONDARY));
+                quiet_changed |= time_slot_combo(ui, "quiet_start", &mut quiet.start);
+                ui.label(RichText::new("to").size(FontSize::MD).color(Theme::TEXT_SECONDARY));
+                quiet_changed |= time_slot_combo(ui, "quiet_end", &mut quiet.end);
+            });
+        }
+    });
+
+    if quiet_changed {
+        if let Ok(mut state) = shared_state.lock() {
+            state.settings.quiet_hours = quiet;
+            state.settings_changed = true;
+        }
+    }
+
+    ui.add_space(Spacing::LG);
+
+    section_header(ui, "Resets");
+
+    let mut notify_resets = if let Ok(state) = shared_state.lock() {
+        state.settings.notify_window_resets
+    } else { return };
+    let mut resets_changed = false;
+
+    settings_card(ui, |ui| {
+        resets_changed |= setting_toggle(ui, "Weekly and model resets", "Notify when a weekly or model window starts over", &mut notify_resets);
+
+        setting_divider(ui);
+
+        ui.label(
+            RichText::new("For a reminder before a reset, add a \"Resets within\" rule above.")
+                .size(FontSize::SM)
+                .color(Theme::TEXT_MUTED),
+        );
+    });
+
+    if resets_changed {
+        if let Ok(mut state) = shared_state.lock() {
+            state.settings.notify_window_resets = notify_resets;
+            state.settings_changed = true;
+        }
+    }
+
+    ui.add_space(Spacing::LG);
+
+    section_header(ui, "Webhooks");
+
+    let (mut sinks, status_msg) = if let Ok(state) = shared_state.lock() {
+        (state.settings.webhooks.clone(), state.webhook_status_msg.clone())
+    } else { (Vec::new(), None) };
+    let mut changed = false;
+    let mut remove: Option<usize> = None;
+    let mut test: Option<usize> = None;
+
+    settings_card(ui, |ui| {
+        ui.label(
+            RichText::new("Every alert is also posted here, even with toasts turned off.")
+                .size(FontSize::SM)
+                .color(Theme::TEXT_MUTED)
+        );
+
+        for (i, sink) in sinks.iter_mut().enumerate() {
+            setting_divider(ui);
+
+            ui.horizontal(|ui| {
+                changed |= ui.checkbox(&mut sink.enabled, "").changed();
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut sink.name).desired_width(140.0).hint_text("Name"))
+                    .changed();
+                egui::ComboBox::from_id_salt(("webhook_kind", i))
+                    .selected_text(sink.kind.label())
+                    .width(80.0)
+                    .show_ui(ui, |ui| {
+                        for kind in WebhookKind::ALL {
+                            if ui.selectable_label(sink.kind == kind, kind.label()).clicked() {
+                                sink.kind = kind;
+                                changed = true;
+                            }
+                        }
+                    });
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if small_button(ui, "Remove", Theme::RED) {
+                        remove = Some(i);
+                    }
+                    if small_button(ui, "Test", Theme::ACCENT_PRIMARY) {
+                        test = Some(i);
+                    }
+                });
+            });
+
+            changed |= ui
+                .add(egui::TextEdit::singleline(&mut sink.url).desired_width(f32::INFINITY).hint_text("https://hooks.slack.com/services/..."))
+                .changed();
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(sink.template.get_or_insert_with(String::new))
+                        .desired_width(f32::INFINITY)
+                        .hint_text("{title}: {message}  ({provider}, {event}, {percent}, {time})")
+                )
+                .changed();
+            if sink.template.as_deref() == Some("") {
+                sink.template = None;
+            }
+
+            ui.horizontal(|ui| {
+                let providers_label = if sink.providers.is_empty() {
+                    "All providers".to_string()
+                } else {
+                    format!("{} providers", sink.providers.len())
+                };
+                ui.menu_button(providers_label, |ui| {
+                    for id in ProviderId::all() {
+                        let mut on = sink.providers.iter().any(|p| p == id.cli_name());
+                        if ui.checkbox(&mut on, id.display_name()).changed() {
+                            sink.providers.retain(|p| p != id.cli_name());
+                            if on {
+                                sink.providers.push(id.cli_name().to_string());
+                            }
+                            changed = true;
+                        }
+                    }
+                });
+
+                let events_label = if sink.events.is_empty() {
+                    "All events".to_string()
+                } else {
+                    format!("{} events", sink.events.len())
+                };
+                ui.menu_button(events_label, |ui| {
+                    for kind in NotificationType::ALL {
+                        let mut on = sink.events.iter().any(|e| e == kind.key());
+                        if ui.checkbox(&mut on, kind.title()).changed() {
+                            sink.events.retain(|e| e != kind.key());
+                            if on {
+                                sink.events.push(kind.key().to_string());
+                            }
+                            changed = true;
+                        }
+                    }
+                });
+            });
+
+            if let Some((_, ref msg, is_error)) = status_msg.as_ref().filter(|(idx, _, _)| *idx == i) {
+                let color = if *is_error { Theme::RED } else { Theme::GREEN };
+                ui.label(RichText::new(msg).size(FontSize::SM).color(color));
+            }
+        }
+
+        setting_divider(ui);
+        if small_button(ui, "Add webhook", Theme::ACCENT_PRIMARY) {
+            sinks.push(WebhookSink {
+                name: String::new(),
+                kind: WebhookKind::Slack,
+                url: String::new(),
+                enabled: true,
+                template: None,
+                providers: Vec::new(),
+                events: Vec::new(),
+            });
+            changed = true;
+        }
+    });
+
+    if let Some(i) = test {
+        let shared = Arc::clone(shared_state);
+        let ctx = ui.ctx().clone();
+        if let Ok(mut state) = shared_state.lock() {
+            state.webhook_status_msg = Some((i, "Sending...".to_string(), false));
+        }
+        webhooks::send_test(sinks[i].clone(), move |result| {
+            if let Ok(mut state) = shared.lock() {
+                state.webhook_status_msg = Some(match result {
+                    Ok(()) => (i, "Test message sent".to_string(), false),
+                    Err(e) => (i, format!("Test failed: {}", e), true),
+                });
+            }
+            ctx.request_repaint();
+        });
+    }
+    if let Some(i) = remove {
+        sinks.remove(i);
+        changed = true;
+    }
+    if changed {
+        if let Ok(mut state) = shared_state.lock() {
+            state.settings.webhooks = sinks;
+            state.webhook_status_msg = None;
+            state.settings_changed = true;
+        }
+    }
+}
+
+/// Alert rule editor for the Alerts tab
+fn render_alert_rules(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSharedState>>) {
+    section_header(ui, "Rules");
+
+    let mut rules = if let Ok(state) = shared_state.lock() {
+        state.settings.effective_notification_rules()
+    } else { return };
+    let mut changed = false;
+    let mut remove: Option<usize> = None;
+
+    settings_card(ui, |ui| {
+        ui.label(
+            RichText::new("Each rule alerts once when its condition starts to hold, then waits for its cooldown. With no rules, the General thresholds are used.")
+                .size(FontSize::SM)
+                .color(Theme::TEXT_MUTED)
+        );
+
+        for (i, rule) in rules.iter_mut().enumerate() {
+            setting_divider(ui);
+
+            ui.horizontal(|ui| {
+                changed |= ui.checkbox(&mut rule.enabled, "").changed();
+
+                let provider_label = rule
+                    .provider
+                    .as_deref()
+                    .and_then(ProviderId::from_cli_name)
+                    .map(|id| id.display_name())
+                    .unwrap_or("All providers");
+                egui::ComboBox::from_id_salt(("rule_provider", i))
+                    .selected_text(provider_label)
+                    .width(110.0)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(rule.provider.is_none(), "All providers").clicked() {
+                            rule.provider = None;
+                            changed = true;
+                        }
+                        for id in ProviderId::all() {
+                            let selected = rule.provider.as_deref() == Some(id.cli_name());
+                            if ui.selectable_label(selected, id.display_name()).clicked() {
+                                rule.provider = Some(id.cli_name().to_string());
+                                changed = true;
+                            }
+                        }
+                    });
+
+                egui::ComboBox::from_id_salt(("rule_trigger", i))
+                    .selected_text(rule.trigger.label())
+                    .width(120.0)
+                    .show_ui(ui, |ui| {
+                        for trigger in [
+                            RuleTrigger::Threshold { percent: 80.0 },
+                            RuleTrigger::PaceAhead { percent: 10.0 },
+                            RuleTrigger::ResetSoon { minutes: 15 },
+                            RuleTrigger::ErrorPersisting { minutes: 30 },
+                            RuleTrigger::StatusChange,
+                        ] {
+                            let selected = std::mem::discriminant(&rule.trigger) == std::mem::discriminant(&trigger);
+                            if ui.selectable_label(selected, trigger.label()).clicked() && !selected {
+                                rule.trigger = trigger;
+                                changed = true;
+                            }
+                        }
+                    });
+
+                changed |= match rule.trigger {
+                    RuleTrigger::Threshold { ref mut percent } | RuleTrigger::PaceAhead { ref mut percent } => ui
+                        .add(egui::DragValue::new(percent).range(1.0..=100.0).speed(1.0).suffix("%"))
+                        .changed(),
+                    RuleTrigger::ResetSoon { ref mut minutes } | RuleTrigger::ErrorPersisting { ref mut minutes } => ui
+                        .add(egui::DragValue::new(minutes).range(1..=10_080).speed(1.0).suffix(" min"))
+                        .changed(),
+                    RuleTrigger::StatusChange => false,
+                };
+
+                if rule.trigger.uses_window() {
+                    egui::ComboBox::from_id_salt(("rule_window", i))
+                        .selected_text(rule.window.name())
+                        .width(80.0)
+                        .show_ui(ui, |ui| {
+                            for window in UsageWindow::ALL {
+                                if ui.selectable_label(rule.window == window, window.name()).clicked() {
+                                    rule.window = window;
+                                    changed = true;
+                                }
+                            }
+                        });
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if small_button(ui, "Remove", Theme::RED) {
+                        remove = Some(i);
+                    }
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut rule.cooldown_minutes).range(0..=10_080).speed(1.0).suffix(" min cooldown"))
+                        .changed();
+                });
+            });
+        }
+
+        setting_divider(ui);
+        if small_button(ui, "Add rule", Theme::ACCENT_PRIMARY) {
+            rules.push(NotificationRule::new(RuleTrigger::Threshold { percent: 80.0 }));
+            changed = true;
+        }
+    });
+
+    if let Some(i) = remove {
+        rules.remove(i);
+        changed = true;
+    }
+    if changed {
+        if let Ok(mut state) = shared_state.lock() {
+            state.settings.notification_rules = rules;
+            state.settings_changed = true;
+        }
+    }
+}
+
+/// Half-hour time picker storing "HH:MM"
+fn time_slot_combo(ui: &mut egui::Ui, id: &str, value: &mut String) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(value.as_str())
+        .width(70.0)
+        .show_ui(ui, |ui| {
+            for slot in 0..48 {
+                let time = format!("{:02}:{:02}", slot / 2, (slot % 2) * 30);
+                if ui.selectable_label(*value == time, &time).clicked() {
+                    *value = time;
+                    changed = true;
+                }
+            }
+        });
+    changed
+}
+
 /// Render API Keys tab for viewport
 fn render_api_keys_tab(ui: &mut egui::Ui, shared_state: &Arc<Mutex<PreferencesSharedState>>) {
     section_header(ui, "API Keys");
@@ -0,0 +1,361 @@
+//! Notification Backends
+//!
+//! Where desktop notifications are shown: Windows toasts, the freedesktop
+//! notification service over D-Bus on Linux, or a log that remembers what
+//! was sent, used on other platforms and in tests.
+
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use crate::core::ProviderId;
+
+/// How insistent a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+/// A button on a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    /// Snooze the provider's alerts for an hour
+    Snooze,
+    /// Open the provider's status page
+    OpenStatusPage,
+}
+
+impl NotificationAction {
+    fn key(&self) -> &'static str {
+        match self {
+            NotificationAction::Snooze => "snooze",
+            NotificationAction::OpenStatusPage => "status",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            NotificationAction::Snooze => "Snooze 1 Hour",
+            NotificationAction::OpenStatusPage => "Status Page",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "snooze" => Some(NotificationAction::Snooze),
+            "status" => Some(NotificationAction::OpenStatusPage),
+            _ => None,
+        }
+    }
+}
+
+/// Actions the user picked, sent back to the notification manager
+pub type ActionSender = Sender<(ProviderId, NotificationAction)>;
+
+/// A notification to show on the desktop
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopNotification {
+    pub title: String,
+    pub body: String,
+    pub urgency: Urgency,
+    /// Provider the alert is about; its previous notification is replaced
+    /// where the platform allows
+    pub provider: Option<ProviderId>,
+    pub actions: Vec<NotificationAction>,
+}
+
+impl DesktopNotification {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            urgency: Urgency::Normal,
+            provider: None,
+            actions: Vec::new(),
+        }
+    }
+}
+
+/// Shows desktop notifications
+pub trait NotificationBackend: Send {
+    /// Name for logs
+    fn name(&self) -> &'static str;
+
+    fn show(&self, notification: &DesktopNotification);
+}
+
+/// The platform's backend, falling back to the log. Picked actions are sent
+/// to `actions` when the platform supports them.
+pub fn default_backend(actions: Option<ActionSender>) -> Box<dyn NotificationBackend> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = actions;
+        Box::new(ToastBackend)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        match DbusBackend::connect(actions) {
+            Ok(backend) => Box::new(backend),
+            Err(e) => {
+                tracing::warn!("Desktop notifications unavailable, logging instead: {}", e);
+                Box::new(LogBackend::new())
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = actions;
+        Box::new(LogBackend::new())
+    }
+}
+
+/// Logs notifications and keeps the most recent ones in memory
+#[derive(Debug, Clone, Default)]
+pub struct LogBackend {
+    sent: Arc<Mutex<Vec<DesktopNotification>>>,
+}
+
+impl LogBackend {
+    /// Notifications kept for `sent`
+    const HISTORY: usize = 100;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notifications shown so far, oldest first
+    pub fn sent(&self) -> Vec<DesktopNotification> {
+        self.sent.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+}
+
+impl NotificationBackend for LogBackend {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn show(&self, notification: &DesktopNotification) {
+        tracing::info!("Notification: {} - {}", notification.title, notification.body);
+        if let Ok(mut sent) = self.sent.lock() {
+            if sent.len() >= Self::HISTORY {
+                sent.remove(0);
+            }
+            sent.push(notification.clone());
+        }
+    }
+}
+
+/// Windows toast shown through PowerShell. Toasts for the same provider
+/// share a tag, so a new one replaces the last in Action Center.
+#[cfg(target_os = "windows")]
+pub struct ToastBackend;
+
+#[cfg(target_os = "windows")]
+impl NotificationBackend for ToastBackend {
+    fn name(&self) -> &'static str {
+        "toast"
+    }
+
+    fn show(&self, notification: &DesktopNotification) {
+        use std::os::windows::process::CommandExt;
+        use std::process::Command;
+
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+                .replace('\'', "&apos;")
+        };
+        let duration = if notification.urgency == Urgency::Critical { "long" } else { "short" };
+        let tag = notification.provider.map(|p| p.cli_name()).unwrap_or("codexbar");
+
+        // Literal here-string so `$` in the text isn't expanded
+        let script = format!(
+            r#"
+            [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+            [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null
+
+            $template = @'
+            <toast duration="{}">
+                <visual>
+                    <binding template="ToastText02">
+                        <text id="1">{}</text>
+                        <text id="2">{}</text>
+                    </binding>
+                </visual>
+            </toast>
+'@
+
+            $xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+            $xml.LoadXml($template)
+            $toast = [Windows.UI.Notifications.ToastNotification]::new($xml)
+            $toast.Tag = "{}"
+            $toast.Group = "CodexBar"
+            [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier("CodexBar").Show($toast)
+            "#,
+            duration,
+            escape(&notification.title),
+            escape(&notification.body),
+            tag
+        );
+
+        let _ = Command::new("powershell")
+            .args(["-ExecutionPolicy", "Bypass", "-Command", &script])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .spawn();
+    }
+}
+
+/// freedesktop notification service over the D-Bus session bus
+#[cfg(target_os = "linux")]
+pub struct DbusBackend {
+    proxy: zbus::blocking::Proxy<'static>,
+    /// Whether the server shows action buttons
+    supports_actions: bool,
+    icon: String,
+    /// Last notification id per provider, replaced by the next one
+    ids: Arc<Mutex<std::collections::HashMap<ProviderId, u32>>>,
+}
+
+#[cfg(target_os = "linux")]
+impl DbusBackend {
+    const DESTINATION: &'static str = "org.freedesktop.Notifications";
+    const PATH: &'static str = "/org/freedesktop/Notifications";
+
+    /// Connect to the notification service; with `actions`, listen for
+    /// picked actions on a background thread
+    pub fn connect(actions: Option<ActionSender>) -> zbus::Result<Self> {
+        let connection = zbus::blocking::Connection::session()?;
+        let proxy = zbus::blocking::Proxy::new_owned(connection, Self::DESTINATION, Self::PATH, Self::DESTINATION)?;
+        let capabilities: Vec<String> = proxy.call("GetCapabilities", &())?;
+        let supports_actions = capabilities.iter().any(|c| c == "actions");
+
+        let backend = Self {
+            proxy,
+            supports_actions,
+            icon: app_icon_path().unwrap_or_else(|| "dialog-information".to_string()),
+            ids: Arc::new(Mutex::new(std::collections::HashMap::new())),
+        };
+        if let Some(sender) = actions.filter(|_| supports_actions) {
+            backend.listen(sender)?;
+        }
+        Ok(backend)
+    }
+
+    fn listen(&self, sender: ActionSender) -> zbus::Result<()> {
+        let signals = self.proxy.receive_signal("ActionInvoked")?;
+        let ids = Arc::clone(&self.ids);
+        std::thread::spawn(move || {
+            for message in signals {
+                let Ok((id, key)) = message.body().deserialize::<(u32, String)>() else {
+                    continue;
+                };
+                let provider = ids
+                    .lock()
+                    .ok()
+                    .and_then(|ids| ids.iter().find(|(_, n)| **n == id).map(|(p, _)| *p));
+                if let (Some(provider), Some(action)) = (provider, NotificationAction::from_key(&key)) {
+                    if sender.send((provider, action)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl NotificationBackend for DbusBackend {
+    fn name(&self) -> &'static str {
+        "dbus"
+    }
+
+    fn show(&self, notification: &DesktopNotification) {
+        use zbus::zvariant::Value;
+
+        let replaces_id = notification
+            .provider
+            .and_then(|p| self.ids.lock().ok()?.get(&p).copied())
+            .unwrap_or(0);
+        let actions: Vec<&str> = if self.supports_actions {
+            notification.actions.iter().flat_map(|a| [a.key(), a.label()]).collect()
+        } else {
+            Vec::new()
+        };
+        let urgency: u8 = match notification.urgency {
+            Urgency::Low => 0,
+            Urgency::Normal => 1,
+            Urgency::Critical => 2,
+        };
+        let hints = std::collections::HashMap::from([
+            ("urgency", Value::U8(urgency)),
+            ("desktop-entry", Value::from("codexbar")),
+        ]);
+
+        let result: zbus::Result<u32> = self.proxy.call(
+            "Notify",
+            &(
+                "CodexBar",
+                replaces_id,
+                self.icon.as_str(),
+                notification.title.as_str(),
+                notification.body.as_str(),
+                actions,
+                hints,
+                -1i32,
+            ),
+        );
+        match result {
+            Ok(id) => {
+                if let (Some(provider), Ok(mut ids)) = (notification.provider, self.ids.lock()) {
+                    ids.insert(provider, id);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to show notification: {}", e),
+        }
+    }
+}
+
+/// The app icon written to the cache directory, for servers that take a path
+#[cfg(target_os = "linux")]
+fn app_icon_path() -> Option<String> {
+    const ICON: &[u8] = include_bytes!("../../icons/icon.png");
+
+    let path = dirs::cache_dir()?.join("CodexBar").join("icon.png");
+    if std::fs::metadata(&path).map(|m| m.len() != ICON.len() as u64).unwrap_or(true) {
+        std::fs::create_dir_all(path.parent()?).ok()?;
+        std::fs::write(&path, ICON).ok()?;
+    }
+    Some(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_backend_keeps_history() {
+        let backend = LogBackend::new();
+        let handle = backend.clone();
+        for i in 0..LogBackend::HISTORY + 5 {
+            backend.show(&DesktopNotification::new("Title", format!("body {}", i)));
+        }
+
+        let sent = handle.sent();
+        assert_eq!(sent.len(), LogBackend::HISTORY);
+        assert_eq!(sent[0].body, "body 5");
+    }
+
+    #[test]
+    fn test_action_keys() {
+        for action in [NotificationAction::Snooze, NotificationAction::OpenStatusPage] {
+            assert_eq!(NotificationAction::from_key(action.key()), Some(action));
+        }
+        assert_eq!(NotificationAction::from_key("default"), None);
+    }
+}
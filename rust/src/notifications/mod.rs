@@ -0,0 +1,568 @@
+//! System notifications for CodexBar
+//!
+//! Shows usage alerts on the desktop through a platform `backend`, also
+//! posted to any configured webhooks. What to alert on is decided by `rules`.
+
+#![allow(dead_code)]
+
+pub mod backend;
+pub mod rules;
+pub mod webhooks;
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+
+use chrono::{DateTime, Utc};
+
+use crate::core::{ProviderId, QuotaEvent, SessionQuotaNotifier, UsageWindow};
+use crate::settings::Settings;
+use crate::sound::{play_alert, AlertSound};
+use crate::status::{get_status_page_url, StatusLevel};
+use backend::{default_backend, DesktopNotification, NotificationAction, NotificationBackend, Urgency};
+use rules::{NotificationRule, ProviderObservation, RuleAlert, RuleEngine, RuleTrigger};
+use webhooks::WebhookEvent;
+
+/// Notification types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationType {
+    /// Usage is approaching limit (high threshold)
+    HighUsage,
+    /// Usage is critical (critical threshold)
+    CriticalUsage,
+    /// Usage limit exhausted
+    Exhausted,
+    /// Provider status issue
+    StatusIssue,
+    /// Session quota depleted (at 100% usage)
+    SessionDepleted,
+    /// Session quota restored (back from 100%)
+    SessionRestored,
+    /// Usage running ahead of an even pace
+    PaceAhead,
+    /// A window resets soon
+    ResetSoon,
+    /// Refreshing has kept failing
+    FetchError,
+    /// Status page changed, other than to an outage
+    StatusChange,
+    /// A weekly or model window reset
+    WindowReset,
+    /// Spend crossed a budget alert percentage
+    BudgetAlert,
+    /// Spend went over a budget
+    BudgetExceeded,
+    /// Today's spend is far above the usual
+    CostAnomaly,
+}
+
+impl NotificationType {
+    pub fn title(&self) -> &'static str {
+        match self {
+            NotificationType::HighUsage => "High Usage Warning",
+            NotificationType::CriticalUsage => "Critical Usage Alert",
+            NotificationType::Exhausted => "Usage Limit Reached",
+            NotificationType::StatusIssue => "Provider Status Issue",
+            NotificationType::SessionDepleted => "Session Depleted",
+            NotificationType::SessionRestored => "Session Restored",
+            NotificationType::PaceAhead => "Usage Ahead of Pace",
+            NotificationType::ResetSoon => "Usage Resets Soon",
+            NotificationType::FetchError => "Refresh Failing",
+            NotificationType::StatusChange => "Provider Status Changed",
+            NotificationType::WindowReset => "Usage Window Reset",
+            NotificationType::BudgetAlert => "Budget Alert",
+            NotificationType::BudgetExceeded => "Budget Exceeded",
+            NotificationType::CostAnomaly => "Unusual Spend",
+        }
+    }
+
+    /// Event name used by webhooks and their filters
+    pub fn key(&self) -> &'static str {
+        match self {
+            NotificationType::HighUsage => "high_usage",
+            NotificationType::CriticalUsage => "critical_usage",
+            NotificationType::Exhausted => "exhausted",
+            NotificationType::StatusIssue => "status_issue",
+            NotificationType::SessionDepleted => "session_depleted",
+            NotificationType::SessionRestored => "session_restored",
+            NotificationType::PaceAhead => "pace_ahead",
+            NotificationType::ResetSoon => "reset_soon",
+            NotificationType::FetchError => "fetch_error",
+            NotificationType::StatusChange => "status_change",
+            NotificationType::WindowReset => "window_reset",
+            NotificationType::BudgetAlert => "budget_alert",
+            NotificationType::BudgetExceeded => "budget_exceeded",
+            NotificationType::CostAnomaly => "cost_anomaly",
+        }
+    }
+
+    pub const ALL: [NotificationType; 14] = [
+        NotificationType::HighUsage,
+        NotificationType::CriticalUsage,
+        NotificationType::Exhausted,
+        NotificationType::StatusIssue,
+        NotificationType::SessionDepleted,
+        NotificationType::SessionRestored,
+        NotificationType::PaceAhead,
+        NotificationType::ResetSoon,
+        NotificationType::FetchError,
+        NotificationType::StatusChange,
+        NotificationType::WindowReset,
+        NotificationType::BudgetAlert,
+        NotificationType::BudgetExceeded,
+        NotificationType::CostAnomaly,
+    ];
+
+    fn sound(&self) -> AlertSound {
+        match self {
+            NotificationType::HighUsage => AlertSound::Warning,
+            NotificationType::CriticalUsage => AlertSound::Critical,
+            NotificationType::Exhausted => AlertSound::Error,
+            NotificationType::StatusIssue => AlertSound::Error,
+            NotificationType::SessionDepleted => AlertSound::Error,
+            NotificationType::SessionRestored => AlertSound::Success,
+            NotificationType::PaceAhead => AlertSound::Warning,
+            NotificationType::ResetSoon => AlertSound::Warning,
+            NotificationType::FetchError => AlertSound::Error,
+            NotificationType::StatusChange => AlertSound::Success,
+            NotificationType::WindowReset => AlertSound::Success,
+            NotificationType::BudgetAlert => AlertSound::Warning,
+            NotificationType::BudgetExceeded => AlertSound::Critical,
+            NotificationType::CostAnomaly => AlertSound::Warning,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            NotificationType::HighUsage => "⚠️",
+            NotificationType::CriticalUsage => "🔴",
+            NotificationType::Exhausted => "🚫",
+            NotificationType::StatusIssue => "⚡",
+            NotificationType::SessionDepleted => "🔴",
+            NotificationType::SessionRestored => "✅",
+            NotificationType::PaceAhead => "📈",
+            NotificationType::ResetSoon => "⏰",
+            NotificationType::FetchError => "❗",
+            NotificationType::StatusChange => "ℹ️",
+            NotificationType::WindowReset => "🔄",
+            NotificationType::BudgetAlert => "💰",
+            NotificationType::BudgetExceeded => "💸",
+            NotificationType::CostAnomaly => "📊",
+        }
+    }
+
+    fn urgency(&self) -> Urgency {
+        match self {
+            NotificationType::CriticalUsage
+            | NotificationType::Exhausted
+            | NotificationType::StatusIssue
+            | NotificationType::SessionDepleted
+            | NotificationType::FetchError
+            | NotificationType::BudgetExceeded => Urgency::Critical,
+            NotificationType::HighUsage
+            | NotificationType::PaceAhead
+            | NotificationType::ResetSoon
+            | NotificationType::BudgetAlert
+            | NotificationType::CostAnomaly => Urgency::Normal,
+            NotificationType::SessionRestored | NotificationType::StatusChange | NotificationType::WindowReset => {
+                Urgency::Low
+            }
+        }
+    }
+
+    /// Buttons offered on the notification; snoozing needs a provider
+    fn actions(&self, provider: Option<ProviderId>) -> Vec<NotificationAction> {
+        let Some(provider) = provider else {
+            return Vec::new();
+        };
+        match self {
+            NotificationType::StatusIssue | NotificationType::StatusChange => get_status_page_url(provider.cli_name())
+                .map(|_| vec![NotificationAction::OpenStatusPage, NotificationAction::Snooze])
+                .unwrap_or_else(|| vec![NotificationAction::Snooze]),
+            NotificationType::SessionRestored | NotificationType::WindowReset => Vec::new(),
+            _ => vec![NotificationAction::Snooze],
+        }
+    }
+
+    /// Notification shown for a rule alert
+    fn for_alert(alert: &RuleAlert, settings: &Settings) -> Self {
+        match alert.trigger {
+            RuleTrigger::Threshold { percent } if percent >= 100.0 => NotificationType::Exhausted,
+            RuleTrigger::Threshold { percent } if percent >= settings.critical_usage_threshold => {
+                NotificationType::CriticalUsage
+            }
+            RuleTrigger::Threshold { .. } => NotificationType::HighUsage,
+            RuleTrigger::PaceAhead { .. } => NotificationType::PaceAhead,
+            RuleTrigger::ResetSoon { .. } => NotificationType::ResetSoon,
+            RuleTrigger::ErrorPersisting { .. } => NotificationType::FetchError,
+            RuleTrigger::StatusChange => match alert.status {
+                Some(StatusLevel::Partial | StatusLevel::Major) => NotificationType::StatusIssue,
+                _ => NotificationType::StatusChange,
+            },
+        }
+    }
+}
+
+/// How often timed rules are checked between refreshes
+const TIMED_RULE_INTERVAL_SECS: i64 = 15;
+
+/// Notification manager
+pub struct NotificationManager {
+    /// Rule state: what fired, snoozes, error and status history
+    engine: RuleEngine,
+    /// Window state for session transitions and resets
+    quota: SessionQuotaNotifier,
+    /// Latest refresh per provider, for timed rules; windows are kept from
+    /// the last successful one
+    latest: HashMap<ProviderId, ProviderObservation>,
+    /// When timed rules were last checked
+    last_timed_check: Option<DateTime<Utc>>,
+    /// Where desktop notifications are shown
+    backend: Box<dyn NotificationBackend>,
+    /// Actions picked on notifications, handled on the next tick
+    actions: Receiver<(ProviderId, NotificationAction)>,
+}
+
+impl NotificationManager {
+    /// Manager whose alert state only lives in memory
+    pub fn new() -> Self {
+        Self::with_engine(RuleEngine::in_memory())
+    }
+
+    /// Manager that restores alert state saved by a previous run
+    pub fn load() -> Self {
+        Self::with_engine(RuleEngine::load())
+    }
+
+    fn with_engine(engine: RuleEngine) -> Self {
+        let (sender, actions) = mpsc::channel();
+        let backend = default_backend(Some(sender));
+        tracing::debug!("Using {} notification backend", backend.name());
+        Self::with_backend(engine, backend, actions)
+    }
+
+    fn with_backend(
+        engine: RuleEngine,
+        backend: Box<dyn NotificationBackend>,
+        actions: Receiver<(ProviderId, NotificationAction)>,
+    ) -> Self {
+        Self {
+            engine,
+            quota: SessionQuotaNotifier::new(),
+            latest: HashMap::new(),
+            last_timed_check: None,
+            backend,
+            actions,
+        }
+    }
+
+    /// Evaluate the alert rules against a refresh and send what fires
+    pub fn observe(&mut self, observation: &ProviderObservation, settings: &Settings) {
+        if !Self::is_enabled(settings) {
+            return;
+        }
+
+        let rules = settings.effective_notification_rules();
+        self.send_rule_alerts(&rules, observation, settings, Utc::now());
+
+        let mut latest = observation.clone();
+        if latest.windows.is_empty() {
+            if let Some(previous) = self.latest.get(&observation.provider) {
+                latest.windows = previous.windows.clone();
+            }
+        }
+        self.latest.insert(observation.provider, latest);
+
+        for (kind, window) in &observation.windows {
+            if let Some(event) = self.quota.update(observation.provider, *kind, window) {
+                self.send_quota_event(event, settings);
+            }
+        }
+    }
+
+    /// Silence a provider's alerts until a time
+    pub fn snooze(&mut self, provider: ProviderId, until: DateTime<Utc>) {
+        self.engine.snooze(provider, until);
+    }
+
+    pub fn unsnooze(&mut self, provider: ProviderId) {
+        self.engine.unsnooze(provider);
+    }
+
+    /// End of a provider's snooze, if it is snoozed
+    pub fn snoozed_until(&self, provider: ProviderId) -> Option<DateTime<Utc>> {
+        self.engine.snoozed_until(provider, Utc::now())
+    }
+
+    /// Whether any alert would be shown or posted
+    fn is_enabled(settings: &Settings) -> bool {
+        settings.show_notifications || settings.webhooks.iter().any(|w| w.enabled)
+    }
+
+    fn send_rule_alerts(
+        &mut self,
+        rules: &[NotificationRule],
+        observation: &ProviderObservation,
+        settings: &Settings,
+        now: DateTime<Utc>,
+    ) {
+        for alert in self.engine.evaluate(rules, &settings.quiet_hours, observation, now) {
+            let notif_type = NotificationType::for_alert(&alert, settings);
+            self.emit(Some(alert.provider), notif_type, &alert.message, alert.used_percent, settings);
+        }
+    }
+
+    /// Check timed rules (reset reminders) against the latest refreshes and
+    /// send reset notices that are due. Call this on a clock tick so they
+    /// arrive on time rather than at the next refresh.
+    pub fn tick(&mut self, settings: &Settings) {
+        self.tick_at(settings, Utc::now());
+    }
+
+    fn tick_at(&mut self, settings: &Settings, now: DateTime<Utc>) {
+        while let Ok((provider, action)) = self.actions.try_recv() {
+            match action {
+                NotificationAction::Snooze => self.snooze(provider, now + chrono::Duration::hours(1)),
+                NotificationAction::OpenStatusPage => {
+                    if let Some(url) = get_status_page_url(provider.cli_name()) {
+                        let _ = open::that(url);
+                    }
+                }
+            }
+        }
+
+        if !Self::is_enabled(settings) {
+            return;
+        }
+
+        if self
+            .last_timed_check
+            .is_none_or(|at| now - at >= chrono::Duration::seconds(TIMED_RULE_INTERVAL_SECS))
+        {
+            self.last_timed_check = Some(now);
+            let rules: Vec<_> = settings
+                .effective_notification_rules()
+                .into_iter()
+                .filter(|r| r.trigger.is_timed())
+                .collect();
+            if !rules.is_empty() {
+                let observations: Vec<ProviderObservation> = self.latest.values().cloned().collect();
+                for observation in &observations {
+                    self.send_rule_alerts(&rules, observation, settings, now);
+                }
+            }
+        }
+
+        for event in self.quota.due(now) {
+            self.send_quota_event(event, settings);
+        }
+    }
+
+    /// Announce a session or reset event unless the provider is silenced
+    fn send_quota_event(&self, event: QuotaEvent, settings: &Settings) {
+        let (provider, notif_type, body, used_percent) = match event {
+            QuotaEvent::Depleted { provider, used_percent } => {
+                // An exhausted rule alert already covers this
+                if self.engine.threshold_active(provider, UsageWindow::Session, 99.99) {
+                    return;
+                }
+                let body = format!(
+                    "{} session depleted. 0% left. Will notify when available again.",
+                    provider.display_name()
+                );
+                (provider, NotificationType::SessionDepleted, body, used_percent)
+            }
+            QuotaEvent::Restored { provider, used_percent } => {
+                let body = format!(
+                    "{} session restored. Session quota is available again.",
+                    provider.display_name()
+                );
+                (provider, NotificationType::SessionRestored, body, used_percent)
+            }
+            QuotaEvent::Reset { provider, window, used_percent } => {
+                if !settings.notify_window_resets {
+                    return;
+                }
+                let body = format!(
+                    "{} {} window has reset ({:.0}% was used)",
+                    provider.display_name(),
+                    window.name(),
+                    used_percent
+                );
+                (provider, NotificationType::WindowReset, body, used_percent)
+            }
+        };
+
+        if !self.engine.is_silenced(provider, &settings.quiet_hours, Utc::now()) {
+            self.emit(Some(provider), notif_type, &body, Some(used_percent), settings);
+        }
+    }
+
+    /// Send an alert once per `key`, remembered across restarts. Alerts held
+    /// back by quiet hours or a snooze are sent on a later call.
+    pub fn notify_once(
+        &mut self,
+        key: &str,
+        provider: Option<ProviderId>,
+        notif_type: NotificationType,
+        body: &str,
+        settings: &Settings,
+    ) {
+        if !Self::is_enabled(settings) {
+            return;
+        }
+        let now = Utc::now();
+        let silenced = match provider {
+            Some(provider) => self.engine.is_silenced(provider, &settings.quiet_hours, now),
+            None => settings.quiet_hours.contains(now.with_timezone(&chrono::Local).time()),
+        };
+        if !silenced && self.engine.mark_sent_once(key, now) {
+            self.emit(provider, notif_type, body, None, settings);
+        }
+    }
+
+    /// Show the notification with its sound and post the event to webhooks
+    fn emit(
+        &self,
+        provider: Option<ProviderId>,
+        notif_type: NotificationType,
+        body: &str,
+        used_percent: Option<f64>,
+        settings: &Settings,
+    ) {
+        if settings.show_notifications {
+            self.backend.show(&DesktopNotification {
+                title: notif_type.title().to_string(),
+                body: body.to_string(),
+                urgency: notif_type.urgency(),
+                provider,
+                actions: notif_type.actions(provider),
+            });
+            play_alert(notif_type.sound(), settings);
+        }
+        webhooks::dispatch(&settings.webhooks, WebhookEvent::new(provider, notif_type, body, used_percent));
+    }
+}
+
+impl Default for NotificationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Simple notification function for one-off notifications
+pub fn show_notification(title: &str, body: &str) {
+    default_backend(None).show(&DesktopNotification::new(title, body));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{RateWindow, UsageSnapshot};
+    use backend::LogBackend;
+
+    fn manager() -> (NotificationManager, LogBackend, mpsc::Sender<(ProviderId, NotificationAction)>) {
+        let log = LogBackend::new();
+        let (sender, actions) = mpsc::channel();
+        let manager = NotificationManager::with_backend(RuleEngine::in_memory(), Box::new(log.clone()), actions);
+        (manager, log, sender)
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            sound_enabled: false,
+            ..Settings::default()
+        }
+    }
+
+    fn usage(used: f64) -> ProviderObservation {
+        ProviderObservation::from_usage(ProviderId::Claude, &UsageSnapshot::new(RateWindow::new(used)))
+    }
+
+    #[test]
+    fn test_alerts_reach_backend() {
+        let (mut manager, log, _) = manager();
+        let settings = settings();
+
+        manager.observe(&usage(40.0), &settings);
+        assert!(log.sent().is_empty());
+
+        manager.observe(&usage(95.0), &settings);
+        // Exhausted covers the session depleted notice
+        manager.observe(&usage(100.0), &settings);
+        manager.observe(&usage(20.0), &settings);
+
+        let sent = log.sent();
+        let titles: Vec<&str> = sent.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, ["Critical Usage Alert", "Usage Limit Reached", "Session Restored"]);
+        assert_eq!(sent[1].urgency, Urgency::Critical);
+        assert_eq!(sent[1].provider, Some(ProviderId::Claude));
+        assert_eq!(sent[1].actions, vec![NotificationAction::Snooze]);
+        assert!(sent[2].actions.is_empty());
+    }
+
+    #[test]
+    fn test_backend_skipped_when_toasts_off() {
+        let (mut manager, log, _) = manager();
+        let mut settings = settings();
+        settings.show_notifications = false;
+
+        manager.observe(&usage(100.0), &settings);
+        assert!(log.sent().is_empty());
+    }
+
+    #[test]
+    fn test_snooze_action() {
+        let (mut manager, log, sender) = manager();
+        let settings = settings();
+
+        sender.send((ProviderId::Claude, NotificationAction::Snooze)).unwrap();
+        manager.tick(&settings);
+        assert!(manager.snoozed_until(ProviderId::Claude).is_some());
+
+        manager.observe(&usage(100.0), &settings);
+        assert!(log.sent().is_empty());
+    }
+
+    #[test]
+    fn test_reset_reminder_from_tick() {
+        let (mut manager, log, _) = manager();
+        let mut settings = settings();
+        settings.notification_rules = vec![NotificationRule::new(RuleTrigger::ResetSoon { minutes: 15 })];
+
+        let now = Utc::now();
+        let resets_at = now + chrono::Duration::minutes(40);
+        let window = RateWindow::with_details(30.0, None, Some(resets_at), None);
+        manager.observe(&ProviderObservation::from_usage(ProviderId::Claude, &UsageSnapshot::new(window)), &settings);
+        manager.tick_at(&settings, now);
+        assert!(log.sent().is_empty());
+
+        // Due between refreshes, sent once
+        manager.tick_at(&settings, now + chrono::Duration::minutes(30));
+        manager.tick_at(&settings, now + chrono::Duration::minutes(31));
+        let sent = log.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].title, "Usage Resets Soon");
+        assert!(sent[0].body.contains("resets in 10 min"));
+    }
+
+    #[test]
+    fn test_notify_once() {
+        let (mut manager, log, _) = manager();
+        let settings = settings();
+        let key = "budget|Overall monthly|2026-10-01|80";
+
+        manager.notify_once(key, None, NotificationType::BudgetAlert, "Overall monthly budget at 80%", &settings);
+        manager.notify_once(key, None, NotificationType::BudgetAlert, "Overall monthly budget at 80%", &settings);
+        assert_eq!(log.sent().len(), 1);
+        assert!(log.sent()[0].actions.is_empty());
+
+        // Held back while snoozed, sent once the snooze is lifted
+        let key = "anomaly|claude|2026-10-18";
+        manager.snooze(ProviderId::Claude, Utc::now() + chrono::Duration::hours(1));
+        manager.notify_once(key, Some(ProviderId::Claude), NotificationType::CostAnomaly, "Unusual", &settings);
+        assert_eq!(log.sent().len(), 1);
+        manager.unsnooze(ProviderId::Claude);
+        manager.notify_once(key, Some(ProviderId::Claude), NotificationType::CostAnomaly, "Unusual", &settings);
+        assert_eq!(log.sent().len(), 2);
+    }
+}
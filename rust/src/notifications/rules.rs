@@ -0,0 +1,701 @@
+//! Notification Rules
+//!
+//! User-defined alert rules evaluated against each refresh: usage
+//! thresholds, pace, upcoming resets, persisting fetch errors and status
+//! changes. The engine keeps which alerts already fired, snoozes and error
+//! start times on disk so a restart doesn't replay alerts.
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::core::{ProviderId, RateWindow, UsagePace, UsageSnapshot, UsageWindow};
+use crate::status::StatusLevel;
+
+/// What makes a rule fire
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleTrigger {
+    /// Window usage at or above a percentage
+    Threshold { percent: f64 },
+    /// Usage this many points ahead of an even pace through the window
+    PaceAhead { percent: f64 },
+    /// Window resets within this many minutes
+    ResetSoon { minutes: u32 },
+    /// Fetching has failed for this many minutes
+    ErrorPersisting { minutes: u32 },
+    /// Status page level changed
+    StatusChange,
+}
+
+impl RuleTrigger {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuleTrigger::Threshold { .. } => "Usage above",
+            RuleTrigger::PaceAhead { .. } => "Ahead of pace by",
+            RuleTrigger::ResetSoon { .. } => "Resets within",
+            RuleTrigger::ErrorPersisting { .. } => "Failing for",
+            RuleTrigger::StatusChange => "Status changes",
+        }
+    }
+
+    /// Whether the condition can start to hold between refreshes, so the
+    /// rule is also checked on a clock tick
+    pub fn is_timed(&self) -> bool {
+        matches!(self, RuleTrigger::ResetSoon { .. })
+    }
+
+    /// Whether the trigger looks at a usage window
+    pub fn uses_window(&self) -> bool {
+        matches!(
+            self,
+            RuleTrigger::Threshold { .. } | RuleTrigger::PaceAhead { .. } | RuleTrigger::ResetSoon { .. }
+        )
+    }
+
+    fn key(&self) -> String {
+        match self {
+            RuleTrigger::Threshold { percent } => format!("threshold:{}", percent),
+            RuleTrigger::PaceAhead { percent } => format!("pace:{}", percent),
+            RuleTrigger::ResetSoon { minutes } => format!("reset:{}", minutes),
+            RuleTrigger::ErrorPersisting { minutes } => format!("error:{}", minutes),
+            RuleTrigger::StatusChange => "status".to_string(),
+        }
+    }
+}
+
+/// A user-defined alert rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Provider CLI name; None applies to every provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Window for usage triggers
+    #[serde(default = "default_window")]
+    pub window: UsageWindow,
+    #[serde(flatten)]
+    pub trigger: RuleTrigger,
+    /// Minimum time between two alerts from this rule for a provider
+    #[serde(default = "default_cooldown_minutes")]
+    pub cooldown_minutes: u32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_window() -> UsageWindow {
+    UsageWindow::Session
+}
+
+fn default_cooldown_minutes() -> u32 {
+    60
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl NotificationRule {
+    pub fn new(trigger: RuleTrigger) -> Self {
+        Self {
+            provider: None,
+            window: default_window(),
+            trigger,
+            cooldown_minutes: default_cooldown_minutes(),
+            enabled: true,
+        }
+    }
+
+    fn applies_to(&self, provider: ProviderId) -> bool {
+        self.enabled && self.provider.as_deref().is_none_or(|p| p.eq_ignore_ascii_case(provider.cli_name()))
+    }
+
+    /// Dedup key for this rule and provider
+    fn key(&self, provider: ProviderId) -> String {
+        let scope = self.provider.as_deref().unwrap_or("*");
+        if self.trigger.uses_window() {
+            format!("{}|{}|{}|{}", provider.cli_name(), scope, self.window.name(), self.trigger.key())
+        } else {
+            format!("{}|{}|{}", provider.cli_name(), scope, self.trigger.key())
+        }
+    }
+}
+
+/// Hours during which no alerts are shown (local time, may wrap midnight)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    #[serde(default)]
+    pub enabled: bool,
+    /// "HH:MM"
+    pub start: String,
+    /// "HH:MM"
+    pub end: String,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+        }
+    }
+}
+
+impl QuietHours {
+    /// Whether a local time falls in the quiet period
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let parse = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+        let (Some(start), Some(end)) = (parse(&self.start), parse(&self.end)) else {
+            return false;
+        };
+        if !self.enabled || start == end {
+            return false;
+        }
+        if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+/// One provider's data from a refresh
+#[derive(Debug, Clone)]
+pub struct ProviderObservation {
+    pub provider: ProviderId,
+    pub windows: Vec<(UsageWindow, RateWindow)>,
+    /// Fetch error, if the refresh failed
+    pub error: Option<String>,
+    pub status: Option<StatusLevel>,
+}
+
+impl ProviderObservation {
+    pub fn from_usage(provider: ProviderId, usage: &UsageSnapshot) -> Self {
+        Self {
+            provider,
+            windows: UsageWindow::ALL
+                .into_iter()
+                .filter_map(|kind| usage.window(kind).map(|w| (kind, w.clone())))
+                .collect(),
+            error: None,
+            status: None,
+        }
+    }
+
+    pub fn from_error(provider: ProviderId, error: impl Into<String>) -> Self {
+        Self {
+            provider,
+            windows: Vec::new(),
+            error: Some(error.into()),
+            status: None,
+        }
+    }
+
+    pub fn window(&self, kind: UsageWindow) -> Option<&RateWindow> {
+        self.windows.iter().find(|(k, _)| *k == kind).map(|(_, w)| w)
+    }
+}
+
+/// An alert a rule produced
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleAlert {
+    pub provider: ProviderId,
+    pub trigger: RuleTrigger,
+    pub window: Option<UsageWindow>,
+    pub message: String,
+    pub used_percent: Option<f64>,
+    /// New status for status changes
+    pub status: Option<StatusLevel>,
+}
+
+/// Persisted engine state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationState {
+    /// Rule keys whose condition still holds since they fired
+    #[serde(default)]
+    active: HashSet<String>,
+    /// When each rule key last fired
+    #[serde(default)]
+    last_fired: HashMap<String, DateTime<Utc>>,
+    /// Providers snoozed until a time
+    #[serde(default)]
+    snoozed: HashMap<String, DateTime<Utc>>,
+    /// When each provider's current run of fetch errors began
+    #[serde(default)]
+    error_since: HashMap<String, DateTime<Utc>>,
+    /// Last known status per provider
+    #[serde(default)]
+    last_status: HashMap<String, StatusLevel>,
+    /// One-off alerts (budgets, unusual spend) already sent, by key
+    #[serde(default)]
+    sent_once: HashMap<String, DateTime<Utc>>,
+}
+
+/// Evaluates rules and remembers what already fired
+pub struct RuleEngine {
+    state: NotificationState,
+    /// Where state is saved; None keeps it in memory
+    path: Option<PathBuf>,
+}
+
+impl RuleEngine {
+    const FILENAME: &'static str = "notification-state.json";
+
+    /// How long one-off alert keys are remembered
+    const SENT_ONCE_DAYS: i64 = 45;
+
+    /// Engine whose state doesn't outlive the process
+    pub fn in_memory() -> Self {
+        Self {
+            state: NotificationState::default(),
+            path: None,
+        }
+    }
+
+    /// Engine backed by the state file in the data directory
+    pub fn load() -> Self {
+        let path = dirs::data_local_dir().map(|d| d.join("CodexBar").join(Self::FILENAME));
+        let state = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self { state, path }
+    }
+
+    fn save(&self) {
+        let Some(ref path) = self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&self.state) {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json) {
+                    tracing::warn!("Failed to save notification state: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize notification state: {}", e),
+        }
+    }
+
+    /// Silence a provider's alerts until a time
+    pub fn snooze(&mut self, provider: ProviderId, until: DateTime<Utc>) {
+        self.state.snoozed.insert(provider.cli_name().to_string(), until);
+        self.save();
+    }
+
+    pub fn unsnooze(&mut self, provider: ProviderId) {
+        if self.state.snoozed.remove(provider.cli_name()).is_some() {
+            self.save();
+        }
+    }
+
+    /// End of a provider's snooze, if it is snoozed now
+    pub fn snoozed_until(&self, provider: ProviderId, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.state.snoozed.get(provider.cli_name()).copied().filter(|until| *until > now)
+    }
+
+    /// Whether a provider's alerts are held back by a snooze or quiet hours
+    pub fn is_silenced(&self, provider: ProviderId, quiet_hours: &QuietHours, now: DateTime<Utc>) -> bool {
+        self.snoozed_until(provider, now).is_some() || quiet_hours.contains(now.with_timezone(&Local).time())
+    }
+
+    /// Record a one-off alert; false if its key was already sent
+    pub fn mark_sent_once(&mut self, key: &str, now: DateTime<Utc>) -> bool {
+        if self.state.sent_once.contains_key(key) {
+            return false;
+        }
+        let cutoff = now - chrono::Duration::days(Self::SENT_ONCE_DAYS);
+        self.state.sent_once.retain(|_, at| *at > cutoff);
+        self.state.sent_once.insert(key.to_string(), now);
+        self.save();
+        true
+    }
+
+    /// Whether a threshold rule at or above `percent` on a window has fired
+    /// for a provider and still holds
+    pub fn threshold_active(&self, provider: ProviderId, window: UsageWindow, percent: f64) -> bool {
+        let prefix = format!("{}|", provider.cli_name());
+        let marker = format!("|{}|threshold:", window.name());
+        self.state.active.iter().any(|key| {
+            key.starts_with(&prefix)
+                && key
+                    .split_once(&marker)
+                    .and_then(|(_, value)| value.parse::<f64>().ok())
+                    .is_some_and(|value| value >= percent)
+        })
+    }
+
+    /// Evaluate rules against a refresh and return the alerts to show.
+    /// Conditions that hold during quiet hours or a snooze fire afterwards
+    /// if they still hold; status changes in that time are dropped.
+    pub fn evaluate(
+        &mut self,
+        rules: &[NotificationRule],
+        quiet_hours: &QuietHours,
+        observation: &ProviderObservation,
+        now: DateTime<Utc>,
+    ) -> Vec<RuleAlert> {
+        let before = self.state.clone();
+        let provider = observation.provider;
+        let name = provider.cli_name().to_string();
+
+        self.state.snoozed.retain(|_, until| *until > now);
+        let silenced = self.is_silenced(provider, quiet_hours, now);
+
+        let error_since = match observation.error {
+            Some(_) => Some(*self.state.error_since.entry(name.clone()).or_insert(now)),
+            None => {
+                self.state.error_since.remove(&name);
+                None
+            }
+        };
+        let previous_status = observation
+            .status
+            .filter(|s| *s != StatusLevel::Unknown)
+            .and_then(|status| self.state.last_status.insert(name.clone(), status))
+            .filter(|previous| Some(*previous) != observation.status);
+
+        let mut alerts = Vec::new();
+        for rule in rules.iter().filter(|r| r.applies_to(provider)) {
+            let key = rule.key(provider);
+            let Some(candidate) = self.check(rule, observation, error_since, previous_status, now) else {
+                // Condition unknown this time (e.g. the fetch failed): keep the state
+                continue;
+            };
+            let Some(alert) = candidate else {
+                self.state.active.remove(&key);
+                continue;
+            };
+
+            let is_event = rule.trigger == RuleTrigger::StatusChange;
+            if !is_event && self.state.active.contains(&key) {
+                continue;
+            }
+            let cooling_down = self
+                .state
+                .last_fired
+                .get(&key)
+                .is_some_and(|at| now - *at < chrono::Duration::minutes(i64::from(rule.cooldown_minutes)));
+            if silenced || cooling_down {
+                continue;
+            }
+
+            if !is_event {
+                self.state.active.insert(key.clone());
+            }
+            self.state.last_fired.insert(key, now);
+            alerts.push(alert);
+        }
+
+        // Crossing several thresholds of a window at once only reports the highest
+        let thresholds: Vec<(Option<UsageWindow>, f64)> = alerts
+            .iter()
+            .filter_map(|a| match a.trigger {
+                RuleTrigger::Threshold { percent } => Some((a.window, percent)),
+                _ => None,
+            })
+            .collect();
+        alerts.retain(|a| match a.trigger {
+            RuleTrigger::Threshold { percent } => !thresholds.iter().any(|(w, p)| *w == a.window && *p > percent),
+            _ => true,
+        });
+
+        if self.state != before {
+            self.save();
+        }
+        alerts
+    }
+
+    /// None when the rule can't be judged from this observation, Some(None)
+    /// when its condition doesn't hold
+    fn check(
+        &self,
+        rule: &NotificationRule,
+        observation: &ProviderObservation,
+        error_since: Option<DateTime<Utc>>,
+        previous_status: Option<StatusLevel>,
+        now: DateTime<Utc>,
+    ) -> Option<Option<RuleAlert>> {
+        let provider = observation.provider;
+        let alert = |message: String, used_percent: Option<f64>| RuleAlert {
+            provider,
+            trigger: rule.trigger,
+            window: rule.trigger.uses_window().then_some(rule.window),
+            message,
+            used_percent,
+            status: None,
+        };
+
+        match rule.trigger {
+            RuleTrigger::Threshold { percent } => {
+                let window = observation.window(rule.window)?;
+                Some((window.used_percent >= percent).then(|| {
+                    alert(
+                        format!("{} {} usage at {:.0}%", provider.display_name(), rule.window.name(), window.used_percent),
+                        Some(window.used_percent),
+                    )
+                }))
+            }
+            RuleTrigger::PaceAhead { percent } => {
+                let window = observation.window(rule.window)?;
+                let default_minutes = if rule.window == UsageWindow::Session { 300 } else { 10080 };
+                let pace = UsagePace::weekly(window, Some(now), default_minutes);
+                Some(pace.filter(|p| p.delta_percent >= percent).map(|p| {
+                    alert(
+                        format!(
+                            "{} {} usage at {:.0}%, {:.0}% ahead of pace",
+                            provider.display_name(),
+                            rule.window.name(),
+                            p.actual_used_percent,
+                            p.delta_percent
+                        ),
+                        Some(window.used_percent),
+                    )
+                }))
+            }
+            RuleTrigger::ResetSoon { minutes } => {
+                let window = observation.window(rule.window)?;
+                let left = window.resets_at.map(|at| at - now);
+                Some(
+                    left.filter(|left| *left > chrono::Duration::zero() && *left <= chrono::Duration::minutes(i64::from(minutes)))
+                        .map(|left| {
+                            alert(
+                                format!(
+                                    "{} {} window resets in {} min ({:.0}% used)",
+                                    provider.display_name(),
+                                    rule.window.name(),
+                                    left.num_minutes().max(1),
+                                    window.used_percent
+                                ),
+                                Some(window.used_percent),
+                            )
+                        }),
+                )
+            }
+            RuleTrigger::ErrorPersisting { minutes } => {
+                let failing_for = error_since.map(|since| now - since);
+                Some(
+                    failing_for
+                        .filter(|d| *d >= chrono::Duration::minutes(i64::from(minutes)))
+                        .map(|d| {
+                            let error = observation.error.as_deref().unwrap_or_default();
+                            alert(
+                                format!("{} has failed to refresh for {} min: {}", provider.display_name(), d.num_minutes(), error),
+                                None,
+                            )
+                        }),
+                )
+            }
+            RuleTrigger::StatusChange => {
+                let status = observation.status?;
+                Some(previous_status.map(|previous| RuleAlert {
+                    status: Some(status),
+                    ..alert(
+                        format!(
+                            "{}: {} (was {})",
+                            provider.display_name(),
+                            status.description(),
+                            previous.description()
+                        ),
+                        None,
+                    )
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(used: f64) -> ProviderObservation {
+        ProviderObservation {
+            provider: ProviderId::Claude,
+            windows: vec![(UsageWindow::Session, RateWindow::new(used))],
+            error: None,
+            status: None,
+        }
+    }
+
+    fn quiet_off() -> QuietHours {
+        QuietHours::default()
+    }
+
+    #[test]
+    fn test_threshold_fires_once_until_cleared() {
+        let rules = [NotificationRule {
+            cooldown_minutes: 0,
+            ..NotificationRule::new(RuleTrigger::Threshold { percent: 80.0 })
+        }];
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(50.0), now).is_empty());
+        let alerts = engine.evaluate(&rules, &quiet_off(), &observation(85.0), now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].used_percent, Some(85.0));
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(90.0), now).is_empty());
+
+        // A failed fetch doesn't rearm the rule
+        let failed = ProviderObservation::from_error(ProviderId::Claude, "timeout");
+        assert!(engine.evaluate(&rules, &quiet_off(), &failed, now).is_empty());
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(90.0), now).is_empty());
+
+        assert!(engine.threshold_active(ProviderId::Claude, UsageWindow::Session, 80.0));
+        assert!(!engine.threshold_active(ProviderId::Claude, UsageWindow::Session, 99.99));
+        assert!(!engine.threshold_active(ProviderId::Claude, UsageWindow::Weekly, 80.0));
+
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(10.0), now).is_empty());
+        assert!(!engine.threshold_active(ProviderId::Claude, UsageWindow::Session, 80.0));
+        assert_eq!(engine.evaluate(&rules, &quiet_off(), &observation(81.0), now).len(), 1);
+    }
+
+    #[test]
+    fn test_only_highest_threshold_reported() {
+        let rules = [
+            NotificationRule::new(RuleTrigger::Threshold { percent: 70.0 }),
+            NotificationRule::new(RuleTrigger::Threshold { percent: 90.0 }),
+        ];
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+
+        let alerts = engine.evaluate(&rules, &quiet_off(), &observation(95.0), now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].trigger, RuleTrigger::Threshold { percent: 90.0 });
+        // The lower rule counts as fired, so it stays quiet on the way down
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(80.0), now).is_empty());
+    }
+
+    #[test]
+    fn test_cooldown_and_provider_filter() {
+        let rules = [NotificationRule {
+            provider: Some("claude".to_string()),
+            ..NotificationRule::new(RuleTrigger::Threshold { percent: 80.0 })
+        }];
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+
+        assert_eq!(engine.evaluate(&rules, &quiet_off(), &observation(85.0), now).len(), 1);
+        engine.evaluate(&rules, &quiet_off(), &observation(10.0), now);
+        // Re-crossed inside the 60 minute cooldown
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(85.0), now).is_empty());
+        let later = now + chrono::Duration::minutes(61);
+        assert_eq!(engine.evaluate(&rules, &quiet_off(), &observation(85.0), later).len(), 1);
+
+        let codex = ProviderObservation {
+            provider: ProviderId::Codex,
+            ..observation(99.0)
+        };
+        assert!(engine.evaluate(&rules, &quiet_off(), &codex, now).is_empty());
+    }
+
+    #[test]
+    fn test_snooze_defers_alert() {
+        let rules = [NotificationRule::new(RuleTrigger::Threshold { percent: 80.0 })];
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+
+        engine.snooze(ProviderId::Claude, now + chrono::Duration::hours(1));
+        assert!(engine.snoozed_until(ProviderId::Claude, now).is_some());
+        assert!(engine.evaluate(&rules, &quiet_off(), &observation(95.0), now).is_empty());
+
+        let after = now + chrono::Duration::hours(2);
+        assert!(engine.snoozed_until(ProviderId::Claude, after).is_none());
+        assert_eq!(engine.evaluate(&rules, &quiet_off(), &observation(95.0), after).len(), 1);
+    }
+
+    #[test]
+    fn test_sent_once() {
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+
+        assert!(engine.mark_sent_once("budget|Overall monthly|2026-10-01|80", now));
+        assert!(!engine.mark_sent_once("budget|Overall monthly|2026-10-01|80", now));
+        assert!(engine.mark_sent_once("budget|Overall monthly|2026-10-01|100", now));
+
+        // Old keys are forgotten
+        let later = now + chrono::Duration::days(RuleEngine::SENT_ONCE_DAYS + 1);
+        engine.mark_sent_once("anomaly|claude|2026-12-01", later);
+        assert!(engine.mark_sent_once("budget|Overall monthly|2026-10-01|80", later));
+    }
+
+    #[test]
+    fn test_error_persisting_and_status_change() {
+        let rules = [
+            NotificationRule::new(RuleTrigger::ErrorPersisting { minutes: 30 }),
+            NotificationRule::new(RuleTrigger::StatusChange),
+        ];
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+        let failed = ProviderObservation::from_error(ProviderId::Claude, "HTTP 401");
+
+        assert!(engine.evaluate(&rules, &quiet_off(), &failed, now).is_empty());
+        let alerts = engine.evaluate(&rules, &quiet_off(), &failed, now + chrono::Duration::minutes(31));
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].message.contains("HTTP 401"));
+
+        let mut ok = observation(10.0);
+        ok.status = Some(StatusLevel::Operational);
+        assert!(engine.evaluate(&rules, &quiet_off(), &ok, now).is_empty());
+        ok.status = Some(StatusLevel::Major);
+        let alerts = engine.evaluate(&rules, &quiet_off(), &ok, now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].status, Some(StatusLevel::Major));
+        assert!(engine.evaluate(&rules, &quiet_off(), &ok, now).is_empty());
+    }
+
+    #[test]
+    fn test_reset_soon() {
+        let rules = [NotificationRule::new(RuleTrigger::ResetSoon { minutes: 15 })];
+        let mut engine = RuleEngine::in_memory();
+        let now = Utc::now();
+        let mut obs = observation(70.0);
+
+        obs.windows[0].1.resets_at = Some(now + chrono::Duration::hours(2));
+        assert!(engine.evaluate(&rules, &quiet_off(), &obs, now).is_empty());
+        obs.windows[0].1.resets_at = Some(now + chrono::Duration::minutes(10));
+        let alerts = engine.evaluate(&rules, &quiet_off(), &obs, now);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].message.contains("resets in 10 min"));
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let t = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let mut quiet = QuietHours {
+            enabled: true,
+            ..QuietHours::default()
+        };
+        assert!(quiet.contains(t(23, 0)));
+        assert!(quiet.contains(t(6, 59)));
+        assert!(!quiet.contains(t(7, 0)));
+        assert!(!quiet.contains(t(12, 0)));
+
+        quiet.start = "12:00".to_string();
+        quiet.end = "13:30".to_string();
+        assert!(quiet.contains(t(13, 0)));
+        assert!(!quiet.contains(t(23, 0)));
+
+        quiet.enabled = false;
+        assert!(!quiet.contains(t(13, 0)));
+    }
+
+    #[test]
+    fn test_rule_serde() {
+        let json = r#"{"provider":"codex","window":"weekly","type":"threshold","percent":90}"#;
+        let rule: NotificationRule = serde_json::from_str(json).unwrap();
+        assert_eq!(rule.trigger, RuleTrigger::Threshold { percent: 90.0 });
+        assert_eq!(rule.window, UsageWindow::Weekly);
+        assert_eq!(rule.cooldown_minutes, 60);
+        assert!(rule.enabled);
+
+        let rule: NotificationRule = serde_json::from_str(r#"{"type":"status_change"}"#).unwrap();
+        assert_eq!(rule.trigger, RuleTrigger::StatusChange);
+    }
+}
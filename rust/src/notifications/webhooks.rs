@@ -0,0 +1,354 @@
+//! Webhook Sinks
+//!
+//! Posts usage and status notifications to Slack, Discord or any endpoint
+//! that accepts JSON, so everyone sharing an account sees when it runs out.
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+use super::NotificationType;
+use crate::core::ProviderId;
+
+/// Attempts per event before giving up
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubles after each failure
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const DEFAULT_TEMPLATE: &str = "{title}: {message}";
+
+/// Payload format a sink expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// Slack incoming webhook (`{"text": ...}`)
+    Slack,
+    /// Discord webhook (`{"content": ...}`)
+    Discord,
+    /// The whole event as JSON, plus the rendered `text`
+    #[default]
+    Json,
+}
+
+impl WebhookKind {
+    pub const ALL: [WebhookKind; 3] = [WebhookKind::Slack, WebhookKind::Discord, WebhookKind::Json];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebhookKind::Slack => "Slack",
+            WebhookKind::Discord => "Discord",
+            WebhookKind::Json => "JSON",
+        }
+    }
+}
+
+/// A configured webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookSink {
+    /// Shown in Preferences and logs
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Message text; `{title}`, `{message}`, `{provider}`, `{event}`,
+    /// `{percent}` and `{time}` are filled in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Provider names to post for; empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
+    /// Events to post (e.g. "exhausted", "status_issue"); empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A notification as sent to webhooks
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEvent {
+    /// Event name, see `NotificationType::key`
+    pub event: &'static str,
+    /// Provider CLI name, or "codexbar" for alerts not about one provider
+    pub provider: String,
+    pub provider_name: String,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_percent: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl WebhookEvent {
+    pub fn new(
+        provider: Option<ProviderId>,
+        kind: NotificationType,
+        message: impl Into<String>,
+        used_percent: Option<f64>,
+    ) -> Self {
+        Self {
+            event: kind.key(),
+            provider: provider.map_or("codexbar", |p| p.cli_name()).to_string(),
+            provider_name: provider.map_or("CodexBar", |p| p.display_name()).to_string(),
+            title: kind.title().to_string(),
+            message: message.into(),
+            used_percent,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Message for the Preferences test button
+    pub fn test() -> Self {
+        Self {
+            event: "test",
+            provider: "codexbar".to_string(),
+            provider_name: "CodexBar".to_string(),
+            title: "CodexBar Test".to_string(),
+            message: "Webhook is set up correctly".to_string(),
+            used_percent: None,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Webhook delivery errors
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("request failed: {0}")]
+    Request(String),
+    #[error("server returned HTTP {0}")]
+    Status(u16),
+}
+
+impl WebhookError {
+    /// Network errors, rate limits and server errors are worth retrying
+    fn is_retryable(&self) -> bool {
+        match self {
+            WebhookError::Request(_) => true,
+            WebhookError::Status(status) => *status == 429 || *status >= 500,
+        }
+    }
+}
+
+impl WebhookSink {
+    /// Name for logs and Preferences
+    pub fn display_name(&self) -> String {
+        if !self.name.is_empty() {
+            return self.name.clone();
+        }
+        url::Url::parse(&self.url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| self.kind.label().to_string())
+    }
+
+    /// Whether this sink wants an event
+    pub fn accepts(&self, event: &WebhookEvent) -> bool {
+        self.enabled
+            && !self.url.trim().is_empty()
+            && (self.providers.is_empty() || self.providers.iter().any(|p| p.eq_ignore_ascii_case(&event.provider)))
+            && (self.events.is_empty() || self.events.iter().any(|e| e == event.event))
+    }
+
+    /// Fill in the template
+    pub fn render(&self, event: &WebhookEvent) -> String {
+        let template = self.template.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(DEFAULT_TEMPLATE);
+        let percent = event.used_percent.map(|p| format!("{:.0}%", p)).unwrap_or_default();
+        template
+            .replace("{title}", &event.title)
+            .replace("{message}", &event.message)
+            .replace("{provider}", &event.provider_name)
+            .replace("{event}", event.event)
+            .replace("{percent}", &percent)
+            .replace("{time}", &event.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    /// Request body for this sink's kind
+    pub fn payload(&self, event: &WebhookEvent) -> serde_json::Value {
+        let text = self.render(event);
+        match self.kind {
+            WebhookKind::Slack => serde_json::json!({ "text": text }),
+            WebhookKind::Discord => serde_json::json!({ "content": text }),
+            WebhookKind::Json => {
+                let mut value = serde_json::to_value(event).unwrap_or_default();
+                value["text"] = serde_json::Value::String(text);
+                value
+            }
+        }
+    }
+
+    /// Post an event, retrying transient failures with backoff
+    pub async fn deliver(&self, client: &reqwest::Client, event: &WebhookEvent) -> Result<(), WebhookError> {
+        let payload = self.payload(event);
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 1;
+
+        loop {
+            let error = match client.post(&self.url).json(&payload).send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) => WebhookError::Status(resp.status().as_u16()),
+                Err(e) => request_error(e),
+            };
+            if attempt >= MAX_ATTEMPTS || !error.is_retryable() {
+                return Err(error);
+            }
+
+            tracing::debug!("Webhook {} attempt {} failed: {}", self.display_name(), attempt, error);
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+/// Request failure without the URL, which holds the webhook's secret token
+fn request_error(e: reqwest::Error) -> WebhookError {
+    WebhookError::Request(e.without_url().to_string())
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("CodexBar")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+/// Run webhook deliveries on a background thread with its own runtime
+fn spawn_delivery<F>(deliver: impl FnOnce(reqwest::Client) -> F + Send + 'static)
+where
+    F: std::future::Future<Output = ()>,
+{
+    std::thread::spawn(move || {
+        match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(rt) => rt.block_on(deliver(client())),
+            Err(e) => tracing::error!("Failed to create runtime for webhooks: {}", e),
+        }
+    });
+}
+
+/// Post an event to every sink that wants it, in the background
+pub fn dispatch(sinks: &[WebhookSink], event: WebhookEvent) {
+    let sinks: Vec<WebhookSink> = sinks.iter().filter(|s| s.accepts(&event)).cloned().collect();
+    if sinks.is_empty() {
+        return;
+    }
+
+    spawn_delivery(move |client| async move {
+        for sink in &sinks {
+            if let Err(e) = sink.deliver(&client, &event).await {
+                tracing::warn!("Webhook {} failed for {}: {}", sink.display_name(), event.event, e);
+            }
+        }
+    });
+}
+
+/// Send a test message to one sink, ignoring its filters, and report the
+/// outcome from the background thread
+pub fn send_test(sink: WebhookSink, done: impl FnOnce(Result<(), WebhookError>) + Send + 'static) {
+    spawn_delivery(move |client| async move {
+        done(sink.deliver(&client, &WebhookEvent::test()).await);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(kind: WebhookKind) -> WebhookSink {
+        WebhookSink {
+            name: String::new(),
+            kind,
+            url: "https://hooks.slack.com/services/T000/B000/XXXX".to_string(),
+            enabled: true,
+            template: None,
+            providers: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    fn event() -> WebhookEvent {
+        WebhookEvent::new(Some(ProviderId::Claude), NotificationType::Exhausted, "Claude usage limit exhausted (100%)", Some(100.0))
+    }
+
+    #[test]
+    fn test_payloads() {
+        let event = event();
+        assert_eq!(
+            sink(WebhookKind::Slack).payload(&event),
+            serde_json::json!({ "text": "Usage Limit Reached: Claude usage limit exhausted (100%)" })
+        );
+        assert!(sink(WebhookKind::Discord).payload(&event)["content"].is_string());
+
+        let json = sink(WebhookKind::Json).payload(&event);
+        assert_eq!(json["event"], "exhausted");
+        assert_eq!(json["provider"], "claude");
+        assert_eq!(json["used_percent"], 100.0);
+        assert!(json["text"].is_string());
+    }
+
+    #[test]
+    fn test_template() {
+        let mut sink = sink(WebhookKind::Slack);
+        sink.template = Some(":rotating_light: {provider} {event} at {percent}".to_string());
+        assert_eq!(sink.render(&event()), ":rotating_light: Claude exhausted at 100%");
+    }
+
+    #[test]
+    fn test_filters() {
+        let event = event();
+        let mut sink = sink(WebhookKind::Json);
+        assert!(sink.accepts(&event));
+
+        sink.providers = vec!["codex".to_string()];
+        assert!(!sink.accepts(&event));
+        sink.providers = vec!["Claude".to_string()];
+        assert!(sink.accepts(&event));
+
+        sink.events = vec!["session_restored".to_string()];
+        assert!(!sink.accepts(&event));
+        sink.events.push("exhausted".to_string());
+        assert!(sink.accepts(&event));
+
+        sink.enabled = false;
+        assert!(!sink.accepts(&event));
+    }
+
+    #[test]
+    fn test_retryable() {
+        assert!(WebhookError::Request("connection reset".to_string()).is_retryable());
+        assert!(WebhookError::Status(503).is_retryable());
+        assert!(WebhookError::Status(429).is_retryable());
+        assert!(!WebhookError::Status(404).is_retryable());
+    }
+
+    #[test]
+    fn test_request_error_hides_url() {
+        let error = tokio_test::block_on(async {
+            reqwest::Client::new()
+                .post("http://127.0.0.1:1/services/SECRET")
+                .send()
+                .await
+                .unwrap_err()
+        });
+        assert!(error.to_string().contains("SECRET"));
+        assert!(!request_error(error).to_string().contains("SECRET"));
+    }
+
+    #[test]
+    fn test_display_name() {
+        let mut sink = sink(WebhookKind::Slack);
+        assert_eq!(sink.display_name(), "hooks.slack.com");
+        sink.name = "Team alerts".to_string();
+        assert_eq!(sink.display_name(), "Team alerts");
+    }
+}
@@ -233,6 +233,9 @@ impl Provider for AmpProvider {
             SourceMode::OAuth => {
                 Err(ProviderError::UnsupportedSource(SourceMode::OAuth))
             }
+            SourceMode::Local => {
+                Err(ProviderError::UnsupportedSource(SourceMode::Local))
+            }
         }
     }
 
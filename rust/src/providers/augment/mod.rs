@@ -233,6 +233,9 @@ impl Provider for AugmentProvider {
             SourceMode::OAuth => {
                 Err(ProviderError::UnsupportedSource(SourceMode::OAuth))
             }
+            SourceMode::Local => {
+                Err(ProviderError::UnsupportedSource(SourceMode::Local))
+            }
         }
     }
 
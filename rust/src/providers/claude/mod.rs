@@ -9,9 +9,11 @@ use tokio::process::Command;
 use tokio::io::AsyncWriteExt;
 use regex_lite::Regex;
 
+use chrono::Utc;
+
 use crate::core::{
-    FetchContext, Provider, ProviderError, ProviderFetchResult, ProviderId, ProviderMetadata,
-    RateWindow, SourceMode, UsageSnapshot,
+    CostSnapshot, FetchContext, JsonlScanner, Provider, ProviderError, ProviderFetchResult, ProviderId,
+    ProviderMetadata, RateWindow, SessionBlocks, SourceMode, UsageSnapshot,
 };
 
 pub use web_api::ClaudeWebApiFetcher;
@@ -64,7 +66,8 @@ impl Provider for ClaudeProvider {
     async fn fetch_usage(&self, ctx: &FetchContext) -> Result<ProviderFetchResult, ProviderError> {
         match ctx.source_mode {
             SourceMode::Auto => {
-                // Try OAuth first, then Web, then CLI
+                // Try OAuth first, then Web, then CLI. Local logs only give a
+                // relative estimate, so they are used only when asked for.
                 if let Ok(result) = self.fetch_via_oauth(ctx).await {
                     return Ok(result);
                 }
@@ -76,6 +79,7 @@ impl Provider for ClaudeProvider {
             SourceMode::OAuth => self.fetch_via_oauth(ctx).await,
             SourceMode::Web => self.fetch_via_web(ctx).await,
             SourceMode::Cli => self.fetch_via_cli(ctx).await,
+            SourceMode::Local => self.fetch_via_local(ctx).await,
         }
     }
 
@@ -85,6 +89,7 @@ impl Provider for ClaudeProvider {
             SourceMode::OAuth,
             SourceMode::Web,
             SourceMode::Cli,
+            SourceMode::Local,
         ]
     }
 
@@ -124,6 +129,28 @@ impl ClaudeProvider {
         self.web_fetcher.fetch_with_cookies().await
     }
 
+    /// Estimate the session window from 5-hour blocks rebuilt from local logs
+    async fn fetch_via_local(&self, _ctx: &FetchContext) -> Result<ProviderFetchResult, ProviderError> {
+        tracing::debug!("Reconstructing Claude session blocks from local logs");
+
+        if JsonlScanner::default_claude_projects_roots().is_empty() {
+            return Err(ProviderError::NotInstalled("No Claude session logs found".to_string()));
+        }
+
+        let now = Utc::now();
+        let blocks = tokio::task::spawn_blocking(move || SessionBlocks::claude_blocks(7, now))
+            .await
+            .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+        let window = SessionBlocks::estimated_session_window(&blocks);
+        let mut result = ProviderFetchResult::new(UsageSnapshot::new(window), "local");
+        if let Some(active) = blocks.last().filter(|b| b.is_active) {
+            result = result.with_cost(CostSnapshot::new(active.cost_usd, "USD", "Session").with_resets_at(active.end));
+        }
+
+        Ok(result)
+    }
+
     async fn fetch_via_cli(&self, _ctx: &FetchContext) -> Result<ProviderFetchResult, ProviderError> {
         tracing::debug!("Attempting CLI probe for Claude");
 
@@ -279,7 +306,7 @@ impl ClaudeProvider {
 }
 
 /// Try to find the claude CLI binary
-fn which_claude() -> Option<std::path::PathBuf> {
+pub fn which_claude() -> Option<std::path::PathBuf> {
     // Check common locations on Windows
     let possible_paths = [
         // In PATH
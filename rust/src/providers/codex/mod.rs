@@ -1,15 +1,17 @@
 //! Codex (OpenAI/ChatGPT) provider implementation
 //!
 //! Fetches usage data from ChatGPT's backend API using OAuth credentials
-//! stored by the Codex CLI in ~/.codex/auth.json
+//! stored by the Codex CLI in ~/.codex/auth.json. When that fails, the last
+//! rate limits the CLI logged to its session files are used instead.
 
 mod api;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::core::{
-    FetchContext, Provider, ProviderId, ProviderError, ProviderFetchResult,
-    ProviderMetadata, SourceMode,
+    CodexLoggedWindow, FetchContext, JsonlScanner, Provider, ProviderId, ProviderError,
+    ProviderFetchResult, ProviderMetadata, RateWindow, SourceMode, UsageSnapshot,
 };
 
 pub use api::CodexApi;
@@ -56,7 +58,36 @@ impl Provider for CodexProvider {
         &self.metadata
     }
 
-    async fn fetch_usage(&self, _ctx: &FetchContext) -> Result<ProviderFetchResult, ProviderError> {
+    async fn fetch_usage(&self, ctx: &FetchContext) -> Result<ProviderFetchResult, ProviderError> {
+        match ctx.source_mode {
+            SourceMode::Local => self.fetch_via_local().await,
+            SourceMode::Auto => match self.fetch_via_oauth().await {
+                Ok(result) => Ok(result),
+                Err(e) => self.fetch_via_local().await.map_err(|_| e),
+            },
+            _ => self.fetch_via_oauth().await,
+        }
+    }
+
+    fn available_sources(&self) -> Vec<SourceMode> {
+        vec![SourceMode::Auto, SourceMode::OAuth, SourceMode::Cli, SourceMode::Local]
+    }
+
+    fn supports_oauth(&self) -> bool {
+        true
+    }
+
+    fn supports_cli(&self) -> bool {
+        true
+    }
+
+    fn detect_version(&self) -> Option<String> {
+        detect_codex_version()
+    }
+}
+
+impl CodexProvider {
+    async fn fetch_via_oauth(&self) -> Result<ProviderFetchResult, ProviderError> {
         tracing::debug!("Fetching Codex usage via OAuth API");
 
         match self.api.fetch_usage().await {
@@ -74,25 +105,65 @@ impl Provider for CodexProvider {
         }
     }
 
-    fn available_sources(&self) -> Vec<SourceMode> {
-        vec![SourceMode::Auto, SourceMode::OAuth, SourceMode::Cli]
-    }
+    /// Read the latest rate limits the Codex CLI wrote to its session logs
+    async fn fetch_via_local(&self) -> Result<ProviderFetchResult, ProviderError> {
+        tracing::debug!("Reading Codex rate limits from local session logs");
+
+        let root = JsonlScanner::default_codex_sessions_root()
+            .filter(|r| r.exists())
+            .ok_or_else(|| ProviderError::NotInstalled("No Codex session logs found".to_string()))?;
+
+        let today = Utc::now().date_naive();
+        let limits = tokio::task::spawn_blocking(move || JsonlScanner::latest_codex_rate_limits(&root, today, 7))
+            .await
+            .map_err(|e| ProviderError::Other(e.to_string()))?
+            .ok_or_else(|| ProviderError::Parse("No rate limits found in recent Codex session logs".to_string()))?;
+
+        let age = format_age(limits.observed_at);
+        let primary = limits
+            .primary
+            .as_ref()
+            .map(|w| logged_window(w, &age))
+            .unwrap_or_else(|| RateWindow::new(0.0));
+
+        let mut usage = UsageSnapshot::new(primary);
+        if let Some(ref secondary) = limits.secondary {
+            usage = usage.with_secondary(logged_window(secondary, &age));
+        }
+        // Surface the age of the data through the snapshot timestamp as well
+        usage.updated_at = limits.observed_at;
 
-    fn supports_oauth(&self) -> bool {
-        true
+        Ok(ProviderFetchResult::new(usage, format!("local, {} old", age)))
     }
+}
 
-    fn supports_cli(&self) -> bool {
-        true
-    }
+/// Convert a logged window; windows whose reset has passed are shown as unused
+fn logged_window(window: &CodexLoggedWindow, age: &str) -> RateWindow {
+    let reset_passed = window.resets_at.is_some_and(|at| at <= Utc::now());
+    let used_percent = if reset_passed { 0.0 } else { window.used_percent };
+
+    RateWindow::with_details(
+        used_percent,
+        window.window_minutes,
+        window.resets_at.filter(|_| !reset_passed),
+        Some(format!("From local logs ({} old)", age)),
+    )
+}
 
-    fn detect_version(&self) -> Option<String> {
-        detect_codex_version()
+/// Compact age like "4m", "3h" or "2d"
+fn format_age(at: DateTime<Utc>) -> String {
+    let secs = (Utc::now() - at).num_seconds().max(0);
+    if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86400)
     }
 }
 
 /// Try to find the codex CLI binary
-fn which_codex() -> Option<std::path::PathBuf> {
+pub fn which_codex() -> Option<std::path::PathBuf> {
     // Check common locations on Windows
     let possible_paths = [
         // In PATH
@@ -253,8 +253,8 @@ impl Provider for FactoryProvider {
                 let usage = self.fetch_via_web().await?;
                 Ok(ProviderFetchResult::new(usage, "web"))
             }
-            SourceMode::Cli | SourceMode::OAuth => {
-                // Droid doesn't have CLI or OAuth support
+            SourceMode::Cli | SourceMode::OAuth | SourceMode::Local => {
+                // Droid doesn't have CLI, OAuth or local log support
                 Err(ProviderError::UnsupportedSource(ctx.source_mode))
             }
         }
@@ -194,7 +194,7 @@ impl Provider for JetBrainsProvider {
         tracing::debug!("Fetching JetBrains AI usage");
 
         match ctx.source_mode {
-            SourceMode::Auto | SourceMode::Cli => {
+            SourceMode::Auto | SourceMode::Cli | SourceMode::Local => {
                 let usage = self.read_local_config().await?;
                 Ok(ProviderFetchResult::new(usage, "local"))
             }
@@ -206,7 +206,7 @@ impl Provider for JetBrainsProvider {
     }
 
     fn available_sources(&self) -> Vec<SourceMode> {
-        vec![SourceMode::Auto, SourceMode::Cli]
+        vec![SourceMode::Auto, SourceMode::Cli, SourceMode::Local]
     }
 
     fn supports_web(&self) -> bool {
@@ -210,6 +210,9 @@ impl Provider for KimiProvider {
             SourceMode::OAuth => {
                 Err(ProviderError::UnsupportedSource(SourceMode::OAuth))
             }
+            SourceMode::Local => {
+                Err(ProviderError::UnsupportedSource(SourceMode::Local))
+            }
         }
     }
 
@@ -183,6 +183,9 @@ impl Provider for KimiK2Provider {
             SourceMode::Cli => {
                 Err(ProviderError::UnsupportedSource(SourceMode::Cli))
             }
+            SourceMode::Local => {
+                Err(ProviderError::UnsupportedSource(SourceMode::Local))
+            }
         }
     }
 
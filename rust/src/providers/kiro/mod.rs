@@ -372,6 +372,9 @@ impl Provider for KiroProvider {
             SourceMode::OAuth => {
                 Err(ProviderError::UnsupportedSource(SourceMode::OAuth))
             }
+            SourceMode::Local => {
+                Err(ProviderError::UnsupportedSource(SourceMode::Local))
+            }
         }
     }
 
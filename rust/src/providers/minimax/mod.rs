@@ -219,6 +219,9 @@ impl Provider for MiniMaxProvider {
             SourceMode::OAuth => {
                 Err(ProviderError::UnsupportedSource(SourceMode::OAuth))
             }
+            SourceMode::Local => {
+                Err(ProviderError::UnsupportedSource(SourceMode::Local))
+            }
         }
     }
 
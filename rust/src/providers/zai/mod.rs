@@ -206,8 +206,8 @@ impl Provider for ZaiProvider {
                 let usage = self.fetch_usage_api(ctx).await?;
                 Ok(ProviderFetchResult::new(usage, "oauth"))
             }
-            SourceMode::Web | SourceMode::Cli => {
-                // z.ai doesn't support web cookies or CLI
+            SourceMode::Web | SourceMode::Cli | SourceMode::Local => {
+                // z.ai doesn't support web cookies, CLI or local logs
                 Err(ProviderError::UnsupportedSource(ctx.source_mode))
             }
         }
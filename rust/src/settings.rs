@@ -12,7 +12,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
-use crate::core::ProviderId;
+use crate::core::{Budget, LogRoot, LogRoots, ProviderId};
+use crate::notifications::rules::{NotificationRule, QuietHours, RuleTrigger};
+use crate::notifications::webhooks::WebhookSink;
 
 /// Update channel for receiving updates
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -198,6 +200,52 @@ pub struct Settings {
     /// Install pending updates when quitting the application
     #[serde(default)]
     pub install_updates_on_quit: bool,
+
+    /// Project aliases for cost attribution: directory path or repository
+    /// name -> project name. Several keys with the same name are merged.
+    #[serde(default)]
+    pub project_aliases: HashMap<String, String>,
+
+    /// Notify when a day's local cost is unusually high
+    #[serde(default)]
+    pub cost_anomaly_notifications: bool,
+
+    /// Spending limits checked against local and provider-reported cost
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+
+    /// Percentages of a budget that trigger a notification
+    #[serde(default = "default_budget_alert_percents")]
+    pub budget_alert_percents: Vec<f64>,
+
+    /// Extra Codex/Claude log directories to scan for cost, e.g. a second
+    /// config dir for work
+    #[serde(default)]
+    pub log_roots: Vec<LogRoot>,
+
+    /// Also scan the Codex/Claude homes inside running WSL distributions
+    #[serde(default = "default_true")]
+    pub discover_wsl_logs: bool,
+
+    /// Slack, Discord or JSON endpoints that also receive notifications
+    #[serde(default)]
+    pub webhooks: Vec<WebhookSink>,
+
+    /// Alert rules; empty uses the usage thresholds and status changes
+    #[serde(default)]
+    pub notification_rules: Vec<NotificationRule>,
+
+    /// Local hours during which alerts are held back
+    #[serde(default)]
+    pub quiet_hours: QuietHours,
+
+    /// Notify when a weekly or model window resets
+    #[serde(default = "default_true")]
+    pub notify_window_resets: bool,
+}
+
+fn default_budget_alert_percents() -> Vec<f64> {
+    vec![80.0, 100.0]
 }
 
 fn default_true() -> bool {
@@ -239,6 +287,16 @@ impl Default for Settings {
             global_shortcut: default_global_shortcut(), // Ctrl+Shift+U by default
             auto_download_updates: true, // Auto-download updates by default
             install_updates_on_quit: false, // Don't auto-install on quit by default
+            project_aliases: HashMap::new(), // Projects named by their git root
+            cost_anomaly_notifications: false, // Opt-in
+            budgets: Vec::new(),
+            budget_alert_percents: default_budget_alert_percents(),
+            log_roots: Vec::new(), // Home directory only
+            discover_wsl_logs: true, // Pick up agents running in WSL
+            webhooks: Vec::new(), // Local notifications only
+            notification_rules: Vec::new(), // Built from the thresholds
+            quiet_hours: QuietHours::default(), // Off
+            notify_window_resets: true,
         }
     }
 }
@@ -249,6 +307,24 @@ impl Settings {
         dirs::config_dir().map(|p| p.join("CodexBar").join("settings.json"))
     }
 
+    /// Codex and Claude log directories to scan for cost
+    pub fn cost_log_roots(&self) -> LogRoots {
+        LogRoots::discover(&self.log_roots, self.discover_wsl_logs)
+    }
+
+    /// Configured alert rules, or session alerts at the high, critical and
+    /// exhausted thresholds plus status changes when none are configured
+    pub fn effective_notification_rules(&self) -> Vec<NotificationRule> {
+        if !self.notification_rules.is_empty() {
+            return self.notification_rules.clone();
+        }
+        [self.high_usage_threshold, self.critical_usage_threshold, 100.0]
+            .into_iter()
+            .map(|percent| NotificationRule::new(RuleTrigger::Threshold { percent }))
+            .chain(std::iter::once(NotificationRule::new(RuleTrigger::StatusChange)))
+            .collect()
+    }
+
     /// Load settings from disk
     pub fn load() -> Self {
         let mut settings = if let Some(path) = Self::settings_path() {
@@ -722,6 +798,22 @@ mod tests {
         assert!(settings.show_notifications);
         assert_eq!(settings.high_usage_threshold, 70.0);
         assert_eq!(settings.critical_usage_threshold, 90.0);
+        assert!(settings.budgets.is_empty());
+        assert_eq!(settings.budget_alert_percents, vec![80.0, 100.0]);
+        assert!(settings.log_roots.is_empty());
+        assert!(settings.discover_wsl_logs);
+    }
+
+    #[test]
+    fn test_effective_notification_rules() {
+        let mut settings = Settings::default();
+        let rules = settings.effective_notification_rules();
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[0].trigger, RuleTrigger::Threshold { percent: 70.0 });
+        assert_eq!(rules[3].trigger, RuleTrigger::StatusChange);
+
+        settings.notification_rules = vec![NotificationRule::new(RuleTrigger::ResetSoon { minutes: 10 })];
+        assert_eq!(settings.effective_notification_rules(), settings.notification_rules);
     }
 
     #[test]
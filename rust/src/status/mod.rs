@@ -45,13 +45,24 @@ impl StatusLevel {
     pub fn from_indicator(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "operational" | "none" | "green" | "ok" => StatusLevel::Operational,
-            "degraded" | "degraded_performance" | "yellow" => StatusLevel::Degraded,
+            "degraded" | "degraded_performance" | "minor" | "yellow" => StatusLevel::Degraded,
             "partial" | "partial_outage" | "orange" => StatusLevel::Partial,
             "major" | "major_outage" | "critical" | "red" => StatusLevel::Major,
             _ => StatusLevel::Unknown,
         }
     }
 
+    /// Indicator name, as accepted by `from_indicator`
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            StatusLevel::Operational => "operational",
+            StatusLevel::Degraded => "degraded",
+            StatusLevel::Partial => "partial",
+            StatusLevel::Major => "major",
+            StatusLevel::Unknown => "unknown",
+        }
+    }
+
     /// Get a human-readable description
     pub fn description(&self) -> &'static str {
         match self {
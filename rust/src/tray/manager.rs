@@ -134,6 +134,10 @@ impl TrayManager {
         // Separator
         menu.append(&PredefinedMenuItem::separator())?;
 
+        // Budgets
+        let budgets_item = MenuItem::with_id("budgets", "Budgets...", true, None);
+        menu.append(&budgets_item)?;
+
         // Settings
         let settings_item = MenuItem::with_id("settings", "Settings...", true, None);
         menu.append(&settings_item)?;
@@ -329,6 +333,8 @@ impl TrayManager {
                 return Some(TrayMenuAction::Refresh);
             } else if id_str == "settings" {
                 return Some(TrayMenuAction::Settings);
+            } else if id_str == "budgets" {
+                return Some(TrayMenuAction::Budgets);
             } else if id_str == "updates" {
                 return Some(TrayMenuAction::CheckForUpdates);
             } else if let Some(provider_name) = id_str.strip_prefix("provider_") {
@@ -345,6 +351,7 @@ pub enum TrayMenuAction {
     Open,
     Refresh,
     Settings,
+    Budgets,
     CheckForUpdates,
     ToggleProvider(String),
     Quit,
@@ -414,6 +421,10 @@ impl MultiTrayManager {
 
         menu.append(&PredefinedMenuItem::separator())?;
 
+        // Budgets
+        let budgets_item = MenuItem::with_id("budgets", "Budgets...", true, None);
+        menu.append(&budgets_item)?;
+
         // Settings
         let settings_item = MenuItem::with_id("settings", "Settings...", true, None);
         menu.append(&settings_item)?;